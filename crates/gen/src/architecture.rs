@@ -0,0 +1,108 @@
+use crate::*;
+use squote::{quote, TokenStream};
+
+/// The `Architecture` flags of `SupportedArchitectureAttribute` along with the matching
+/// values of `target_arch` and `target_pointer_width`
+const ARCHITECTURES: &[(u32, &str, u32)] = &[(1, "x86", 32), (2, "x86_64", 64), (4, "aarch64", 64)];
+
+/// Gets the architectures that a type is limited to by `SupportedArchitectureAttribute` as
+/// a combination of `Architecture` flags
+///
+/// Nested types have the architectures of their enclosing types since the Win32 metadata
+/// only marks the outermost definition of each per-architecture variant.
+pub fn type_architectures(def: &winmd::TypeDef) -> Option<u32> {
+    supported_architectures(def.attributes()).or_else(|| {
+        def.enclosing_type()
+            .and_then(|def| type_architectures(&def))
+    })
+}
+
+/// Gets the architectures that a function is limited to by `SupportedArchitectureAttribute`
+pub fn method_architectures(method: &winmd::MethodDef) -> Option<u32> {
+    supported_architectures(method.attributes())
+}
+
+fn supported_architectures<I: Iterator<Item = winmd::Attribute>>(attributes: I) -> Option<u32> {
+    for attribute in attributes {
+        if attribute.name() == ("Windows.Win32.Interop", "SupportedArchitectureAttribute") {
+            // The `Architecture` enum isn't part of the metadata so its value is usually read
+            // as a plain integer.
+            let architectures = match attribute.args().first().map(|(_, arg)| arg) {
+                Some(winmd::AttributeArg::I32(value)) => *value as u32,
+                Some(winmd::AttributeArg::U32(value)) => *value,
+                Some(winmd::AttributeArg::Enum(_, value)) => match **value {
+                    winmd::AttributeArg::I32(value) => value as u32,
+                    winmd::AttributeArg::U32(value) => value,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            return Some(architectures);
+        }
+    }
+
+    None
+}
+
+/// Generates the `#[cfg]` attribute that limits an item to the given architectures
+pub fn gen_architectures(architectures: Option<u32>) -> TokenStream {
+    let architectures = match architectures {
+        Some(architectures) => architectures,
+        None => return TokenStream::new(),
+    };
+
+    let targets = ARCHITECTURES
+        .iter()
+        .filter(|(flag, _, _)| architectures & flag != 0)
+        .map(|(_, target, _)| quote! { target_arch = #target });
+
+    quote! {
+        #[cfg(any(#(#targets),*))]
+    }
+}
+
+/// Gets the pointer width in bits that the given architectures have in common, if any
+pub fn pointer_width(architectures: Option<u32>) -> Option<u32> {
+    let architectures = architectures?;
+
+    let mut widths = ARCHITECTURES
+        .iter()
+        .filter(|(flag, _, _)| architectures & flag != 0)
+        .map(|(_, _, width)| *width);
+
+    let width = widths.next()?;
+    widths.all(|other| other == width).then_some(width)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn architectures() {
+        let reader = winmd::TypeReader::get();
+        let namespace = "Windows.Win32.Devices.DeviceAndDriverInstallation";
+
+        let variants: Vec<_> = reader
+            .type_variants((namespace, "SP_DEVINFO_DATA"))
+            .map(|t| match t {
+                winmd::Type::TypeDef(def) => type_architectures(&def),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        assert_eq!(variants, [Some(6), Some(1)]);
+
+        assert_eq!(
+            gen_architectures(Some(6)).as_str(),
+            r#"# [ cfg ( any ( target_arch ="x86_64" , target_arch ="aarch64" ) ) ]"#
+        );
+        assert!(gen_architectures(None).as_str().is_empty());
+
+        assert_eq!(pointer_width(Some(6)), Some(64));
+        assert_eq!(pointer_width(Some(1)), Some(32));
+        assert_eq!(pointer_width(Some(3)), None);
+        assert_eq!(pointer_width(None), None);
+    }
+}
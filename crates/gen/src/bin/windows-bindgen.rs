@@ -0,0 +1,135 @@
+//! Generates the bindings for the given types, as `build!` does, and writes them to a file
+//!
+//! ```text
+//! windows-bindgen [--out <file.rs>] [--check] [--manifest <windows.toml>]... [--types <file>]...
+//!                 [<type or option>]...
+//! ```
+//!
+//! Types and options are written as they are given to `build!`, e.g. `windows::foundation::Uri`
+//! or `minimum_os_version(6.3)`. Those in a `--types` file are separated by commas, just like
+//! the arguments of `build!`, and may be commented with `//`. A `--manifest` adds the types and
+//! options of a manifest such as `windows.toml`, as `manifest("windows.toml")` does. Without `--out` the bindings are
+//! written to stdout. With `--check` nothing is written and the tool fails if the output file
+//! isn't up to date, which is useful for bindings that are checked in.
+//!
+//! The bindings are formatted with `rustfmt` if it is available. DLLs given with `dll_path`
+//! aren't copied since there is no build to copy them to.
+//!
+//! The tool is built with the `bindgen` feature of `windows_gen`, which locates errors within
+//! the arguments, e.g. with `cargo install windows_gen --features bindgen`.
+
+use std::io::Write;
+use windows_gen::*;
+
+const HEADER: &str = "// This file was generated by windows-bindgen. Do not edit it by hand.\n\n";
+
+fn main() {
+    if let Err(message) = run() {
+        eprintln!("error: {}", message);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut out = None;
+    let mut check = false;
+    let mut sources = Vec::new();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" | "-o" => out = Some(args.next().ok_or("expected a path after `--out`")?),
+            "--check" => check = true,
+            "--types" | "-t" => {
+                let path = args.next().ok_or("expected a path after `--types`")?;
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|error| format!("could not read `{}`: {}", path, error))?;
+                sources.push(ArgumentSource {
+                    name: Some(path),
+                    text,
+                    is_file: true,
+                });
+            }
+            "--manifest" | "-m" => {
+                let path = args.next().ok_or("expected a path after `--manifest`")?;
+                sources.push(ArgumentSource {
+                    name: None,
+                    text: format!("manifest({:?})", path),
+                    is_file: false,
+                });
+            }
+            "--help" | "-h" => {
+                println!("usage: windows-bindgen [--out <file.rs>] [--check] [--manifest <windows.toml>]... [--types <file>]... [<type or option>]...");
+                return Ok(());
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => sources.push(ArgumentSource {
+                name: Some(format!("`{}`", arg)),
+                text: arg,
+                is_file: false,
+            }),
+        }
+    }
+
+    if check && out.is_none() {
+        return Err("`--check` requires `--out`".to_string());
+    }
+
+    let tokens = BuildLimits::parse_sources(&sources)?
+        .to_tokens_string()
+        .map_err(|error| describe_error(&sources, &error))?;
+
+    let bindings = format!("{}{}", HEADER, format(&tokens));
+
+    let out = match out {
+        Some(out) => out,
+        None => {
+            print!("{}", bindings);
+            return Ok(());
+        }
+    };
+
+    let existing = std::fs::read_to_string(&out).ok();
+
+    if existing.as_deref() == Some(bindings.as_str()) {
+        return Ok(());
+    }
+
+    if check {
+        return Err(format!("`{}` is out of date", out));
+    }
+
+    std::fs::write(&out, bindings).map_err(|error| format!("could not write `{}`: {}", out, error))
+}
+
+/// Formats the source with `rustfmt`, or leaves it as it is if `rustfmt` can't be run
+fn format(source: &str) -> String {
+    let child = std::process::Command::new("rustfmt")
+        .arg("--edition=2018")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(_) => {
+            eprintln!("warning: could not run `rustfmt`, the bindings are not formatted");
+            return source.to_string();
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(source.as_bytes());
+    }
+
+    match child.wait_with_output() {
+        Ok(output) if output.status.success() => {
+            String::from_utf8(output.stdout).unwrap_or_else(|_| source.to_string())
+        }
+        _ => {
+            eprintln!("warning: `rustfmt` failed, the bindings are not formatted");
+            source.to_string()
+        }
+    }
+}
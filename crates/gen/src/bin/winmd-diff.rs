@@ -0,0 +1,69 @@
+//! Reports the differences between two versions of Windows Metadata
+//!
+//! ```text
+//! winmd-diff --old <file.winmd>... --new <file.winmd>... [--breaking] [<namespace or type>]...
+//! ```
+//!
+//! Types defined by files outside of a set are compared by name. The exit code is 2 if any
+//! breaking changes were found, so that metadata updates can be checked in CI.
+
+use windows_gen::*;
+
+const USAGE: &str =
+    "usage: winmd-diff --old <file.winmd>... --new <file.winmd>... [--breaking] [<namespace or type>]...";
+
+fn main() {
+    match run() {
+        Ok(true) => std::process::exit(2),
+        Ok(false) => {}
+        Err(message) => {
+            eprintln!("error: {}", message);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run() -> Result<bool, String> {
+    let mut old = Vec::new();
+    let mut new = Vec::new();
+    let mut breaking_only = false;
+    let mut filters = Vec::new();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--old" => old.push(args.next().ok_or("expected a path after `--old`")?),
+            "--new" => new.push(args.next().ok_or("expected a path after `--new`")?),
+            "--breaking" => breaking_only = true,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(false);
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => filters.push(arg),
+        }
+    }
+
+    if old.is_empty() || new.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let old = load(&old)?;
+    let new = load(&new)?;
+    let changes = diff_metadata(old, new, &filters);
+
+    for change in &changes {
+        if !breaking_only || change.kind == ChangeKind::Breaking {
+            println!("{}", change);
+        }
+    }
+
+    Ok(changes
+        .iter()
+        .any(|change| change.kind == ChangeKind::Breaking))
+}
+
+fn load(paths: &[String]) -> Result<&'static winmd::TypeReader, String> {
+    let reader = winmd::TypeReader::from_paths(paths).map_err(|error| error.to_string())?;
+    Ok(Box::leak(Box::new(reader)))
+}
@@ -0,0 +1,60 @@
+//! Prints the types of Windows Metadata files as `TypeReader` sees them
+//!
+//! ```text
+//! winmd-dump [--json] [--input <file.winmd>]... [<namespace or type>]...
+//! ```
+//!
+//! Without any `--input` files the workspace metadata used by `build!` is read. The types that
+//! the inputs refer to must be defined by the inputs, just as for `build!`. A namespace or
+//! type that matches nothing is an error.
+
+use windows_gen::*;
+
+fn main() {
+    if let Err(message) = run() {
+        eprintln!("error: {}", message);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut json = false;
+    let mut inputs = Vec::new();
+    let mut filters = Vec::new();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--input" | "-i" => inputs.push(args.next().ok_or("expected a path after `--input`")?),
+            "--help" | "-h" => {
+                println!(
+                    "usage: winmd-dump [--json] [--input <file.winmd>]... [<namespace or type>]..."
+                );
+                return Ok(());
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => filters.push(arg),
+        }
+    }
+
+    let reader = if inputs.is_empty() {
+        winmd::TypeReader::try_get().map_err(|error| error.to_string())?
+    } else {
+        let reader = winmd::TypeReader::from_paths(&inputs).map_err(|error| error.to_string())?;
+        Box::leak(Box::new(reader))
+    };
+
+    let dumps = dump_types(reader, &filters)?;
+
+    if json {
+        let dumps: Vec<_> = dumps.iter().map(|dump| dump.to_json()).collect();
+        println!("[{}]", dumps.join(",\n"));
+    } else {
+        for dump in &dumps {
+            println!("{}", dump.to_text());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+//! Compiles an interface definition into a Windows Metadata file
+//!
+//! ```text
+//! winmd-idl [--name <assembly>] [--output <file.winmd>] <file.idl>
+//! ```
+//!
+//! The assembly is named after the input file and written next to it with a `.winmd`
+//! extension unless given otherwise. The result may be read by `build!` like any other
+//! metadata by placing it in the workspace's `.windows/winmd` directory.
+
+use std::path::PathBuf;
+use windows_gen::*;
+
+fn main() {
+    if let Err(message) = run() {
+        eprintln!("error: {}", message);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut name = None;
+    let mut output = None;
+    let mut input = None;
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--name" | "-n" => name = Some(args.next().ok_or("expected a name after `--name`")?),
+            "--output" | "-o" => {
+                output = Some(PathBuf::from(
+                    args.next().ok_or("expected a path after `--output`")?,
+                ))
+            }
+            "--help" | "-h" => {
+                println!("usage: winmd-idl [--name <assembly>] [--output <file.winmd>] <file.idl>");
+                return Ok(());
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ if input.is_some() => return Err(format!("unexpected argument `{}`", arg)),
+            _ => input = Some(PathBuf::from(arg)),
+        }
+    }
+
+    let input = input.ok_or("expected an input file")?;
+    let source = std::fs::read_to_string(&input)
+        .map_err(|error| format!("could not read `{}`: {}", input.display(), error))?;
+
+    let name = match name {
+        Some(name) => name,
+        None => input
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| format!("could not name the assembly after `{}`", input.display()))?
+            .to_string(),
+    };
+
+    let output = output.unwrap_or_else(|| input.with_extension("winmd"));
+
+    let metadata = winmd::idl::compile(&source, &name)
+        .map_err(|error| format!("{}:{}", input.display(), error))?;
+
+    metadata
+        .write_to_path(&output)
+        .map_err(|error| format!("could not write `{}`: {}", output.display(), error))
+}
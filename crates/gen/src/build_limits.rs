@@ -0,0 +1,998 @@
+use crate::*;
+use std::path::PathBuf;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+
+/// The arguments of the `build!` macro, which are also read by the `windows-bindgen` tool
+///
+/// These are paths of the types to generate, or of types to exclude when preceded by `!`,
+/// optionally preceded by options such as
+/// `minimum_contract(Windows.Foundation.UniversalApiContract, 7)`, `winmd_path("...")` or
+/// `manifest("windows.toml")`, which adds the types and options of a [`Manifest`].
+pub struct BuildLimits {
+    pub reader: &'static winmd::TypeReader,
+    pub types: std::collections::BTreeSet<TypesDeclaration>,
+    /// The types that aren't generated unless other types depend on them
+    pub excluded: std::collections::BTreeSet<TypesDeclaration>,
+    /// The types to generate along with their aliases, as namespace, name and alias
+    pub renames: Vec<(&'static str, String, String)>,
+    pub versions: VersionLimits,
+    /// Extra directories of DLLs, laid out like the `.windows` directory
+    pub dll_paths: Vec<PathBuf>,
+    /// The manifests that were read
+    pub manifest_paths: Vec<PathBuf>,
+}
+
+impl BuildLimits {
+    /// Builds the tree of types to generate
+    pub fn to_tree(self) -> syn::Result<TypeTree> {
+        let is_foundation = self.types.is_empty() && self.renames.is_empty();
+
+        // Exclusions only limit the types to generate, so they can't stand on their own.
+        if let Some(limit) = self.excluded.iter().next().filter(|_| is_foundation) {
+            return Err(syn::Error::new(
+                limit.span,
+                "expected types to generate along with the types to exclude",
+            ));
+        }
+
+        let reader = self.reader;
+        let mut limits = TypeLimits::new(reader);
+        limits.versions = self.versions;
+
+        let foundation_namespaces = &[
+            "Windows.Foundation",
+            "Windows.Foundation.Collections",
+            "Windows.Foundation.Diagnostics",
+            "Windows.Foundation.Numerics",
+        ];
+
+        if is_foundation {
+            for namespace in foundation_namespaces {
+                limits
+                    .insert(NamespaceTypes {
+                        namespace,
+                        limit: TypeLimit::All,
+                    })
+                    .unwrap();
+            }
+        }
+
+        for limit in self.types {
+            let span = limit.span;
+            limits.insert(limit.types).map_err(|ns| {
+                syn::Error::new(span, format!("'{}' is not a known namespace", ns))
+            })?;
+        }
+
+        for limit in self.excluded {
+            let types = NamespaceTypes {
+                namespace: limit.types.namespace,
+                limit: TypeLimit::Except(Box::new(limit.types.limit)),
+            };
+
+            let span = limit.span;
+            limits.insert(types).map_err(|ns| {
+                syn::Error::new(span, format!("'{}' is not a known namespace", ns))
+            })?;
+        }
+
+        for (namespace, name, alias) in &self.renames {
+            limits.rename(namespace, name, alias).map_err(|ns| {
+                syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!("'{}' is not a known namespace", ns),
+                )
+            })?;
+        }
+
+        let mut tree = TypeTree::from_limits(reader, &limits);
+
+        if !is_foundation {
+            for namespace in foundation_namespaces {
+                tree.remove(namespace);
+            }
+
+            tree.reexport();
+        }
+
+        // Aliases are only added for the types that are generated, which excludes the types of
+        // the foundation namespaces since they are used from the `windows` crate.
+        for ((namespace, name), alias) in &limits.renames {
+            if !tree.has_alias(namespace, alias) {
+                let name = name.split('`').next().unwrap_or(name);
+
+                let reason = if foundation_namespaces.contains(namespace) {
+                    format!(
+                        "the types of '{}' are used from the `windows` crate",
+                        namespace
+                    )
+                } else {
+                    "it isn't generated".to_string()
+                };
+
+                return Err(syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!(
+                        "'{}.{}' can't be renamed to '{}' since {}",
+                        namespace, name, alias, reason
+                    ),
+                ));
+            }
+        }
+
+        Ok(tree)
+    }
+
+    /// Generates the source of the types
+    pub fn to_tokens_string(self) -> syn::Result<String> {
+        let ts = self
+            .to_tree()?
+            .gen()
+            .fold(squote::TokenStream::new(), |mut accum, n| {
+                accum.combine(&n);
+                accum
+            });
+
+        Ok(ts.into_string())
+    }
+
+    /// Parses the sources as the arguments of a single `build!`
+    ///
+    /// Each source starts on a new line so that the line of an error identifies its source.
+    #[cfg(any(test, feature = "bindgen"))]
+    pub fn parse_sources(sources: &[ArgumentSource]) -> Result<Self, String> {
+        let text = sources
+            .iter()
+            .map(|source| source.text.trim_end().trim_end_matches(','))
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        syn::parse_str(&text).map_err(|error| describe_error(sources, &error))
+    }
+}
+
+/// Where arguments given to the `windows-bindgen` tool were read from, either a `--types` file
+/// or a single argument
+///
+/// Manifests have no name since their errors already name them.
+#[cfg(any(test, feature = "bindgen"))]
+pub struct ArgumentSource {
+    pub name: Option<String>,
+    pub text: String,
+    pub is_file: bool,
+}
+
+/// Describes an error along with the source, and the line of the source, that it refers to
+///
+/// Lines are only known when `proc-macro2` is built with its `span-locations` feature, which the
+/// `bindgen` feature of this crate enables.
+#[cfg(any(test, feature = "bindgen"))]
+pub fn describe_error(sources: &[ArgumentSource], error: &syn::Error) -> String {
+    let start = error.span().start();
+    let mut line = start.line;
+
+    // Spans without a location, such as those of errors about the metadata, start at line 0.
+    if line == 0 {
+        return error.to_string();
+    }
+
+    for source in sources {
+        let lines = source.text.trim_end().lines().count().max(1);
+
+        if line <= lines {
+            return match &source.name {
+                Some(name) if source.is_file => {
+                    format!("{}:{}:{}: {}", name, line, start.column + 1, error)
+                }
+                Some(name) => format!("{}: {}", name, error),
+                None => error.to_string(),
+            };
+        }
+
+        line -= lines;
+    }
+
+    error.to_string()
+}
+
+pub struct TypesDeclaration {
+    pub types: NamespaceTypes,
+    pub span: proc_macro2::Span,
+}
+
+impl std::cmp::PartialOrd for TypesDeclaration {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::cmp::Ord for TypesDeclaration {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.types.cmp(&other.types)
+    }
+}
+
+impl PartialEq for TypesDeclaration {
+    fn eq(&self, other: &Self) -> bool {
+        self.types == other.types
+    }
+}
+
+impl Eq for TypesDeclaration {}
+
+impl syn::parse::Parse for BuildLimits {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let mut options = Vec::new();
+        let mut trees = Vec::new();
+        loop {
+            if input.is_empty() {
+                break;
+            }
+
+            // Options look like calls whereas types look like `use` paths.
+            if input.peek(syn::Ident) && input.peek2(syn::token::Paren) {
+                let option: syn::Ident = input.parse()?;
+                let content;
+                syn::parenthesized!(content in input);
+                options.push((option, content));
+            } else {
+                trees.push(input.parse::<BuildTree>()?);
+            }
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        // The metadata paths must be known before the reader used to resolve the other
+        // options and the types is created.
+        let mut winmd_paths = Vec::new();
+        let mut dll_paths = Vec::new();
+        let mut manifests = Vec::new();
+        let mut version_options = Vec::new();
+
+        for (option, content) in options {
+            match option.to_string().as_str() {
+                "winmd_path" => winmd_paths.push(parse_path(&content, true)?),
+                "dll_path" => dll_paths.push(parse_path(&content, false)?),
+                "manifest" => {
+                    let manifest = Manifest::read(parse_path(&content, true)?)
+                        .map_err(|error| syn::Error::new(option.span(), error))?;
+
+                    winmd_paths.extend(manifest.winmd_paths.iter().cloned());
+                    dll_paths.extend(manifest.dll_paths.iter().cloned());
+                    manifests.push((option, manifest));
+                }
+                _ => version_options.push((option, content)),
+            }
+        }
+
+        let reader = winmd::TypeReader::try_get_with(&winmd_paths)
+            .map_err(|error| syn::Error::new(proc_macro2::Span::call_site(), error))?;
+
+        let mut limits = Self {
+            reader,
+            types: Default::default(),
+            excluded: Default::default(),
+            renames: Vec::new(),
+            versions: VersionLimits::default(),
+            dll_paths,
+            manifest_paths: manifests
+                .iter()
+                .map(|(_, manifest)| manifest.path.clone())
+                .collect(),
+        };
+
+        for (option, manifest) in &manifests {
+            limits
+                .add_manifest(manifest)
+                .map_err(|error| syn::Error::new(option.span(), error))?;
+        }
+
+        for (option, content) in version_options {
+            parse_version_limit(reader, &option, &content, &mut limits.versions)?;
+        }
+
+        for tree in &trees {
+            let mut resolved = Resolved::default();
+            resolve_tree(reader, tree, false, &mut Vec::new(), &mut resolved)?;
+            limits.add(resolved);
+        }
+
+        Ok(limits)
+    }
+}
+
+impl BuildLimits {
+    fn add(&mut self, resolved: Resolved) {
+        self.types.extend(resolved.types);
+        self.excluded.extend(resolved.excluded);
+        self.renames.extend(resolved.renames);
+    }
+
+    /// Adds the types and versions of a manifest, whose paths have already been read
+    fn add_manifest(&mut self, manifest: &Manifest) -> Result<(), ManifestError> {
+        let reader = self.reader;
+
+        let resolve = |value: &ManifestValue, excluded: bool| {
+            let mut resolved = Resolved::default();
+
+            syn::parse_str::<BuildTree>(&value.value)
+                .and_then(|tree| {
+                    resolve_tree(reader, &tree, excluded, &mut Vec::new(), &mut resolved)
+                })
+                .map_err(|error| manifest.error(value.line, error))?;
+
+            Ok(resolved)
+        };
+
+        for value in &manifest.types {
+            self.add(resolve(value, false)?);
+        }
+
+        for value in &manifest.exclude {
+            self.add(resolve(value, true)?);
+        }
+
+        for (value, alias) in &manifest.renames {
+            let mut types = resolve(value, false)?.types;
+
+            match types.pop().map(|t| t.types) {
+                Some(NamespaceTypes {
+                    namespace,
+                    limit: TypeLimit::Some(mut names),
+                }) if types.is_empty() && names.len() == 1 => {
+                    self.renames
+                        .push((namespace, names.remove(0), alias.clone()))
+                }
+                _ => return Err(manifest.error(value.line, "expected the path of a single type")),
+            }
+        }
+
+        for (value, (major, minor)) in &manifest.minimum_contracts {
+            if !is_contract(reader, &value.value) {
+                return Err(manifest.error(
+                    value.line,
+                    format!("'{}' is not a known contract", value.value),
+                ));
+            }
+
+            self.versions
+                .contracts
+                .insert(value.value.clone(), major << 16 | minor);
+        }
+
+        if let Some((major, minor)) = manifest.minimum_os_version {
+            self.versions.os_version = Some(major << 24 | minor << 16);
+        }
+
+        Ok(())
+    }
+}
+
+fn is_contract(reader: &'static winmd::TypeReader, name: &str) -> bool {
+    match name.rfind('.') {
+        Some(pos) => reader
+            .find_type_def((&name[..pos], &name[pos + 1..]))
+            .is_some_and(|def| def.category() == winmd::TypeCategory::Contract),
+        None => false,
+    }
+}
+
+/// Parses the path of `winmd_path("...")` or `dll_path("...")`, which is relative to the
+/// crate's manifest directory
+fn parse_path(input: syn::parse::ParseStream, must_exist: bool) -> syn::parse::Result<PathBuf> {
+    let literal: syn::LitStr = input.parse()?;
+
+    if !input.is_empty() {
+        return Err(input.error("unexpected token"));
+    }
+
+    let mut path = PathBuf::from(literal.value());
+
+    if path.is_relative() {
+        if let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR") {
+            path = PathBuf::from(manifest_dir).join(path);
+        }
+    }
+
+    if must_exist && !path.exists() {
+        return Err(syn::Error::new(
+            literal.span(),
+            format!("'{}' does not exist", path.display()),
+        ));
+    }
+
+    Ok(path)
+}
+
+/// Parses the arguments of `minimum_contract(Namespace.Contract, 7)` or
+/// `minimum_os_version(6.3)`
+fn parse_version_limit(
+    reader: &'static winmd::TypeReader,
+    option: &syn::Ident,
+    content: syn::parse::ParseStream,
+    versions: &mut VersionLimits,
+) -> syn::parse::Result<()> {
+    match option.to_string().as_str() {
+        "minimum_contract" => {
+            let path =
+                syn::punctuated::Punctuated::<syn::Ident, syn::Token![.]>::parse_separated_nonempty(
+                    content,
+                )?;
+            content.parse::<syn::Token![,]>()?;
+            let (major, minor) = parse_version(content)?;
+
+            let name = path
+                .iter()
+                .map(|ident| ident.to_string())
+                .collect::<Vec<_>>()
+                .join(".");
+
+            if !is_contract(reader, &name) {
+                return Err(syn::Error::new(
+                    path.span(),
+                    format!("'{}' is not a known contract", name),
+                ));
+            }
+
+            versions.contracts.insert(name, major << 16 | minor);
+        }
+        "minimum_os_version" => {
+            let (major, minor) = parse_version(content)?;
+            versions.os_version = Some(major << 24 | minor << 16);
+        }
+        _ => {
+            return Err(syn::Error::new(
+                option.span(),
+                "expected `minimum_contract`, `minimum_os_version`, `winmd_path`, `dll_path` or `manifest`",
+            ))
+        }
+    }
+
+    if !content.is_empty() {
+        return Err(content.error("unexpected token"));
+    }
+
+    Ok(())
+}
+
+/// Parses a version such as `7` or `6.3` into its major and minor parts
+fn parse_version(input: syn::parse::ParseStream) -> syn::parse::Result<(u32, u32)> {
+    let literal: syn::Lit = input.parse()?;
+
+    let digits = match &literal {
+        syn::Lit::Int(literal) => literal.base10_digits().to_string(),
+        syn::Lit::Float(literal) => literal.base10_digits().to_string(),
+        _ => String::new(),
+    };
+
+    parse_version_text(&digits)
+        .ok_or_else(|| syn::Error::new(literal.span(), "expected a version such as `7` or `6.3`"))
+}
+
+/// Parses a version such as `7` or `6.3` into its major and minor parts
+pub(crate) fn parse_version_text(text: &str) -> Option<(u32, u32)> {
+    let mut parts = text.splitn(2, '.').map(|part| part.parse::<u16>());
+
+    match (parts.next(), parts.next()) {
+        (Some(Ok(major)), None) => Some((major as u32, 0)),
+        (Some(Ok(major)), Some(Ok(minor))) => Some((major as u32, minor as u32)),
+        _ => None,
+    }
+}
+
+/// The types, excluded types and renames of a tree
+#[derive(Default)]
+struct Resolved {
+    types: Vec<TypesDeclaration>,
+    excluded: Vec<TypesDeclaration>,
+    renames: Vec<(&'static str, String, String)>,
+}
+
+fn resolve_tree(
+    reader: &'static winmd::TypeReader,
+    tree: &BuildTree,
+    excluded: bool,
+    current: &mut Vec<syn::Ident>,
+    resolved: &mut Resolved,
+) -> syn::parse::Result<()> {
+    match tree {
+        BuildTree::Path(path) => {
+            current.push(path.ident.clone());
+            check_namespace_prefix(reader, current)?;
+            resolve_tree(reader, &path.tree, excluded, current, resolved)?;
+            current.pop();
+        }
+        BuildTree::Group(group) => {
+            for tree in &group.items {
+                resolve_tree(reader, tree, excluded, current, resolved)?;
+            }
+        }
+        BuildTree::Exclude(exclude) => {
+            if excluded {
+                return Err(syn::Error::new(
+                    exclude.bang_token.span,
+                    "the types are already excluded",
+                ));
+            }
+
+            resolve_tree(reader, &exclude.tree, true, current, resolved)?;
+        }
+        BuildTree::Name(name) => {
+            let namespace = if current.is_empty() {
+                find_type_namespace(reader, name)?
+            } else {
+                find_namespace(reader, current)?
+            };
+
+            if !name.is_pattern() {
+                check_type(reader, namespace, name)?;
+            }
+
+            match &name.rename {
+                Some(rename) if excluded => {
+                    return Err(syn::Error::new(
+                        rename.as_token.span,
+                        "excluded types can't be renamed",
+                    ));
+                }
+                Some(rename) => resolved.renames.push((
+                    namespace,
+                    name.pattern.clone(),
+                    rename.ident.unraw().to_string(),
+                )),
+                None => {
+                    let limit = if name.pattern == "*" {
+                        TypeLimit::All
+                    } else if name.is_pattern() {
+                        TypeLimit::Matching(vec![name.pattern.clone()])
+                    } else {
+                        TypeLimit::Some(vec![name.pattern.clone()])
+                    };
+
+                    let types = TypesDeclaration {
+                        types: NamespaceTypes { namespace, limit },
+                        span: name.span,
+                    };
+
+                    if excluded {
+                        resolved.excluded.push(types);
+                    } else {
+                        resolved.types.push(types);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the namespace of a path, reporting a path that is only the start of other namespaces
+/// on its last segment
+fn find_namespace(
+    reader: &'static winmd::TypeReader,
+    path: &[syn::Ident],
+) -> syn::parse::Result<&'static str> {
+    let namespace = namespace_literal_to_rough_namespace(&path_to_string(path, "."));
+
+    if let Some(namespace) = reader.find_lowercase_namespace(&namespace) {
+        return Ok(namespace);
+    }
+
+    let children = child_namespaces(reader, &namespace);
+    let last = path.last().expect("namespace paths aren't empty");
+
+    Err(syn::Error::new(
+        last.span(),
+        format!(
+            "'{}' is not a namespace, only the start of {}",
+            path_to_string(path, "::"),
+            quoted_list(
+                children
+                    .iter()
+                    .map(|child| format!("{}::{}", path_to_string(path, "::"), child)),
+                "or"
+            )
+        ),
+    ))
+}
+
+/// Checks that the path is a namespace or the start of one, reporting the first segment
+/// that isn't along with the segments that might have been meant
+fn check_namespace_prefix(
+    reader: &'static winmd::TypeReader,
+    path: &[syn::Ident],
+) -> syn::parse::Result<()> {
+    let (last, parent) = path.split_last().expect("namespace paths aren't empty");
+    let parent_namespace = namespace_literal_to_rough_namespace(&path_to_string(parent, "."));
+    let segment = namespace_literal_to_rough_namespace(&last.unraw().to_string());
+    let children = child_namespaces(reader, &parent_namespace);
+
+    if children
+        .iter()
+        .any(|child| namespace_literal_to_rough_namespace(child) == segment)
+    {
+        return Ok(());
+    }
+
+    let mut message = if parent.is_empty() {
+        format!("'{}' is not a known namespace", last.unraw())
+    } else {
+        format!(
+            "'{}' is not a namespace of '{}'",
+            last.unraw(),
+            path_to_string(parent, "::")
+        )
+    };
+
+    // Namespaces are compared without case or underscores since the segments of their paths
+    // are converted to snake case.
+    let suggestions = suggestions(
+        &segment,
+        children.iter().map(|child| child.as_str()),
+        namespace_literal_to_rough_namespace,
+    );
+
+    push_suggestions(&mut message, suggestions);
+    Err(syn::Error::new(last.span(), message))
+}
+
+/// Checks that the namespace defines a type, function or constant with the given name
+fn check_type(
+    reader: &'static winmd::TypeReader,
+    namespace: &'static str,
+    name: &BuildName,
+) -> syn::parse::Result<()> {
+    if reader.find_type((namespace, &name.pattern)).is_some() {
+        return Ok(());
+    }
+
+    let mut message = format!("'{}' is not a type of '{}'", name.pattern, namespace);
+    let names: Vec<_> = reader
+        .namespace_types(namespace)
+        .map(|t| t.name())
+        .collect();
+    push_suggestions(
+        &mut message,
+        suggestions(
+            &name.pattern.to_lowercase(),
+            names.into_iter(),
+            str::to_lowercase,
+        ),
+    );
+
+    Err(syn::Error::new(name.span, message))
+}
+
+/// The next segments of the namespaces that start with the given lowercase namespace, such as
+/// `foundation` for `windows`, converted to the snake case of `build!` paths
+fn child_namespaces(reader: &'static winmd::TypeReader, parent: &str) -> Vec<String> {
+    let mut children: Vec<String> = reader
+        .namespaces()
+        .filter_map(|namespace| {
+            let rest = if parent.is_empty() {
+                namespace.as_str()
+            } else {
+                let rest = namespace.get(parent.len()..)?;
+
+                if !namespace[..parent.len()].eq_ignore_ascii_case(parent) {
+                    return None;
+                }
+
+                rest.strip_prefix('.')?
+            };
+
+            match rest.split('.').next() {
+                Some(segment) if !segment.is_empty() => Some(to_snake(segment)),
+                _ => None,
+            }
+        })
+        .collect();
+
+    children.sort();
+    children.dedup();
+    children
+}
+
+fn path_to_string(path: &[syn::Ident], separator: &str) -> String {
+    path.iter()
+        .map(|ident| ident.unraw().to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Finds the candidates that are closest to the name, at most three of them, in order of
+/// their edit distance from the name
+///
+/// The name is compared with each candidate after converting it with `key`, so that
+/// case differences may be ignored.
+fn suggestions<'a, I: Iterator<Item = &'a str>, F: Fn(&str) -> String>(
+    name: &str,
+    candidates: I,
+    key: F,
+) -> Vec<&'a str> {
+    // Allow roughly one typo for every three characters.
+    let limit = (name.chars().count() / 3).max(1);
+
+    let mut suggestions: Vec<_> = candidates
+        .filter_map(|candidate| {
+            let distance = edit_distance(name, &key(candidate));
+            if distance <= limit {
+                Some((distance, candidate))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    suggestions.sort();
+    suggestions.dedup();
+    suggestions
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+fn push_suggestions(message: &mut String, suggestions: Vec<&str>) {
+    if !suggestions.is_empty() {
+        message.push_str(", did you mean ");
+        message.push_str(&quoted_list(suggestions.into_iter(), "or"));
+        message.push('?');
+    }
+}
+
+/// Quotes the items and joins them with commas and a final `or` or `and`
+fn quoted_list<T: std::fmt::Display, I: Iterator<Item = T>>(items: I, conjunction: &str) -> String {
+    let items: Vec<_> = items.map(|item| format!("'{}'", item)).collect();
+
+    match items.split_last() {
+        Some((last, rest)) if !rest.is_empty() => {
+            format!("{} {} {}", rest.join(", "), conjunction, last)
+        }
+        _ => items.concat(),
+    }
+}
+
+/// The Levenshtein distance between two strings, counted in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous + if a == *b { 0 } else { 1 };
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(previous + 1).min(row[j] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Finds the namespace of a type given without one, which must be defined by a single namespace
+fn find_type_namespace(
+    reader: &'static winmd::TypeReader,
+    name: &BuildName,
+) -> syn::parse::Result<&'static str> {
+    if name.is_pattern() {
+        return Err(syn::Error::new(
+            name.span,
+            "patterns can only match the types of a namespace",
+        ));
+    }
+
+    let namespaces: Vec<_> = reader.find_type_namespaces(&name.pattern).collect();
+
+    match namespaces.as_slice() {
+        [] => {
+            let mut message = format!("'{}' is not a known type", name.pattern);
+            push_suggestions(
+                &mut message,
+                suggestions(
+                    &name.pattern.to_lowercase(),
+                    reader.type_names(),
+                    str::to_lowercase,
+                ),
+            );
+            Err(syn::Error::new(name.span, message))
+        }
+        [namespace] => Ok(namespace),
+        _ => Err(syn::Error::new(
+            name.span,
+            format!(
+                "'{}' is defined in more than one namespace, use the path of one of: {}",
+                name.pattern,
+                namespaces
+                    .iter()
+                    .map(|namespace| namespace_to_path(namespace))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        )),
+    }
+}
+
+/// Converts a namespace such as `Windows.Win32.SystemServices` to the module path used by
+/// `build!`, such as `windows::win32::system_services`
+fn namespace_to_path(namespace: &str) -> String {
+    namespace
+        .split('.')
+        .map(to_snake)
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+// Snake <-> camel casing is lossy so we go for character but not case conversion
+// and deal with casing once we have an index of namespaces to compare against.
+pub fn namespace_literal_to_rough_namespace(namespace: &str) -> String {
+    let mut result = String::with_capacity(namespace.len());
+    for c in namespace.chars() {
+        if c != '"' && c != '_' {
+            result.extend(c.to_lowercase());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let limits: BuildLimits = syn::parse_str(
+            "minimum_os_version(6.3), windows::globalization::{Language, ILanguage2},",
+        )
+        .unwrap();
+
+        assert_eq!(limits.versions.os_version, Some(6 << 24 | 3 << 16));
+        assert_eq!(limits.types.len(), 2);
+
+        let tokens = limits.to_tokens_string().unwrap();
+        assert!(tokens.contains("pub struct Language"));
+
+        let limits: BuildLimits = syn::parse_str(
+            "windows::{ui::{*, !{Colors, IColors*}}, globalization::{Language as Lang}}",
+        )
+        .unwrap();
+
+        assert_eq!(limits.types.len(), 1);
+        assert_eq!(limits.excluded.len(), 2);
+        assert_eq!(
+            limits.renames,
+            [(
+                "Windows.Globalization",
+                "Language".to_string(),
+                "Lang".to_string()
+            )]
+        );
+
+        let error = syn::parse_str::<BuildLimits>("windows::ui::{Colo*s as C}")
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "patterns can't be renamed");
+
+        let error =
+            syn::parse_str::<BuildLimits>("windows::foundation::Uri,\nminimum_os_version(x)")
+                .err()
+                .unwrap();
+        assert_eq!(error.span().start().line, 2);
+    }
+
+    #[test]
+    fn bare_names() {
+        let limits: BuildLimits = syn::parse_str("{GetLastError, Uri as WindowsUri}").unwrap();
+        assert_eq!(limits.types.len(), 1);
+        assert_eq!(limits.renames[0].0, "Windows.Foundation");
+    }
+
+    #[test]
+    fn ambiguous_names() {
+        let error = syn::parse_str::<BuildLimits>("Ellipse").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "'Ellipse' is defined in more than one namespace, use the path of one of: \
+             windows::ui::xaml::shapes, windows::win32::graphics::gdi"
+        );
+    }
+
+    #[test]
+    fn to_tree() {
+        let limits: BuildLimits = syn::parse_str("windows::foundation::Uri as U").unwrap();
+        let error = limits.to_tree().err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "'Windows.Foundation.Uri' can't be renamed to 'U' since the types of \
+             'Windows.Foundation' are used from the `windows` crate"
+        );
+
+        let limits: BuildLimits =
+            syn::parse_str("windows::globalization::{Language as Lang, !Language}").unwrap();
+        let error = limits.to_tree().err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "'Windows.Globalization.Language' can't be renamed to 'Lang' since it isn't generated"
+        );
+
+        let limits: BuildLimits = syn::parse_str("!windows::foundation::Uri").unwrap();
+        let error = limits.to_tree().err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "expected types to generate along with the types to exclude"
+        );
+
+        let limits: BuildLimits =
+            syn::parse_str("windows::globalization::Language as Lang").unwrap();
+        let tokens = limits.to_tokens_string().unwrap();
+        assert!(tokens.contains("pub use self :: Language as Lang ;"));
+    }
+
+    #[test]
+    fn describe() {
+        let file = ArgumentSource {
+            name: Some("types.txt".to_string()),
+            text: "// Types\nwindows::foundation::Uri,\nwindows::foundation::Urr".to_string(),
+            is_file: true,
+        };
+
+        let argument = |text: &str| ArgumentSource {
+            name: Some(format!("`{}`", text)),
+            text: text.to_string(),
+            is_file: false,
+        };
+
+        let error = BuildLimits::parse_sources(&[file, argument("windows::foundation::Uri")])
+            .err()
+            .unwrap();
+        assert!(error.starts_with("types.txt:3:22: 'Urr' is not a type of 'Windows.Foundation'"));
+
+        let sources = [
+            argument("windows::foundation::Uri"),
+            argument("minimum_os_version(x)"),
+        ];
+        let error = BuildLimits::parse_sources(&sources).err().unwrap();
+        assert_eq!(error, "`minimum_os_version(x)`: expected literal");
+    }
+
+    #[test]
+    fn suggestions() {
+        assert_eq!(edit_distance("XmlDocumnet", "XmlDocument"), 2);
+        assert_eq!(edit_distance("", "Uri"), 3);
+        assert_eq!(edit_distance("Uri", "Uri"), 0);
+
+        let error = syn::parse_str::<BuildLimits>("windows::data::xml::dom::XmlDocumnet")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "'XmlDocumnet' is not a type of 'Windows.Data.Xml.Dom', \
+             did you mean 'XmlDocument' or 'IXmlDocument'?"
+        );
+        assert_eq!(error.span().start().column, 25);
+
+        let error = syn::parse_str::<BuildLimits>("windows::foundaton::{Uri, IStringable}")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "'foundaton' is not a namespace of 'windows', did you mean 'foundation'?"
+        );
+        assert_eq!(error.span().start().column, 9);
+
+        let error = syn::parse_str::<BuildLimits>("windows::data::xml::XmlDocument")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "'windows::data::xml' is not a namespace, only the start of \
+             'windows::data::xml::dom' or 'windows::data::xml::xsl'"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::token::Brace;
+use syn::{braced, Ident, Token};
+
+/// A path of the types to generate, like the path of a `use` declaration but with patterns
+/// such as `Create*W` in place of type names and `!` before the types to exclude
+pub enum BuildTree {
+    Path(BuildPath),
+    Name(BuildName),
+    Group(BuildGroup),
+    Exclude(BuildExclude),
+}
+
+pub struct BuildPath {
+    pub ident: Ident,
+    pub colon2_token: Token![::],
+    pub tree: Box<BuildTree>,
+}
+
+/// A type name, a pattern of type names, or `*` for all the types of a namespace
+pub struct BuildName {
+    pub pattern: String,
+    pub span: proc_macro2::Span,
+    pub rename: Option<BuildRename>,
+}
+
+pub struct BuildRename {
+    pub as_token: Token![as],
+    pub ident: Ident,
+}
+
+pub struct BuildGroup {
+    pub brace_token: Brace,
+    pub items: Punctuated<BuildTree, Token![,]>,
+}
+
+pub struct BuildExclude {
+    pub bang_token: Token![!],
+    pub tree: Box<BuildTree>,
+}
+
+impl BuildName {
+    pub fn is_pattern(&self) -> bool {
+        self.pattern.contains('*')
+    }
+}
+
+impl Parse for BuildTree {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![!]) {
+            Ok(BuildTree::Exclude(BuildExclude {
+                bang_token: input.parse()?,
+                tree: Box::new(input.parse()?),
+            }))
+        } else if lookahead.peek(Ident::peek_any) || lookahead.peek(Token![*]) {
+            let span = input.span();
+            let pattern = parse_pattern(input)?;
+
+            if input.peek(Token![::]) {
+                if pattern.contains('*') {
+                    return Err(syn::Error::new(
+                        span,
+                        "patterns can only match the types of a namespace",
+                    ));
+                }
+
+                Ok(BuildTree::Path(BuildPath {
+                    ident: Ident::new(&pattern, span),
+                    colon2_token: input.parse()?,
+                    tree: Box::new(input.parse()?),
+                }))
+            } else {
+                let rename = if input.peek(Token![as]) {
+                    if pattern.contains('*') {
+                        return Err(input.error("patterns can't be renamed"));
+                    }
+
+                    Some(BuildRename {
+                        as_token: input.parse()?,
+                        ident: input.call(Ident::parse_any)?,
+                    })
+                } else {
+                    None
+                };
+
+                Ok(BuildTree::Name(BuildName {
+                    pattern,
+                    span,
+                    rename,
+                }))
+            }
+        } else if lookahead.peek(Brace) {
+            let content;
+            Ok(BuildTree::Group(BuildGroup {
+                brace_token: braced!(content in input),
+                items: content.parse_terminated(BuildTree::parse)?,
+            }))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+/// Parses a name such as `CreateEventW` or a pattern such as `Create*W`, where identifiers
+/// and `*` alternate
+fn parse_pattern(input: ParseStream) -> Result<String> {
+    let mut pattern = String::new();
+
+    loop {
+        if input.peek(Token![*]) {
+            input.parse::<Token![*]>()?;
+            pattern.push('*');
+
+            // `peek(Ident)` skips keywords so a rename such as `* as Alias` isn't taken for
+            // a pattern.
+            if !input.peek(Ident) {
+                break;
+            }
+        } else {
+            pattern.push_str(&input.call(Ident::parse_any)?.unraw().to_string());
+
+            if !input.peek(Token![*]) {
+                break;
+            }
+        }
+    }
+
+    Ok(pattern)
+}
@@ -25,6 +25,7 @@ impl Callback {
 
     pub fn gen(&self) -> TokenStream {
         let name = self.name.gen();
+        let doc = Doc::from_type_def(&self.name.def).gen();
 
         let params = self.signature.params.iter().map(|t| {
             let name = format_ident(&t.name);
@@ -40,6 +41,7 @@ impl Callback {
         };
 
         quote! {
+            #doc
             #[allow(non_camel_case_types)]
             pub type #name = extern "system" fn(#(#params),*) #return_type;
         }
@@ -69,23 +69,25 @@ impl Class {
                     // One of the arguments is a CompositionType enum and the Public variant
                     // has a value of 2 as a signed 32-bit integer.
                     for (_name, arg) in attribute.args() {
-                        if let winmd::AttributeArg::I32(2) = arg {
-                            add_type(
-                                &mut interfaces,
-                                &attribute_factory(&attribute).unwrap(),
-                                &name.namespace,
-                                InterfaceKind::Composable,
-                            );
+                        if let winmd::AttributeArg::Enum(_, value) = arg {
+                            if let winmd::AttributeArg::I32(2) = *value {
+                                add_type(
+                                    &mut interfaces,
+                                    &attribute_factory(&attribute).unwrap(),
+                                    &name.namespace,
+                                    InterfaceKind::Composable,
+                                );
+                            }
                         }
                     }
                 }
                 ("Windows.Foundation.Metadata", "MarshalingBehaviorAttribute") => {
                     // The only argument is a MarshalingType enum and the Agile variant
                     // has a value of 2 as a signed 32-bit integer.
-                    let (_name, arg) = &attribute.args()[0];
-
-                    if let winmd::AttributeArg::I32(2) = arg {
-                        is_agile = true;
+                    if let (_name, winmd::AttributeArg::Enum(_, value)) = &attribute.args()[0] {
+                        if let winmd::AttributeArg::I32(2) = **value {
+                            is_agile = true;
+                        }
                     }
                 }
                 _ => {}
@@ -112,8 +114,42 @@ impl Class {
             .collect()
     }
 
+    pub fn doc(&self) -> Doc {
+        let mut doc = Doc::from_type_def(&self.name.def);
+        doc.push("Runtime class", code(&self.name.runtime_name()));
+
+        if let Some(default_interface) = self
+            .interfaces
+            .iter()
+            .find(|i| i.kind == InterfaceKind::Default)
+        {
+            doc.push(
+                "Default interface",
+                code(&default_interface.name.runtime_name()),
+            );
+
+            // The IIDs of generic interfaces are only known once their signatures are hashed.
+            if default_interface.name.generics.is_empty() {
+                let guid = TypeGuid::from_type_def(&default_interface.name.def);
+                doc.push_guid("Default interface IID", &default_interface.name, &guid);
+            }
+        }
+
+        let yes_no = |value| if value { "yes" } else { "no" };
+        doc.push("Agile", yes_no(self.is_agile));
+
+        let activatable = self.default_constructor
+            || self
+                .name
+                .def
+                .has_attribute(("Windows.Foundation.Metadata", "ActivatableAttribute"));
+        doc.push("Activatable", yes_no(activatable));
+        doc
+    }
+
     pub fn gen(&self) -> TokenStream {
         let name = self.name.gen();
+        let doc = self.doc().gen();
         let type_name = self.type_name(&name);
         let methods = gen_method(&self.interfaces);
         let call_factory = self.gen_call_factory();
@@ -157,6 +193,7 @@ impl Class {
             };
 
             quote! {
+                #doc
                 #[repr(transparent)]
                 pub struct #name(::windows::Object);
                 impl #name {
@@ -218,6 +255,7 @@ impl Class {
             }
         } else {
             quote! {
+                #doc
                 pub struct #name {}
                 impl #name {
                     #methods
@@ -12,6 +12,8 @@ pub struct ComInterface {
 
 #[derive(Debug)]
 struct Method {
+    /// The interface that declares the method, which may be a base interface
+    def: winmd::TypeDef,
     signature: Signature,
     overload: u32,
 }
@@ -78,6 +80,7 @@ impl ComInterface {
                 }
 
                 methods.push(Method {
+                    def,
                     signature,
                     overload: *count,
                 });
@@ -95,6 +98,11 @@ impl ComInterface {
         let name = self.name.gen();
         let abi_name = self.name.gen_abi_definition();
         let guid = TypeGuid::from_type_def(&self.name.def);
+
+        let mut doc = Doc::from_type_def(&self.name.def);
+        doc.push_guid("IID", &self.name, &guid);
+        let doc = doc.gen();
+
         let guid = self.name.gen_guid(&guid);
 
         // TODO: here we're looking up the param name (from the file) repeatedly - cache name in Type
@@ -112,8 +120,10 @@ impl ComInterface {
             let args = gen_abi_args(method);
             let name = method.gen_name();
             let vtable_offset = Literal::u32_unsuffixed((vtable_offset + 3) as u32);
+            let doc = Doc::from_method_def(&method.def, &method.signature.method).gen();
 
             quote! {
+                #doc
                 pub unsafe fn #name<#constraints>(&self, #params) #return_type {
                     (::windows::Interface::vtable(self).#vtable_offset)(::windows::Abi::abi(self), #args)
                 }
@@ -201,6 +211,7 @@ impl ComInterface {
         }
 
         quote! {
+            #doc
             #[repr(transparent)]
             #[allow(non_camel_case_types)]
             pub struct #name(::windows::IUnknown);
@@ -35,10 +35,12 @@ impl Constant {
 
         let value = constant.value();
 
+        let doc = Doc::from_field(&self.name.def, &self.field).gen();
         let name = format_ident(name);
         let value = value.gen();
 
         quote! {
+            #doc
             pub const #name: #value;
         }
     }
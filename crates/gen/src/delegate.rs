@@ -24,7 +24,14 @@ impl Delegate {
         self.method.dependencies()
     }
 
+    pub fn doc(&self) -> Doc {
+        let mut doc = Doc::from_type_def(&self.name.def);
+        doc.push_guid("IID", &self.name, &self.guid);
+        doc
+    }
+
     pub fn gen(&self) -> TokenStream {
+        let doc = self.doc().gen();
         let definition = self.name.gen_definition();
         let vtable_definition = self.name.gen_abi_definition();
         let fn_constraint = self.gen_fn_constraint();
@@ -49,6 +56,7 @@ impl Delegate {
         let invoke_upcall = self.method.gen_upcall(quote! { ((*this).invoke) }, true);
 
         quote! {
+            #doc
             #[repr(transparent)]
             pub struct #definition(::windows::IUnknown, #phantoms) where #constraints;
             impl<#constraints> ::std::clone::Clone for #name {
@@ -0,0 +1,426 @@
+use crate::*;
+use std::collections::BTreeMap;
+
+/// Whether a metadata change preserves the generated APIs or may break code that uses them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Additive,
+    Breaking,
+}
+
+/// A difference between two versions of the same metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// The fully qualified name of the affected type
+    pub type_name: String,
+    pub description: String,
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            ChangeKind::Additive => "additive",
+            ChangeKind::Breaking => "breaking",
+        };
+
+        write!(f, "[{}] {}: {}", kind, self.type_name, self.description)
+    }
+}
+
+/// Compares the types of two metadata sets, such as successive versions of the Win32 or
+/// WinRT metadata, optionally limited to the given namespaces or types
+///
+/// Changes are ordered by type name and then in the order in which they were found.
+pub fn diff_metadata(
+    old: &'static winmd::TypeReader,
+    new: &'static winmd::TypeReader,
+    filters: &[String],
+) -> Vec<Change> {
+    let old = index(find_types(old, filters).0);
+    let new = index(find_types(new, filters).0);
+    let mut changes = Vec::new();
+
+    for (name, old_type) in &old {
+        match new.get(name) {
+            Some(new_type) => diff_type(name, old_type, new_type, &mut changes),
+            None => changes.push(change(
+                ChangeKind::Breaking,
+                name,
+                "type removed".to_string(),
+            )),
+        }
+    }
+
+    for name in new.keys().filter(|name| !old.contains_key(*name)) {
+        changes.push(change(ChangeKind::Additive, name, "type added".to_string()));
+    }
+
+    changes.sort_by(|a, b| a.type_name.cmp(&b.type_name));
+    changes
+}
+
+/// Indexes the types by name, keeping the first of the per-architecture variants of a type
+fn index(dumps: Vec<TypeDump>) -> BTreeMap<String, TypeDump> {
+    let mut index = BTreeMap::new();
+
+    for dump in dumps {
+        index
+            .entry(format!("{}.{}", dump.namespace, dump.name))
+            .or_insert(dump);
+    }
+
+    index
+}
+
+fn change(kind: ChangeKind, type_name: &str, description: String) -> Change {
+    Change {
+        kind,
+        type_name: type_name.to_string(),
+        description,
+    }
+}
+
+fn diff_type(name: &str, old: &TypeDump, new: &TypeDump, changes: &mut Vec<Change>) {
+    let mut breaking =
+        |description: String| changes.push(change(ChangeKind::Breaking, name, description));
+
+    if old.category != new.category {
+        breaking(format!("changed from {} to {}", old.category, new.category));
+        return;
+    }
+
+    if old.guid != new.guid {
+        breaking(format!(
+            "GUID changed from {} to {}",
+            old.guid.as_deref().unwrap_or("none"),
+            new.guid.as_deref().unwrap_or("none")
+        ));
+    }
+
+    if old.generics.len() != new.generics.len() {
+        breaking(format!(
+            "generic parameter count changed from {} to {}",
+            old.generics.len(),
+            new.generics.len()
+        ));
+    }
+
+    if old.extends != new.extends {
+        breaking(format!(
+            "base type changed from {} to {}",
+            old.extends.as_deref().unwrap_or("none"),
+            new.extends.as_deref().unwrap_or("none")
+        ));
+    }
+
+    diff_interfaces(name, old, new, changes);
+
+    match old.category {
+        "struct" => diff_struct(name, old, new, changes),
+        "enum" => diff_constants(name, old, new, changes),
+        "interface" | "delegate" => {
+            diff_vtable(name, old, new, changes);
+        }
+        _ => {
+            diff_constants(name, old, new, changes);
+            diff_methods(name, old, new, changes);
+        }
+    }
+}
+
+fn diff_interfaces(name: &str, old: &TypeDump, new: &TypeDump, changes: &mut Vec<Change>) {
+    for interface in &old.interfaces {
+        if !new.interfaces.contains(interface) {
+            changes.push(change(
+                ChangeKind::Breaking,
+                name,
+                format!("no longer implements {}", interface),
+            ));
+        }
+    }
+
+    for interface in &new.interfaces {
+        if !old.interfaces.contains(interface) {
+            changes.push(change(
+                ChangeKind::Additive,
+                name,
+                format!("now implements {}", interface),
+            ));
+        }
+    }
+}
+
+/// Any change to the fields or layout of a struct changes its size or the offsets of its
+/// fields and so is always breaking.
+fn diff_struct(name: &str, old: &TypeDump, new: &TypeDump, changes: &mut Vec<Change>) {
+    let fields = |dump: &TypeDump| -> Vec<String> {
+        dump.fields
+            .iter()
+            .filter(|field| field.constant.is_none())
+            .map(|field| format!("{} {}", field.ty, field.name))
+            .collect()
+    };
+
+    let (old_fields, new_fields) = (fields(old), fields(new));
+
+    if old_fields != new_fields {
+        changes.push(change(
+            ChangeKind::Breaking,
+            name,
+            format!(
+                "layout changed from {{ {} }} to {{ {} }}",
+                old_fields.join("; "),
+                new_fields.join("; ")
+            ),
+        ));
+    }
+
+    if old.layout != new.layout {
+        let layout = |layout: Option<(u32, u32)>| match layout {
+            Some((packing, size)) => format!("pack {}, size {}", packing, size),
+            None => "default".to_string(),
+        };
+
+        changes.push(change(
+            ChangeKind::Breaking,
+            name,
+            format!(
+                "packing changed from {} to {}",
+                layout(old.layout),
+                layout(new.layout)
+            ),
+        ));
+    }
+}
+
+fn diff_constants(name: &str, old: &TypeDump, new: &TypeDump, changes: &mut Vec<Change>) {
+    let constants = |dump: &TypeDump| -> BTreeMap<&str, (String, String)> {
+        dump.fields
+            .iter()
+            .filter_map(|field| {
+                field
+                    .constant
+                    .as_ref()
+                    .map(|value| (field.name, (field.ty.clone(), value.clone())))
+            })
+            .collect()
+    };
+
+    let (old_constants, new_constants) = (constants(old), constants(new));
+
+    // The underlying type of an enum is the type of its `value__` field.
+    if old.category == "enum" {
+        let underlying = |dump: &TypeDump| {
+            dump.fields
+                .iter()
+                .find(|field| field.constant.is_none())
+                .map(|field| field.ty.clone())
+        };
+
+        if underlying(old) != underlying(new) {
+            changes.push(change(
+                ChangeKind::Breaking,
+                name,
+                format!(
+                    "underlying type changed from {} to {}",
+                    underlying(old).unwrap_or_default(),
+                    underlying(new).unwrap_or_default()
+                ),
+            ));
+        }
+    }
+
+    for (constant, (old_type, old_value)) in &old_constants {
+        match new_constants.get(constant) {
+            None => changes.push(change(
+                ChangeKind::Breaking,
+                name,
+                format!("{} removed", constant),
+            )),
+            Some((new_type, new_value)) if (old_type, old_value) != (new_type, new_value) => {
+                changes.push(change(
+                    ChangeKind::Breaking,
+                    name,
+                    format!(
+                        "{} changed from {} {} to {} {}",
+                        constant, old_type, old_value, new_type, new_value
+                    ),
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    for constant in new_constants
+        .keys()
+        .filter(|constant| !old_constants.contains_key(*constant))
+    {
+        changes.push(change(
+            ChangeKind::Additive,
+            name,
+            format!("{} added", constant),
+        ));
+    }
+}
+
+/// The methods of interfaces and delegates make up a vtable, so methods may only be added
+/// after the existing ones.
+fn diff_vtable(name: &str, old: &TypeDump, new: &TypeDump, changes: &mut Vec<Change>) {
+    for (slot, old_method) in old.methods.iter().enumerate() {
+        match new.methods.get(slot) {
+            None => changes.push(change(
+                ChangeKind::Breaking,
+                name,
+                format!("{} removed from vtable slot {}", old_method.name, slot),
+            )),
+            Some(new_method) if new_method.name != old_method.name => changes.push(change(
+                ChangeKind::Breaking,
+                name,
+                format!(
+                    "vtable slot {} changed from {} to {}",
+                    slot, old_method.name, new_method.name
+                ),
+            )),
+            Some(new_method) => diff_signature(name, old_method, new_method, changes),
+        }
+    }
+
+    for new_method in new.methods.iter().skip(old.methods.len()) {
+        changes.push(change(
+            ChangeKind::Additive,
+            name,
+            format!("{} appended to vtable", new_method.name),
+        ));
+    }
+}
+
+/// The methods of classes, including the functions of Win32 `Apis` classes, are matched by
+/// name since their order does not affect the generated APIs.
+fn diff_methods(name: &str, old: &TypeDump, new: &TypeDump, changes: &mut Vec<Change>) {
+    fn methods(dump: &TypeDump) -> BTreeMap<&str, &MethodDump> {
+        dump.methods
+            .iter()
+            .map(|method| (method.name, method))
+            .collect()
+    }
+
+    let (old_methods, new_methods) = (methods(old), methods(new));
+
+    for (method, old_method) in &old_methods {
+        match new_methods.get(method) {
+            Some(new_method) => diff_signature(name, old_method, new_method, changes),
+            None => changes.push(change(
+                ChangeKind::Breaking,
+                name,
+                format!("{} removed", method),
+            )),
+        }
+    }
+
+    for method in new_methods
+        .keys()
+        .filter(|method| !old_methods.contains_key(*method))
+    {
+        changes.push(change(
+            ChangeKind::Additive,
+            name,
+            format!("{} added", method),
+        ));
+    }
+}
+
+/// Parameter names are ignored since they don't affect callers.
+fn diff_signature(name: &str, old: &MethodDump, new: &MethodDump, changes: &mut Vec<Change>) {
+    let signature = |method: &MethodDump| {
+        let params: Vec<_> = method
+            .params
+            .iter()
+            .map(|param| param.ty.as_str())
+            .collect();
+        format!(
+            "{}({}) -> {}",
+            method.name,
+            params.join(", "),
+            method.return_type
+        )
+    };
+
+    let (old_signature, new_signature) = (signature(old), signature(new));
+
+    if old_signature != new_signature {
+        changes.push(change(
+            ChangeKind::Breaking,
+            name,
+            format!(
+                "signature changed from {} to {}",
+                old_signature, new_signature
+            ),
+        ));
+    }
+
+    if old.dll != new.dll {
+        changes.push(change(
+            ChangeKind::Breaking,
+            name,
+            format!(
+                "{} moved from {} to {}",
+                old.name,
+                old.dll.unwrap_or("none"),
+                new.dll.unwrap_or("none")
+            ),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn diff() {
+        let old = winmd::idl::test_reader(
+            r#"
+            namespace Test
+            {
+                enum Color { Red, Green };
+                struct Point { Int32 X; Int32 Y; };
+                struct Size { Int32 Width; };
+                interface IShape { Int32 Area(); Point Center(); };
+            }
+        "#,
+        );
+
+        let new = winmd::idl::test_reader(
+            r#"
+            namespace Test
+            {
+                enum Color { Red, Green = 5, Blue };
+                struct Point { Int32 X; Int32 Y; };
+                struct Rect { Int32 Left; };
+                interface IShape { Point Center(); Int32 Area(); String Name(); };
+            }
+        "#,
+        );
+
+        let changes: Vec<_> = diff_metadata(old, new, &[])
+            .iter()
+            .map(|change| change.to_string())
+            .collect();
+
+        assert_eq!(
+            changes,
+            [
+                "[breaking] Test.Color: Green changed from Test.Color 1 to Test.Color 5",
+                "[additive] Test.Color: Blue added",
+                "[breaking] Test.IShape: vtable slot 0 changed from Area to Center",
+                "[breaking] Test.IShape: vtable slot 1 changed from Center to Area",
+                "[additive] Test.IShape: Name appended to vtable",
+                "[additive] Test.Rect: type added",
+                "[breaking] Test.Size: type removed",
+            ]
+        );
+
+        assert!(diff_metadata(old, old, &[]).is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+use crate::*;
+use squote::{quote, TokenStream};
+
+/// The doc comment of a generated item
+///
+/// The comment starts with the summary from the XML documentation next to the metadata,
+/// if any, followed by a list of facts from the metadata such as the item's original name.
+/// Items marked with `DeprecatedAttribute` are also marked as deprecated in Rust.
+#[derive(Debug, Default)]
+pub struct Doc {
+    pub summary: Option<String>,
+    pub facts: Vec<String>,
+    /// The message of the item's `DeprecatedAttribute`, if any
+    pub deprecated: Option<String>,
+}
+
+impl Doc {
+    /// The doc comment of a type, including its contract and version attributes
+    pub fn from_type_def(def: &winmd::TypeDef) -> Self {
+        let (namespace, name) = def.name();
+        let mut doc = Self::new(def, &format!("T:{}.{}", namespace, name));
+        doc.push("Metadata", format!("{} in {}", code(name), code(namespace)));
+        doc.push_attributes(def.attributes());
+        doc
+    }
+
+    /// The doc comment of a method or function declared by the given type
+    pub fn from_method_def(def: &winmd::TypeDef, method: &winmd::MethodDef) -> Self {
+        let (namespace, name) = def.name();
+        let mut doc = Self::new(def, &format!("M:{}.{}.{}", namespace, name, method.name()));
+        doc.push(
+            "Metadata",
+            format!(
+                "{} in {}",
+                code(method.name()),
+                code(&format!("{}.{}", namespace, name))
+            ),
+        );
+        doc.push_attributes(method.attributes());
+        doc
+    }
+
+    /// The doc comment of a constant declared by the given type
+    pub fn from_field(def: &winmd::TypeDef, field: &winmd::Field) -> Self {
+        let (namespace, name) = def.name();
+        let mut doc = Self::new(def, &format!("F:{}.{}.{}", namespace, name, field.name()));
+        doc.push(
+            "Metadata",
+            format!(
+                "{} in {}",
+                code(field.name()),
+                code(&format!("{}.{}", namespace, name))
+            ),
+        );
+        doc
+    }
+
+    fn new(def: &winmd::TypeDef, member: &str) -> Self {
+        Self {
+            summary: def
+                .reader
+                .doc_summary(member)
+                .map(|summary| summary.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a fact to the list, e.g. `push("DLL", "`KERNEL32`")`
+    pub fn push<T: AsRef<str>>(&mut self, label: &str, value: T) {
+        self.facts.push(format!("{}: {}", label, value.as_ref()));
+    }
+
+    /// Adds the GUID of a type, which for a generic WinRT interface or delegate is the
+    /// parameterized IID from which the IIDs of its instances are derived
+    pub fn push_guid(&mut self, label: &str, name: &TypeName, guid: &TypeGuid) {
+        if *guid == TypeGuid::default() {
+            return;
+        }
+
+        if name.generics.is_empty() {
+            self.push(label, format!("`{:?}`", guid));
+        } else {
+            self.push(&format!("Parameterized {}", label), format!("`{:?}`", guid));
+        }
+    }
+
+    fn push_attributes<I: Iterator<Item = winmd::Attribute>>(&mut self, attributes: I) {
+        for attribute in attributes {
+            let args = match attribute.try_args() {
+                Ok(args) => args,
+                Err(_) => continue,
+            };
+
+            match (attribute.name(), args.as_slice()) {
+                (
+                    ("Windows.Foundation.Metadata", "ContractVersionAttribute"),
+                    [(_, contract), (_, winmd::AttributeArg::U32(version))],
+                ) => {
+                    let contract = match contract_name(contract) {
+                        Some(contract) => contract,
+                        None => continue,
+                    };
+
+                    self.push(
+                        "Contract",
+                        format!("{} version {}", code(&contract), contract_version(*version)),
+                    );
+                }
+                (
+                    ("Windows.Foundation.Metadata", "ContractVersionAttribute"),
+                    [(_, winmd::AttributeArg::U32(version))],
+                ) => self.push("Contract version", contract_version(*version)),
+                (
+                    ("Windows.Foundation.Metadata", "VersionAttribute"),
+                    [(_, winmd::AttributeArg::U32(version)), ..],
+                ) => self.push(
+                    "Windows version",
+                    format!("{}.{}", version >> 24, (version >> 16) & 0xFF),
+                ),
+                // Both the `Deprecate` and `Remove` deprecation types are treated alike since
+                // removed APIs remain in the metadata, and so in the bindings.
+                (
+                    ("Windows.Foundation.Metadata", "DeprecatedAttribute"),
+                    [(_, winmd::AttributeArg::String(message)), ..],
+                ) => self.deprecated = Some(message.clone()),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn gen(&self) -> TokenStream {
+        let mut lines = Vec::new();
+
+        if let Some(summary) = &self.summary {
+            lines.push(format!(" {}", summary));
+
+            if !self.facts.is_empty() {
+                lines.push(String::new());
+            }
+        }
+
+        lines.extend(self.facts.iter().map(|fact| format!(" * {}", fact)));
+
+        // Doc attributes are written as string literals so quotes and backslashes in the
+        // summaries must be escaped.
+        let lines = lines.iter().map(|line| line.escape_default().to_string());
+
+        let deprecated = self.deprecated.as_ref().map(|message| {
+            let message = message.escape_default().to_string();
+            quote! { #[deprecated(note = #message)] }
+        });
+
+        quote! {
+            #(#[doc = #lines])*
+            #deprecated
+        }
+    }
+}
+
+/// Formats a name as inline code, using double backticks for the names of generic types
+/// like ``IVector`1`` since they contain a backtick
+pub fn code(name: &str) -> String {
+    if name.contains('`') {
+        format!("`` {} ``", name)
+    } else {
+        format!("`{}`", name)
+    }
+}
+
+/// Contract versions store the major version in the high word and the minor version in
+/// the low word
+fn contract_version(version: u32) -> String {
+    format!("{}.{}", version >> 16, version & 0xFFFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn doc() {
+        let reader = winmd::TypeReader::get();
+        let def = reader.expect_type_def(("Windows.Foundation", "Uri"));
+
+        let class = match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Class(class) => class,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            class.doc().facts,
+            [
+                "Metadata: `Uri` in `Windows.Foundation`",
+                "Contract: `Windows.Foundation.UniversalApiContract` version 1.0",
+                "Runtime class: `Windows.Foundation.Uri`",
+                "Default interface: `Windows.Foundation.IUriRuntimeClass`",
+                "Default interface IID: `9e365e57-48b2-4160-956f-c7385120bbfc`",
+                "Agile: yes",
+                "Activatable: yes",
+            ]
+        );
+
+        let function = reader.expect_type(("Windows.Win32.Foundation", "GetLastError"));
+        let tokens = TypeDefinition::from_type_row(&function).gen();
+
+        assert!(tokens.as_str().contains(concat!(
+            r#"# [ doc =" * Metadata: `GetLastError` in `Windows.Win32.Foundation.Apis`" ] "#,
+            r#"# [ doc =" * DLL: `KERNEL32`" ] pub fn GetLastError"#
+        )));
+    }
+
+    #[test]
+    fn deprecated() {
+        let source = r#"
+            namespace Test
+            {
+                runtimeclass Widget
+                {
+                    [deprecated("Use \"Size\" instead", DeprecationType.Deprecate, 1)]
+                    Int32 Length();
+                };
+            }
+        "#;
+
+        let reader = winmd::idl::test_reader(source);
+
+        let def = reader.expect_type_def(("Test", "IWidget"));
+        let method = def.methods().next().unwrap();
+        let doc = Doc::from_method_def(&def, &method);
+
+        assert_eq!(doc.deprecated.as_deref(), Some(r#"Use "Size" instead"#));
+        assert!(doc
+            .gen()
+            .as_str()
+            .contains(r#"# [ deprecated ( note ="Use \"Size\" instead" ) ]"#));
+    }
+}
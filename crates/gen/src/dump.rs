@@ -0,0 +1,692 @@
+use crate::*;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// A readable description of a type definition as seen by the [`winmd::TypeReader`]
+///
+/// This is what the `winmd-dump` tool prints, either as C#-like declarations or as JSON.
+/// Fields and methods are described by the [`Type`]s that `build!` reads from their
+/// signatures, so nested types appear under their generated names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeDump {
+    pub namespace: &'static str,
+    pub name: &'static str,
+    pub category: &'static str,
+    pub flags: u32,
+    pub guid: Option<String>,
+    pub extends: Option<String>,
+    pub generics: Vec<&'static str>,
+    pub interfaces: Vec<String>,
+    /// The packing size and class size of a type with an explicit layout
+    pub layout: Option<(u32, u32)>,
+    pub attributes: Vec<AttributeDump>,
+    pub fields: Vec<FieldDump>,
+    pub methods: Vec<MethodDump>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeDump {
+    pub name: String,
+    /// The arguments in order, with named arguments prefixed by their name
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDump {
+    pub name: &'static str,
+    pub flags: u32,
+    pub ty: String,
+    pub constant: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodDump {
+    pub name: &'static str,
+    pub flags: u32,
+    pub return_type: String,
+    pub params: Vec<ParamDump>,
+    /// The DLL that a function is imported from
+    pub dll: Option<&'static str>,
+    pub attributes: Vec<AttributeDump>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamDump {
+    pub name: String,
+    pub flags: u32,
+    pub ty: String,
+}
+
+impl TypeDump {
+    pub fn new(def: &winmd::TypeDef) -> Self {
+        let (namespace, name) = def.name();
+
+        let category = match def.category() {
+            winmd::TypeCategory::Interface => "interface",
+            winmd::TypeCategory::Class => "class",
+            winmd::TypeCategory::Enum => "enum",
+            winmd::TypeCategory::Struct => "struct",
+            winmd::TypeCategory::Delegate => "delegate",
+            winmd::TypeCategory::Attribute => "attribute",
+            winmd::TypeCategory::Contract => "contract",
+        };
+
+        let generics: Vec<_> = def.generics().map(|param| param.name()).collect();
+        let kinds: Vec<_> = generics
+            .iter()
+            .map(|generic| TypeKind::Generic(generic))
+            .collect();
+
+        // The base types are `System` types that aren't part of Windows metadata and so are
+        // only named.
+        let extends = if def.flags().interface() {
+            None
+        } else {
+            let (namespace, name) = def.extends().name();
+            Some(format!("{}.{}", namespace, name))
+        };
+
+        let interfaces = def
+            .interfaces()
+            .map(|interface| {
+                name_text(&TypeName::from_type_def_or_ref(
+                    &interface.interface(),
+                    &kinds,
+                    namespace,
+                ))
+            })
+            .collect();
+
+        let layout = def
+            .class_layout()
+            .map(|layout| (layout.packing_size(), layout.class_size()));
+
+        // The members of attribute types refer to `System` types such as `System.Type` that
+        // are not part of Windows metadata and so cannot be decoded.
+        let (fields, methods) = if category == "attribute" {
+            (Vec::new(), Vec::new())
+        } else {
+            let fields = def
+                .fields()
+                .map(|field| FieldDump {
+                    name: field.name(),
+                    flags: field.flags().0,
+                    ty: type_text(&Type::from_field(def, &field, namespace)),
+                    constant: field
+                        .constant()
+                        .map(|constant| constant_text(&constant.value())),
+                })
+                .collect();
+
+            let methods = def
+                .methods()
+                .map(|method| MethodDump::new(&method, &kinds, namespace))
+                .collect();
+
+            (fields, methods)
+        };
+
+        Self {
+            namespace,
+            name,
+            category,
+            flags: def.flags().0,
+            guid: guid(def),
+            extends,
+            generics,
+            interfaces,
+            layout,
+            attributes: def
+                .attributes()
+                .map(|attribute| AttributeDump::new(&attribute))
+                .collect(),
+            fields,
+            methods,
+        }
+    }
+
+    /// Formats the type as a C#-like declaration
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        writeln!(text, "// flags: 0x{:X}", self.flags).unwrap();
+
+        if let Some(guid) = &self.guid {
+            writeln!(text, "// guid: {{{}}}", guid).unwrap();
+        }
+
+        if let Some((packing, size)) = self.layout {
+            writeln!(text, "// layout: pack {}, size {}", packing, size).unwrap();
+        }
+
+        for attribute in &self.attributes {
+            writeln!(text, "{}", attribute.to_text()).unwrap();
+        }
+
+        write!(text, "{} {}.{}", self.category, self.namespace, self.name).unwrap();
+
+        if !self.generics.is_empty() {
+            write!(text, "<{}>", self.generics.join(", ")).unwrap();
+        }
+
+        let bases: Vec<_> = self
+            .extends
+            .iter()
+            .chain(self.interfaces.iter())
+            .map(|base| base.as_str())
+            .collect();
+
+        if !bases.is_empty() {
+            write!(text, " : {}", bases.join(", ")).unwrap();
+        }
+
+        text.push_str("\n{\n");
+
+        for field in &self.fields {
+            write!(text, "    {} {}", field.ty, field.name).unwrap();
+
+            if let Some(constant) = &field.constant {
+                write!(text, " = {}", constant).unwrap();
+            }
+
+            writeln!(text, "; // 0x{:X}", field.flags).unwrap();
+        }
+
+        for method in &self.methods {
+            for attribute in &method.attributes {
+                writeln!(text, "    {}", attribute.to_text()).unwrap();
+            }
+
+            if let Some(dll) = method.dll {
+                writeln!(text, "    [DllImport(\"{}\")]", dll).unwrap();
+            }
+
+            let params: Vec<_> = method
+                .params
+                .iter()
+                .map(|param| format!("{} {}", param.ty, param.name))
+                .collect();
+
+            writeln!(
+                text,
+                "    {} {}({}); // 0x{:X}",
+                method.return_type,
+                method.name,
+                params.join(", "),
+                method.flags
+            )
+            .unwrap();
+        }
+
+        text.push_str("}\n");
+        text
+    }
+
+    /// Formats the type as a JSON object
+    pub fn to_json(&self) -> String {
+        let attributes = |attributes: &[AttributeDump]| {
+            json_array(attributes.iter().map(|attribute| {
+                format!(
+                    "{{\"name\":{},\"args\":{}}}",
+                    json_string(&attribute.name),
+                    json_array(attribute.args.iter().map(|arg| json_string(arg)))
+                )
+            }))
+        };
+
+        let fields = json_array(self.fields.iter().map(|field| {
+            format!(
+                "{{\"name\":{},\"flags\":{},\"type\":{},\"constant\":{}}}",
+                json_string(field.name),
+                field.flags,
+                json_string(&field.ty),
+                json_option(field.constant.as_deref())
+            )
+        }));
+
+        let methods = json_array(self.methods.iter().map(|method| {
+            let params = json_array(method.params.iter().map(|param| {
+                format!(
+                    "{{\"name\":{},\"flags\":{},\"type\":{}}}",
+                    json_string(&param.name),
+                    param.flags,
+                    json_string(&param.ty)
+                )
+            }));
+
+            format!(
+                "{{\"name\":{},\"flags\":{},\"return_type\":{},\"params\":{},\"dll\":{},\"attributes\":{}}}",
+                json_string(method.name),
+                method.flags,
+                json_string(&method.return_type),
+                params,
+                json_option(method.dll),
+                attributes(&method.attributes)
+            )
+        }));
+
+        let layout = match self.layout {
+            Some((packing, size)) => format!("{{\"packing\":{},\"size\":{}}}", packing, size),
+            None => "null".to_string(),
+        };
+
+        format!(
+            "{{\"namespace\":{},\"name\":{},\"category\":{},\"flags\":{},\"guid\":{},\"extends\":{},\"generics\":{},\"interfaces\":{},\"layout\":{},\"attributes\":{},\"fields\":{},\"methods\":{}}}",
+            json_string(self.namespace),
+            json_string(self.name),
+            json_string(self.category),
+            self.flags,
+            json_option(self.guid.as_deref()),
+            json_option(self.extends.as_deref()),
+            json_array(self.generics.iter().map(|generic| json_string(generic))),
+            json_array(self.interfaces.iter().map(|interface| json_string(interface))),
+            layout,
+            attributes(&self.attributes),
+            fields,
+            methods
+        )
+    }
+}
+
+impl AttributeDump {
+    pub fn new(attribute: &winmd::Attribute) -> Self {
+        let (namespace, name) = attribute.name();
+
+        let args = match attribute.try_args() {
+            Ok(args) => args
+                .iter()
+                .map(|(name, arg)| {
+                    if name.is_empty() {
+                        arg_text(arg)
+                    } else {
+                        format!("{} = {}", name, arg_text(arg))
+                    }
+                })
+                .collect(),
+            Err(error) => vec![format!("/* {} */", error)],
+        };
+
+        Self {
+            name: format!("{}.{}", namespace, name),
+            args,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        format!("[{}({})]", self.name, self.args.join(", "))
+    }
+}
+
+impl MethodDump {
+    pub fn new(
+        method: &winmd::MethodDef,
+        generics: &[TypeKind],
+        calling_namespace: &'static str,
+    ) -> Self {
+        let signature = Signature::new(method, generics, calling_namespace);
+
+        let params = signature
+            .params
+            .iter()
+            .map(|param| {
+                let ty = type_text(param);
+
+                ParamDump {
+                    name: param.param.map_or("", |param| param.name()).to_string(),
+                    flags: param.param.map_or(0, |param| param.flags().0),
+                    ty: if param.by_ref && !param.is_input {
+                        format!("out {}", ty)
+                    } else if param.by_ref {
+                        format!("ref {}", ty)
+                    } else {
+                        ty
+                    },
+                }
+            })
+            .collect();
+
+        Self {
+            name: method.name(),
+            flags: method.flags().0,
+            return_type: signature
+                .return_type
+                .as_ref()
+                .map_or_else(|| "void".to_string(), type_text),
+            params,
+            dll: method.impl_map().map(|impl_map| impl_map.scope().name()),
+            attributes: method
+                .attributes()
+                .map(|attribute| AttributeDump::new(&attribute))
+                .collect(),
+        }
+    }
+}
+
+/// Finds the type definitions that match any of the filters, which are either namespaces or
+/// fully qualified type names, or all type definitions if there are no filters
+///
+/// Every per-architecture variant of a type is included along with the types nested within
+/// it. A filter that matches no type is an error.
+pub fn dump_types(
+    reader: &'static winmd::TypeReader,
+    filters: &[String],
+) -> Result<Vec<TypeDump>, String> {
+    match find_types(reader, filters) {
+        (_, Some(filter)) => Err(format!("no types match `{}`", filter)),
+        (dumps, None) => Ok(dumps),
+    }
+}
+
+/// Finds the types that match the filters along with the first filter that matches nothing
+#[allow(clippy::mutable_key_type)]
+pub(crate) fn find_types<'a>(
+    reader: &'static winmd::TypeReader,
+    filters: &'a [String],
+) -> (Vec<TypeDump>, Option<&'a String>) {
+    let mut defs = BTreeSet::new();
+
+    // Skips the `<Module>` type, which has no namespace and no base type.
+    for namespace in reader
+        .namespaces()
+        .filter(|namespace| !namespace.is_empty())
+    {
+        for t in reader.namespace_types(namespace) {
+            match t {
+                winmd::Type::TypeDef(def) => {
+                    defs.extend(reader.type_variants(def.name()).filter_map(|t| match t {
+                        winmd::Type::TypeDef(def) => Some(def),
+                        _ => None,
+                    }));
+                }
+                winmd::Type::MethodDef((def, _)) | winmd::Type::Field((def, _)) => {
+                    defs.insert(def);
+                }
+            }
+        }
+    }
+
+    let mut matched = vec![false; filters.len()];
+    let mut dumps = Vec::new();
+
+    for def in &defs {
+        let (namespace, name) = def.name();
+        let mut is_match = filters.is_empty();
+
+        for (filter, matched) in filters.iter().zip(&mut matched) {
+            if namespace == filter
+                || namespace.starts_with(&format!("{}.", filter))
+                || format!("{}.{}", namespace, name) == *filter
+            {
+                *matched = true;
+                is_match = true;
+            }
+        }
+
+        if is_match {
+            dump_nested(def, &mut dumps);
+        }
+    }
+
+    dumps.sort_by(|a, b| (a.namespace, a.name).cmp(&(b.namespace, b.name)));
+
+    let unmatched = filters
+        .iter()
+        .zip(&matched)
+        .find(|(_, matched)| !**matched)
+        .map(|(filter, _)| filter);
+
+    (dumps, unmatched)
+}
+
+fn dump_nested(def: &winmd::TypeDef, dumps: &mut Vec<TypeDump>) {
+    dumps.push(TypeDump::new(def));
+
+    for nested in def.nested_types() {
+        dump_nested(&nested, dumps);
+    }
+}
+
+fn guid(def: &winmd::TypeDef) -> Option<String> {
+    match TypeGuid::from_type_def(def) {
+        guid if guid == TypeGuid::default() => None,
+        guid => {
+            let mut bytes = Vec::with_capacity(16);
+
+            for constant in &guid.0 {
+                match constant {
+                    GuidConstant::U32(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+                    GuidConstant::U16(value) => bytes.extend_from_slice(&value.to_le_bytes()),
+                    GuidConstant::U8(value) => bytes.push(*value),
+                }
+            }
+
+            let mut array = [0; 16];
+            array.copy_from_slice(&bytes);
+            Some(winmd::Guid(array).to_string())
+        }
+    }
+}
+
+/// Describes a type the way `build!` reads it, with fixed size arrays that are parameters
+/// decayed to pointers
+fn type_text(t: &Type) -> String {
+    let mut text = String::new();
+
+    if t.is_const {
+        text.push_str("const ");
+    }
+
+    text.push_str(&kind_text(&t.kind));
+
+    for _ in 0..t.pointers {
+        text.push('*');
+    }
+
+    if t.is_array {
+        text.push_str("[]");
+    }
+
+    if let Some(len) = t.array {
+        write!(text, "[{}]", len).unwrap();
+    }
+
+    text
+}
+
+fn kind_text(kind: &TypeKind) -> String {
+    match kind {
+        TypeKind::Void => "void".to_string(),
+        TypeKind::Bool => "Boolean".to_string(),
+        TypeKind::Char => "Char16".to_string(),
+        TypeKind::I8 => "Int8".to_string(),
+        TypeKind::U8 => "UInt8".to_string(),
+        TypeKind::I16 => "Int16".to_string(),
+        TypeKind::U16 => "UInt16".to_string(),
+        TypeKind::I32 => "Int32".to_string(),
+        TypeKind::U32 => "UInt32".to_string(),
+        TypeKind::I64 => "Int64".to_string(),
+        TypeKind::U64 => "UInt64".to_string(),
+        TypeKind::F32 => "Single".to_string(),
+        TypeKind::F64 => "Double".to_string(),
+        TypeKind::ISize => "isize".to_string(),
+        TypeKind::USize => "usize".to_string(),
+        TypeKind::String => "String".to_string(),
+        TypeKind::Object => "Object".to_string(),
+        TypeKind::Guid => "Guid".to_string(),
+        TypeKind::IUnknown => "IUnknown".to_string(),
+        TypeKind::ErrorCode => "HRESULT".to_string(),
+        TypeKind::Bool32 => "BOOL".to_string(),
+        TypeKind::Matrix3x2 => "Matrix3x2".to_string(),
+        TypeKind::Class(name)
+        | TypeKind::Interface(name)
+        | TypeKind::Enum(name)
+        | TypeKind::Struct(name)
+        | TypeKind::Delegate(name) => name_text(name),
+        TypeKind::Generic(name) => name.to_string(),
+        TypeKind::NotYetSupported => "?".to_string(),
+    }
+}
+
+fn name_text(name: &TypeName) -> String {
+    let mut text = format!("{}.{}", name.namespace, name.name);
+
+    if !name.generics.is_empty() {
+        let generics: Vec<_> = name.generics.iter().map(kind_text).collect();
+        write!(text, "<{}>", generics.join(", ")).unwrap();
+    }
+
+    text
+}
+
+fn constant_text(value: &winmd::ConstantValue) -> String {
+    match value {
+        winmd::ConstantValue::U8(value) => value.to_string(),
+        winmd::ConstantValue::I8(value) => value.to_string(),
+        winmd::ConstantValue::U16(value) => value.to_string(),
+        winmd::ConstantValue::I16(value) => value.to_string(),
+        winmd::ConstantValue::U32(value) => value.to_string(),
+        winmd::ConstantValue::I32(value) => value.to_string(),
+        winmd::ConstantValue::U64(value) => value.to_string(),
+        winmd::ConstantValue::I64(value) => value.to_string(),
+        winmd::ConstantValue::F32(value) => value.to_string(),
+        winmd::ConstantValue::F64(value) => value.to_string(),
+        winmd::ConstantValue::String(value) => format!("{:?}", value),
+    }
+}
+
+fn arg_text(arg: &winmd::AttributeArg) -> String {
+    match arg {
+        winmd::AttributeArg::Bool(value) => value.to_string(),
+        winmd::AttributeArg::Char(value) => format!("{:?}", value),
+        winmd::AttributeArg::I8(value) => value.to_string(),
+        winmd::AttributeArg::U8(value) => value.to_string(),
+        winmd::AttributeArg::I16(value) => value.to_string(),
+        winmd::AttributeArg::U16(value) => value.to_string(),
+        winmd::AttributeArg::I32(value) => value.to_string(),
+        winmd::AttributeArg::U32(value) => value.to_string(),
+        winmd::AttributeArg::I64(value) => value.to_string(),
+        winmd::AttributeArg::U64(value) => value.to_string(),
+        winmd::AttributeArg::F32(value) => value.to_string(),
+        winmd::AttributeArg::F64(value) => value.to_string(),
+        winmd::AttributeArg::String(value) => format!("{:?}", value),
+        winmd::AttributeArg::TypeDef(def) => {
+            let (namespace, name) = def.name();
+            format!("typeof({}.{})", namespace, name)
+        }
+        winmd::AttributeArg::TypeName(name) => format!("typeof({})", name),
+        winmd::AttributeArg::Enum(def, value) => {
+            let (namespace, name) = def.name();
+            format!("({}.{}){}", namespace, name, arg_text(value))
+        }
+        winmd::AttributeArg::Array(values) => {
+            let values: Vec<_> = values.iter().map(arg_text).collect();
+            format!("{{ {} }}", values.join(", "))
+        }
+        winmd::AttributeArg::Null => "null".to_string(),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+fn json_option(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_string(), json_string)
+}
+
+fn json_array<I: Iterator<Item = String>>(values: I) -> String {
+    format!("[{}]", values.collect::<Vec<_>>().join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn dump_uri() {
+        let reader = winmd::TypeReader::get();
+        let dumps = dump_types(reader, &["Windows.Foundation.Uri".to_string()]).unwrap();
+        assert_eq!(dumps.len(), 1);
+
+        let uri = &dumps[0];
+        assert_eq!(uri.category, "class");
+        assert!(uri
+            .interfaces
+            .contains(&"Windows.Foundation.IUriRuntimeClass".to_string()));
+
+        let text = uri.to_text();
+        assert!(text.contains("class Windows.Foundation.Uri : System.Object"));
+        assert!(text.contains("(Windows.Foundation.Metadata.MarshalingType)2"));
+
+        let json = uri.to_json();
+        assert!(json.starts_with("{\"namespace\":\"Windows.Foundation\",\"name\":\"Uri\""));
+    }
+
+    #[test]
+    fn dump_nested() {
+        let reader = winmd::TypeReader::get();
+        let dumps = dump_types(
+            reader,
+            &["Windows.Win32.Foundation.LARGE_INTEGER".to_string()],
+        )
+        .unwrap();
+
+        let names: Vec<_> = dumps.iter().map(|dump| dump.name).collect();
+        assert_eq!(
+            names,
+            ["LARGE_INTEGER", "LARGE_INTEGER_0", "LARGE_INTEGER_1"]
+        );
+
+        let field = dumps[0]
+            .fields
+            .iter()
+            .find(|field| field.name == "u")
+            .unwrap();
+        assert_eq!(field.ty, "Windows.Win32.Foundation.LARGE_INTEGER_1");
+    }
+
+    #[test]
+    fn dump_unmatched() {
+        let reader = winmd::TypeReader::get();
+        let filters = [
+            "Windows.Foundation.Uri".to_string(),
+            "Windows.Foundation.Missing".to_string(),
+        ];
+
+        assert_eq!(
+            dump_types(reader, &filters),
+            Err("no types match `Windows.Foundation.Missing`".to_string())
+        );
+    }
+
+    #[test]
+    fn dump_function() {
+        let reader = winmd::TypeReader::get();
+        let dumps = dump_types(reader, &["Windows.Win32.Foundation.Apis".to_string()]).unwrap();
+
+        let method = dumps
+            .iter()
+            .flat_map(|dump| dump.methods.iter())
+            .find(|method| method.name == "GetLastError")
+            .unwrap();
+
+        assert_eq!(method.dll, Some("KERNEL32"));
+        assert_eq!(method.return_type, "Windows.Win32.Foundation.WIN32_ERROR");
+    }
+}
@@ -71,6 +71,7 @@ impl Enum {
 
     pub fn gen(&self) -> TokenStream {
         let name = self.name.gen();
+        let doc = Doc::from_type_def(&self.name.def).gen();
 
         let (underlying_type, bitwise) = match self.underlying_type {
             winmd::ElementType::I32 => (format_ident!("i32"), TokenStream::new()),
@@ -122,6 +123,7 @@ impl Enum {
         };
 
         quote! {
+            #doc
             #[allow(non_camel_case_types)]
             #[derive(PartialEq, Eq)]
             #[repr(transparent)]
@@ -33,14 +33,21 @@ impl Function {
 
         let mut link = self.signature.method.impl_map().unwrap().scope().name();
 
+        let mut doc = Doc::from_method_def(&self.name.def, &self.signature.method);
+        doc.push("DLL", code(link));
+        let doc = doc.gen();
+        let architectures = gen_architectures(method_architectures(&self.signature.method));
+
         // TODO: workaround for https://github.com/microsoft/windows-rs/issues/463
         if link.contains("-ms-win-") || link == "D3DCOMPILER_47" {
             link = "onecoreuap";
         }
 
         quote! {
+            #architectures
             #[link(name = #link)]
             extern "system" {
+                #doc
                 pub fn #name(#(#params),*) #return_type;
             }
         }
@@ -54,7 +54,14 @@ impl Interface {
             .unwrap()
     }
 
+    pub fn doc(&self) -> Doc {
+        let mut doc = Doc::from_type_def(&self.name.def);
+        doc.push_guid("IID", &self.name, &self.guid);
+        doc
+    }
+
     pub fn gen(&self) -> TokenStream {
+        let doc = self.doc().gen();
         let definition = self.name.gen_definition();
         let abi_definition = self.name.gen_abi_definition();
         let name = self.name.gen();
@@ -85,6 +92,7 @@ impl Interface {
         let (async_get, future) = gen_async(&self.name, &self.interfaces);
 
         quote! {
+            #doc
             #[repr(transparent)]
             pub struct #definition(::windows::Object, #phantoms) where #constraints;
             impl<#constraints> ::std::clone::Clone for #name {
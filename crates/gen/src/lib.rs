@@ -1,8 +1,14 @@
+mod architecture;
+mod build_limits;
+mod build_tree;
 mod callback;
 mod class;
 mod com_interface;
 mod constant;
 mod delegate;
+mod diff;
+mod doc;
+mod dump;
 mod r#enum;
 mod format_ident;
 mod function;
@@ -11,6 +17,7 @@ mod hex_reader;
 mod interface;
 mod interface_kind;
 mod iterator;
+mod manifest;
 mod method;
 mod method_kind;
 mod namespace;
@@ -27,11 +34,17 @@ mod type_namespaces;
 mod type_tree;
 pub mod winmd;
 
+pub use architecture::*;
+pub use build_limits::*;
+pub use build_tree::*;
 pub use callback::*;
 pub use class::*;
 pub use com_interface::*;
 pub use constant::*;
 pub use delegate::*;
+pub use diff::*;
+pub use doc::*;
+pub use dump::*;
 pub use format_ident::*;
 pub use function::*;
 pub use futures::*;
@@ -39,6 +52,7 @@ pub use hex_reader::*;
 pub use interface::*;
 pub use interface_kind::*;
 pub use iterator::*;
+pub use manifest::*;
 pub use method::*;
 pub use method_kind::*;
 pub use namespace::*;
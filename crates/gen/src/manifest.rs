@@ -0,0 +1,288 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+
+/// A manifest listing the types and options of the bindings, such as `windows.toml`
+///
+/// The manifest is written in TOML and holds the same types and options as the arguments of
+/// `build!`:
+///
+/// ```toml
+/// # Types are written as paths, just like the arguments of `build!`
+/// types = [
+///     "windows::foundation::Uri",
+///     "windows::ui::xaml::*",
+///     "windows::win32::system::threading::{Create*W, SetEvent as set_event}",
+/// ]
+/// exclude = ["windows::ui::xaml::IApplicationOverrides"]
+/// winmd_paths = ["metadata"]
+/// dll_paths = ["bin"]
+/// minimum_os_version = 6.3
+///
+/// [minimum_contracts]
+/// "Windows.Foundation.UniversalApiContract" = 7
+///
+/// [renames]
+/// "windows::ui::Colors" = "UiColors"
+/// ```
+///
+/// Paths are relative to the directory of the manifest.
+#[derive(Debug, Default)]
+pub struct Manifest {
+    pub path: PathBuf,
+    pub types: Vec<ManifestValue>,
+    pub exclude: Vec<ManifestValue>,
+    /// The types to rename along with their aliases
+    pub renames: Vec<(ManifestValue, String)>,
+    pub winmd_paths: Vec<PathBuf>,
+    pub dll_paths: Vec<PathBuf>,
+    /// The full names of contracts along with their minimum versions, as major and minor
+    pub minimum_contracts: Vec<(ManifestValue, (u32, u32))>,
+    /// The minimum OS version, as major and minor
+    pub minimum_os_version: Option<(u32, u32)>,
+}
+
+/// A string in a manifest along with the line it is on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestValue {
+    pub line: usize,
+    pub value: String,
+}
+
+/// An error in a manifest, on the given line if it isn't zero
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestError {
+    pub path: PathBuf,
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.path.display(), self.message)
+        } else {
+            write!(f, "{}:{}: {}", self.path.display(), self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+impl Manifest {
+    /// Reads the manifest at the given path
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+
+        let text = std::fs::read_to_string(path).map_err(|error| ManifestError {
+            path: path.to_path_buf(),
+            line: 0,
+            message: error.to_string(),
+        })?;
+
+        Self::parse(path, &text)
+    }
+
+    /// Parses the text of a manifest, where `path` locates the manifest for errors and
+    /// relative paths
+    pub fn parse<P: AsRef<Path>>(path: P, text: &str) -> Result<Self, ManifestError> {
+        let mut manifest = Self {
+            path: path.as_ref().to_path_buf(),
+            ..Default::default()
+        };
+
+        let document: Document = toml::from_str(text).map_err(|error| {
+            let line = error.line_col().map_or(0, |(line, _)| line + 1);
+            let message = error.to_string();
+
+            // The line is reported separately so the location is dropped from the message.
+            let message = match message.rfind(" at line ") {
+                Some(pos) if line != 0 => message[..pos].to_string(),
+                _ => message,
+            };
+
+            manifest.error(line, message)
+        })?;
+
+        let value = |span: Spanned<String>| ManifestValue {
+            line: line_of(text, span.start()),
+            value: span.into_inner(),
+        };
+
+        let version = |key: &str, span: &Spanned<toml::Value>| {
+            // Numbers are read from the text since `6.10` and `6.1` are the same float. The span
+            // of a float only covers its fraction so it is widened to the whole number.
+            let version = match span.get_ref() {
+                toml::Value::String(text) => crate::parse_version_text(text),
+                toml::Value::Integer(_) | toml::Value::Float(_) => {
+                    let number = &text[..span.end()];
+                    let start = number
+                        .rfind(|c: char| !c.is_ascii_digit() && c != '.')
+                        .map_or(0, |pos| pos + 1);
+                    crate::parse_version_text(&number[start..])
+                }
+                _ => None,
+            };
+
+            version.ok_or_else(|| {
+                manifest.error(
+                    line_of(text, span.start()),
+                    format!("expected a version such as `7` or `6.3` for `{}`", key),
+                )
+            })
+        };
+
+        let minimum_os_version = match &document.minimum_os_version {
+            Some(span) => Some(version("minimum_os_version", span)?),
+            None => None,
+        };
+
+        let mut minimum_contracts = Vec::new();
+
+        for (key, span) in &document.minimum_contracts {
+            let version = version(key, span)?;
+            let key = ManifestValue {
+                line: line_of(text, span.start()),
+                value: key.clone(),
+            };
+            minimum_contracts.push((key, version));
+        }
+
+        let renames = document.renames.into_iter().map(|(key, alias)| {
+            let key = ManifestValue {
+                line: line_of(text, alias.start()),
+                value: key,
+            };
+            (key, alias.into_inner())
+        });
+
+        manifest.types = document.types.into_iter().map(value).collect();
+        manifest.exclude = document.exclude.into_iter().map(value).collect();
+        manifest.renames = renames.collect();
+        manifest.minimum_contracts = minimum_contracts;
+        manifest.minimum_os_version = minimum_os_version;
+
+        // Tables are sorted by key so their entries are put back in the order of the text.
+        manifest.renames.sort_by_key(|(key, _)| key.line);
+        manifest.minimum_contracts.sort_by_key(|(key, _)| key.line);
+
+        let winmd_paths: Vec<_> = document.winmd_paths.into_iter().map(value).collect();
+        let dll_paths: Vec<_> = document.dll_paths.into_iter().map(value).collect();
+        manifest.winmd_paths = manifest.paths(winmd_paths, true)?;
+        manifest.dll_paths = manifest.paths(dll_paths, false)?;
+
+        Ok(manifest)
+    }
+
+    /// Creates an error on the given line of the manifest
+    pub fn error<T: ToString>(&self, line: usize, message: T) -> ManifestError {
+        ManifestError {
+            path: self.path.clone(),
+            line,
+            message: message.to_string(),
+        }
+    }
+
+    /// Resolves paths relative to the directory of the manifest
+    fn paths(
+        &self,
+        values: Vec<ManifestValue>,
+        must_exist: bool,
+    ) -> Result<Vec<PathBuf>, ManifestError> {
+        let dir = self.path.parent().unwrap_or_else(|| Path::new(""));
+
+        values
+            .into_iter()
+            .map(|value| {
+                let path = dir.join(&value.value);
+
+                if must_exist && !path.exists() {
+                    Err(self.error(value.line, format!("'{}' does not exist", path.display())))
+                } else {
+                    Ok(path)
+                }
+            })
+            .collect()
+    }
+}
+
+/// The manifest as written, along with the positions of its values
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Document {
+    #[serde(default)]
+    types: Vec<Spanned<String>>,
+    #[serde(default)]
+    exclude: Vec<Spanned<String>>,
+    #[serde(default)]
+    winmd_paths: Vec<Spanned<String>>,
+    #[serde(default)]
+    dll_paths: Vec<Spanned<String>>,
+    /// Versions may be written as numbers, e.g. `6.3`, or as strings, e.g. `"6.3"`
+    minimum_os_version: Option<Spanned<toml::Value>>,
+    #[serde(default)]
+    minimum_contracts: BTreeMap<String, Spanned<toml::Value>>,
+    #[serde(default)]
+    renames: BTreeMap<String, Spanned<String>>,
+}
+
+/// The line, counting from one, of the given byte offset in the text
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest() {
+        let manifest = Manifest::parse(
+            "windows.toml",
+            r#"
+            # Comments are ignored
+            types = [
+                "windows::foundation::Uri", # along with trailing ones
+                "windows::ui::*",
+            ]
+            exclude = ["windows::ui::Colors"]
+            minimum_os_version = 6.3
+
+            [minimum_contracts]
+            "Windows.Foundation.UniversalApiContract" = "7"
+
+            [renames]
+            "windows::foundation::Uri" = "WindowsUri"
+            "#,
+        )
+        .unwrap();
+
+        let lines: Vec<_> = manifest.types.iter().map(|t| t.line).collect();
+        assert_eq!(lines, [4, 5]);
+        assert_eq!(manifest.types[1].value, "windows::ui::*");
+        assert_eq!(manifest.exclude[0].line, 7);
+        assert_eq!(manifest.minimum_os_version, Some((6, 3)));
+        assert_eq!(manifest.minimum_contracts[0].0.line, 11);
+        assert_eq!(manifest.minimum_contracts[0].1, (7, 0));
+        assert_eq!(manifest.renames[0].1, "WindowsUri");
+
+        let error = Manifest::parse("windows.toml", "types = []\n\n[renames]\n\"a\" = 7\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "windows.toml:4: invalid type: integer `7`, expected a string for key `renames.a`"
+        );
+
+        let error = Manifest::parse("windows.toml", "types = [\n  \"a\"\n  \"b\"\n]\n")
+            .err()
+            .unwrap();
+        assert_eq!(error.line, 3);
+
+        let error = Manifest::parse("windows.toml", "typos = []").err().unwrap();
+        assert!(error
+            .to_string()
+            .starts_with("windows.toml:1: unknown field `typos`"));
+    }
+}
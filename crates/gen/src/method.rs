@@ -17,23 +17,20 @@ impl Method {
         generics: &[TypeKind],
         calling_namespace: &'static str,
     ) -> Method {
-        let name = if method.flags().special() {
-            let name = method.name();
-
-            if name.starts_with("get") {
-                method_to_snake(&name[4..], MethodKind::Get)
-            } else if name.starts_with("put") {
-                method_to_snake(&name[4..], MethodKind::Set)
-            } else if name.starts_with("add") {
-                method_to_snake(&name[4..], MethodKind::Add)
-            } else if name.starts_with("remove") {
-                method_to_snake(&name[7..], MethodKind::Remove)
-            } else {
-                // A delegate's 'Invoke' method is "special" but lacks a preamble.
-                "invoke".to_owned()
-            }
-        } else {
-            Method::name(method)
+        // Property and event accessors are named after the property or event they belong to.
+        let kind = match method.category() {
+            winmd::MethodCategory::Get => Some(MethodKind::Get),
+            winmd::MethodCategory::Set => Some(MethodKind::Set),
+            winmd::MethodCategory::Add => Some(MethodKind::Add),
+            winmd::MethodCategory::Remove => Some(MethodKind::Remove),
+            winmd::MethodCategory::Normal => None,
+        };
+
+        let name = match (kind, method.semantics()) {
+            (Some(kind), Some(semantics)) => method_to_snake(semantics.association().name(), kind),
+            // A delegate's 'Invoke' method is "special" but isn't associated with a property or event.
+            _ if method.flags().special() => "invoke".to_owned(),
+            _ => Method::name(method),
         };
 
         let signature = Signature::new(method, generics, calling_namespace);
@@ -127,6 +124,7 @@ impl Method {
         };
 
         let vtable_offset = Literal::u32_unsuffixed(self.vtable_offset);
+        let doc = Doc::from_method_def(&interface.def, &self.signature.method).gen();
 
         let vcall = if let Some(return_type) = &self.signature.return_type {
             let return_arg = param_gen_abi_return_arg(return_type);
@@ -152,6 +150,7 @@ impl Method {
 
         match kind {
             InterfaceKind::Default => quote! {
+                #doc
                 pub fn #method_name<#constraints>(&self, #params) -> ::windows::Result<#return_type_tokens> {
                     let this = self;
                     unsafe {
@@ -162,6 +161,7 @@ impl Method {
             InterfaceKind::NonDefault | InterfaceKind::Overrides => {
                 let interface = interface.gen();
                 quote! {
+                    #doc
                     pub fn #method_name<#constraints>(&self, #params) -> ::windows::Result<#return_type_tokens> {
                         let this = &::windows::Interface::cast::<#interface>(self).unwrap();
                         unsafe {
@@ -173,6 +173,7 @@ impl Method {
             InterfaceKind::Statics | InterfaceKind::Composable => {
                 let interface = interface.gen();
                 quote! {
+                    #doc
                     pub fn #method_name<#constraints>(#params) -> ::windows::Result<#return_type_tokens> {
                         Self::#interface(|this| unsafe { #vcall })
                     }
@@ -541,4 +542,28 @@ mod tests {
 
         assert!(token.runtime_name() == "Windows.Foundation.EventRegistrationToken");
     }
+
+    #[test]
+    fn test_semantics() {
+        let reader = winmd::TypeReader::get();
+        let def = reader.expect_type_def(("Windows.Foundation.Collections", "IObservableMap`2"));
+
+        let event = def.events().next().unwrap();
+        assert!(event.name() == "MapChanged");
+        assert!(event.add_method().unwrap().category() == winmd::MethodCategory::Add);
+        assert!(event.remove_method().unwrap().category() == winmd::MethodCategory::Remove);
+        assert!(def.properties().next().is_none());
+
+        let def = reader.expect_type_def(("Windows.Foundation", "IUriRuntimeClass"));
+
+        let property = def
+            .properties()
+            .find(|property| property.name() == "AbsoluteUri")
+            .unwrap();
+
+        let getter = property.getter().unwrap();
+        assert!(getter.name() == "get_AbsoluteUri");
+        assert!(getter.category() == winmd::MethodCategory::Get);
+        assert!(property.setter().is_none());
+    }
 }
@@ -8,6 +8,12 @@ pub struct Struct {
     pub fields: Vec<(String, Type)>,
     pub signature: String,
     pub is_typedef: bool,
+    pub is_union: bool,
+    /// The length of the bytes that stand in for the fields of an explicit layout with fields
+    /// at different offsets, which Rust can't express
+    pub opaque_len: Option<TokenStream>,
+    pub packing: Option<u32>,
+    pub size: Option<u32>,
     pub guid: TypeGuid,
 }
 
@@ -29,7 +35,7 @@ impl Struct {
                 continue;
             }
 
-            let mut t = Type::from_field(&field, &name.namespace);
+            let mut t = Type::from_field(&name.def, &field, &name.namespace);
 
             // TODO: workaround for https://github.com/microsoft/win32metadata/issues/132
             if let TypeKind::Delegate(_) = &t.kind {
@@ -82,11 +88,51 @@ impl Struct {
             .def
             .has_attribute(("Windows.Win32.Interop", "NativeTypedefAttribute"));
 
+        let is_union = name.def.is_union();
+
+        let layout = name.def.class_layout();
+
+        let opaque_len = if name.def.flags().explicit() && !is_union {
+            match layout.as_ref().map(|layout| layout.class_size()) {
+                Some(size) if size != 0 => {
+                    let size = Literal::u32_unsuffixed(size);
+                    Some(quote! { #size })
+                }
+                // Without a recorded size the bytes extend to the end of the last field.
+                _ => name
+                    .def
+                    .fields()
+                    .filter(|field| !field.flags().literal())
+                    .filter_map(|field| Some((field.layout()?.offset(), field)))
+                    .max_by_key(|(offset, _)| *offset)
+                    .map(|(offset, field)| {
+                        let offset = Literal::u32_unsuffixed(offset);
+                        let abi = Type::from_field(&name.def, &field, name.namespace).gen_abi();
+                        quote! { #offset + ::std::mem::size_of::<#abi>() }
+                    }),
+            }
+        } else {
+            None
+        };
+
+        let packing = layout
+            .as_ref()
+            .map(|layout| layout.packing_size())
+            .filter(|packing| *packing != 0);
+
+        let size = layout
+            .map(|layout| layout.class_size())
+            .filter(|size| *size != 0);
+
         Self {
             name,
             fields,
             signature,
             is_typedef,
+            is_union,
+            opaque_len,
+            packing,
+            size,
             guid,
         }
     }
@@ -100,11 +146,20 @@ impl Struct {
 
     pub fn gen(&self) -> TokenStream {
         let name = self.name.gen();
+        let mut doc = Doc::from_type_def(&self.name.def);
+
+        // Every item generated for a per-architecture variant of a struct is limited to its
+        // architectures since the other variants share its name.
+        let cfg = gen_architectures(type_architectures(&self.name.def));
 
         if self.guid != TypeGuid::default() {
+            doc.push_guid("GUID", &self.name, &self.guid);
+            let doc = doc.gen();
             let guid = self.name.gen_guid(&self.guid);
 
             return quote! {
+                #doc
+                #cfg
                 pub const #name: ::windows::Guid = #guid;
             };
         }
@@ -112,6 +167,31 @@ impl Struct {
         // TODO: if the struct is blittable then don't generate a separate abi type.
         let abi_ident = format_ident!("{}_abi", self.name.name);
 
+        let constants = self.name.def.fields().filter_map(|field| {
+            if field.flags().literal() {
+                if let Some(constant) = field.constant() {
+                    let name = format_ident(field.name());
+                    let value = constant.value().gen();
+
+                    return Some(quote! {
+                        pub const #name: #value;
+                    });
+                }
+            }
+
+            None
+        });
+
+        let doc = doc.gen();
+
+        if self.is_union {
+            return self.gen_union(&name, &doc, &cfg, &abi_ident, constants);
+        }
+
+        if let Some(len) = &self.opaque_len {
+            return self.gen_opaque(&name, &doc, &cfg, &abi_ident, constants, len);
+        }
+
         let body = if self.is_typedef {
             let fields = self.fields.iter().map(|(_, kind)| {
                 let kind = kind.gen_field();
@@ -162,10 +242,34 @@ impl Struct {
             }
         };
 
+        // Fields of a packed struct may be unaligned so they can't be borrowed. Instead they are
+        // read into an aligned copy that is never dropped since the struct still owns the field.
+        let field = |object: TokenStream, field: TokenStream| {
+            if self.packing.is_some() {
+                quote! {
+                    (*::std::mem::ManuallyDrop::new(unsafe {
+                        ::std::ptr::read_unaligned(::std::ptr::addr_of!(#object.#field))
+                    }))
+                }
+            } else {
+                quote! { #object.#field }
+            }
+        };
+
+        let clone = |kind: &Type, member: TokenStream| {
+            if self.packing.is_some() {
+                let value = field(quote! { self }, member);
+                let kind = kind.gen_field();
+                quote! { <#kind as ::std::clone::Clone>::clone(&#value) }
+            } else {
+                kind.gen_clone(&member)
+            }
+        };
+
         let clones = if self.is_typedef {
             let clones = self.fields.iter().enumerate().map(|(index, (_, kind))| {
                 let index = Literal::u32_unsuffixed(index as u32);
-                let clone = kind.gen_clone(&quote! { #index });
+                let clone = clone(kind, quote! { #index });
                 quote! {
                     #clone
                 }
@@ -177,7 +281,7 @@ impl Struct {
         } else {
             let clones = self.fields.iter().map(|(name, kind)| {
                 let name = format_ident(&name);
-                let clone = kind.gen_clone(&quote! { #name });
+                let clone = clone(kind, quote! { #name });
                 quote! {
                     #name: #clone
                 }
@@ -188,21 +292,6 @@ impl Struct {
             }
         };
 
-        let constants = self.name.def.fields().filter_map(|field| {
-            if field.flags().literal() {
-                if let Some(constant) = field.constant() {
-                    let name = format_ident(field.name());
-                    let value = constant.value().gen();
-
-                    return Some(quote! {
-                        pub const #name: #value;
-                    });
-                }
-            }
-
-            None
-        });
-
         let debug_fields = self
             .fields
             .iter()
@@ -214,19 +303,17 @@ impl Struct {
                     }
                 }
 
-                if self.is_typedef {
+                let value = if self.is_typedef {
                     let index = Literal::u32_unsuffixed(index as u32);
-
-                    Some(quote! {
-                        .field(#name, &format_args!("{:?}", self.#index))
-                    })
+                    field(quote! { self }, quote! { #index })
                 } else {
                     let name_ident = format_ident(&name);
+                    field(quote! { self }, quote! { #name_ident })
+                };
 
-                    Some(quote! {
-                        .field(#name, &format_args!("{:?}", self.#name_ident))
-                    })
-                }
+                Some(quote! {
+                    .field(#name, &format_args!("{:?}", #value))
+                })
             });
 
         let compare_fields = if self.fields.is_empty() {
@@ -235,24 +322,23 @@ impl Struct {
             let fields = self.fields.iter().enumerate().map(|(index, (name, t))| {
                 let name_ident = format_ident(&name);
 
-                if let TypeKind::Delegate(name) = &t.kind {
-                    if !name.def.is_winrt() {
-                        return quote! {
-                            self.#name_ident.map(|f| f as usize) == other.#name_ident.map(|f| f as usize)
-                        };
-                    }
-                }
-
-                if self.is_typedef {
+                let member = if self.is_typedef {
                     let index = Literal::u32_unsuffixed(index as u32);
-
-                    quote! {
-                        self.#index == other.#index
-                    }
+                    quote! { #index }
                 } else {
-                    quote! {
-                        self.#name_ident == other.#name_ident
-                    }
+                    quote! { #name_ident }
+                };
+
+                let left = field(quote! { self }, member.clone());
+                let right = field(quote! { other }, member);
+
+                match &t.kind {
+                    TypeKind::Delegate(name) if !name.def.is_winrt() => quote! {
+                        #left.map(|f| f as usize) == #right.map(|f| f as usize)
+                    },
+                    _ => quote! {
+                        #left == #right
+                    },
                 }
             });
 
@@ -276,34 +362,81 @@ impl Struct {
             }
         };
 
-        let copy = if self.is_typedef {
+        // Union fields must be `Copy` so structs that unions hold are `Copy` as well. Unions
+        // hold structs that aren't blittable in their ABI form so that must be `Copy` too.
+        let is_union_field = self.name.def.reader.is_union_field(&self.name.def);
+
+        let is_copy = !self.name.def.is_winrt()
+            && self.fields.iter().all(|(_, t)| t.is_blittable())
+            && is_union_field;
+
+        let mut copy = if self.is_typedef || is_copy {
             quote! {
+                #cfg
                 impl ::std::marker::Copy for #name {}
             }
         } else {
             quote! {}
         };
 
+        if is_union_field {
+            copy.combine(&quote! {
+                #cfg
+                impl ::std::clone::Clone for #abi_ident {
+                    fn clone(&self) -> Self {
+                        *self
+                    }
+                }
+                #cfg
+                impl ::std::marker::Copy for #abi_ident {}
+            });
+        }
+
+        let compare = if self.fields.iter().all(|(_, t)| t.is_comparable()) {
+            quote! {
+                #cfg
+                impl ::std::cmp::PartialEq for #name {
+                    fn eq(&self, other: &Self) -> bool {
+                        #compare_fields
+                    }
+                }
+                #cfg
+                impl ::std::cmp::Eq for #name {}
+            }
+        } else {
+            quote! {}
+        };
+
         let debug_name = self.name.name;
+        let repr = self.gen_repr();
+        let size = self.gen_size_assertion(&name, &cfg);
 
         quote! {
-            #[repr(C)]
+            #doc
+            #cfg
+            #repr
             #[allow(non_snake_case)]
             pub struct #name #body
+            #cfg
             impl #name {
                 #(#constants)*
             }
-            #[repr(C)]
+            #size
+            #cfg
+            #repr
             #[doc(hidden)]
             pub struct #abi_ident(#(#abi),*);
+            #cfg
             unsafe impl ::windows::Abi for #name {
                 type Abi = #abi_ident;
             }
+            #cfg
             impl ::std::default::Default for #name {
                 fn default() -> Self {
                     #defaults
                 }
             }
+            #cfg
             impl ::std::fmt::Debug for #name {
                 fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
                     fmt.debug_struct(#debug_name)
@@ -311,19 +444,224 @@ impl Struct {
                         .finish()
                 }
             }
+            #cfg
             impl ::std::clone::Clone for #name {
                 fn clone(&self) -> Self {
                     #clones
                 }
             }
+            #compare
+            #copy
+            #runtime_type
+        }
+    }
+
+    fn gen_union<C: Iterator<Item = TokenStream>>(
+        &self,
+        name: &TokenStream,
+        doc: &TokenStream,
+        cfg: &TokenStream,
+        abi_ident: &squote::Ident,
+        constants: C,
+    ) -> TokenStream {
+        // Union fields must be `Copy` so any field that isn't blittable is exposed in its ABI form.
+        // Unions can't be compared since it isn't known which of their fields is in use.
+
+        let fields = self.fields.iter().map(|(name, kind)| {
+            let name = format_ident(name);
+            let kind = if kind.is_blittable() {
+                kind.gen_field()
+            } else {
+                kind.gen_abi()
+            };
+            quote! {
+                pub #name: #kind
+            }
+        });
+
+        let debug_name = self.name.name;
+        let repr = self.gen_repr();
+        let size = self.gen_size_assertion(name, cfg);
+
+        quote! {
+            #doc
+            #cfg
+            #repr
+            #[allow(non_snake_case)]
+            pub union #name { #(#fields),* }
+            #cfg
+            impl #name {
+                #(#constants)*
+            }
+            #size
+            #cfg
+            #[doc(hidden)]
+            pub type #abi_ident = #name;
+            #cfg
+            unsafe impl ::windows::Abi for #name {
+                type Abi = Self;
+            }
+            #cfg
+            impl ::std::default::Default for #name {
+                fn default() -> Self {
+                    unsafe { ::std::mem::zeroed() }
+                }
+            }
+            #cfg
+            impl ::std::fmt::Debug for #name {
+                fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    fmt.debug_struct(#debug_name).finish()
+                }
+            }
+            #cfg
+            impl ::std::clone::Clone for #name {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+            #cfg
+            impl ::std::marker::Copy for #name {}
+        }
+    }
+
+    /// Generates an explicit layout whose fields don't all start at offset zero as the bytes
+    /// it occupies, since neither a struct nor a union can place fields at given offsets
+    fn gen_opaque<C: Iterator<Item = TokenStream>>(
+        &self,
+        name: &TokenStream,
+        doc: &TokenStream,
+        cfg: &TokenStream,
+        abi_ident: &squote::Ident,
+        constants: C,
+        len: &TokenStream,
+    ) -> TokenStream {
+        let debug_name = self.name.name;
+        let size = self.gen_size_assertion(name, cfg);
+
+        // The bytes are only aligned to a single byte so the struct takes the alignment of the
+        // fields they stand in for, which may depend on the pointer width. Packing is accounted
+        // for by that alignment since it can't be combined with one.
+        let repr = |pointer_width: u32| {
+            let alignment = Literal::u32_unsuffixed(self.alignment(pointer_width / 8));
+            quote! { repr(C, align(#alignment)) }
+        };
+
+        let repr = match pointer_width(type_architectures(&self.name.def)) {
+            Some(width) => {
+                let repr = repr(width);
+                quote! { #[#repr] }
+            }
+            None if self.alignment(4) == self.alignment(8) => {
+                let repr = repr(64);
+                quote! { #[#repr] }
+            }
+            None => {
+                let repr32 = repr(32);
+                let repr64 = repr(64);
+                quote! {
+                    #[cfg_attr(target_pointer_width = "32", #repr32)]
+                    #[cfg_attr(target_pointer_width = "64", #repr64)]
+                }
+            }
+        };
+
+        quote! {
+            #doc
+            #cfg
+            #repr
+            pub struct #name {
+                pub bytes: [u8; #len],
+            }
+            #cfg
+            impl #name {
+                #(#constants)*
+            }
+            #size
+            #cfg
+            #[doc(hidden)]
+            pub type #abi_ident = #name;
+            #cfg
+            unsafe impl ::windows::Abi for #name {
+                type Abi = Self;
+            }
+            #cfg
+            impl ::std::default::Default for #name {
+                fn default() -> Self {
+                    Self { bytes: [0; #len] }
+                }
+            }
+            #cfg
+            impl ::std::fmt::Debug for #name {
+                fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    fmt.debug_struct(#debug_name).finish()
+                }
+            }
+            #cfg
+            impl ::std::clone::Clone for #name {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+            #cfg
             impl ::std::cmp::PartialEq for #name {
                 fn eq(&self, other: &Self) -> bool {
-                    #compare_fields
+                    self.bytes[..] == other.bytes[..]
                 }
             }
+            #cfg
             impl ::std::cmp::Eq for #name {}
-            #copy
-            #runtime_type
+            #cfg
+            impl ::std::marker::Copy for #name {}
+        }
+    }
+
+    /// The alignment of the fields in bytes where pointers have the given size in bytes
+    fn alignment(&self, pointer_size: u32) -> u32 {
+        let alignment = self
+            .fields
+            .iter()
+            .map(|(_, t)| t.alignment(pointer_size))
+            .max()
+            .unwrap_or(1);
+
+        self.packing
+            .map_or(alignment, |packing| alignment.min(packing))
+    }
+
+    fn gen_repr(&self) -> TokenStream {
+        if let Some(packing) = self.packing {
+            let packing = Literal::u32_unsuffixed(packing);
+            quote! { #[repr(C, packed(#packing))] }
+        } else {
+            quote! { #[repr(C)] }
+        }
+    }
+
+    /// Checks at compile time that the generated type matches the size recorded in metadata.
+    ///
+    /// The metadata records a single size so a struct with pointers or pointer-sized fields is
+    /// only checked when it's limited to architectures with the same pointer width, and then
+    /// only for that `target_pointer_width`.
+    fn gen_size_assertion(&self, name: &TokenStream, cfg: &TokenStream) -> TokenStream {
+        let size = match self.size {
+            Some(size) => size,
+            None => return TokenStream::new(),
+        };
+
+        let width = if self.fields.iter().all(|(_, t)| t.has_fixed_size()) {
+            TokenStream::new()
+        } else if let Some(width) = pointer_width(type_architectures(&self.name.def)) {
+            let width = width.to_string();
+            quote! { #[cfg(target_pointer_width = #width)] }
+        } else {
+            return TokenStream::new();
+        };
+
+        let size = Literal::u32_unsuffixed(size);
+        quote! {
+            #cfg
+            #width
+            const _: () = assert!(::std::mem::size_of::<#name>() == #size);
         }
     }
 }
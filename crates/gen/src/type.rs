@@ -1,5 +1,5 @@
 use crate::*;
-use squote::{quote, TokenStream};
+use squote::{quote, Literal, TokenStream};
 use winmd::Decode;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
@@ -58,6 +58,26 @@ impl Type {
         generics: &[TypeKind],
         calling_namespace: &'static str,
         is_return_type: bool,
+    ) -> Option<Self> {
+        Self::from_blob_in(
+            blob,
+            None,
+            param,
+            generics,
+            calling_namespace,
+            is_return_type,
+        )
+    }
+
+    /// Reads a type from a signature that belongs to the given type definition, if any, so
+    /// that its nested types resolve within the same per-architecture variant
+    fn from_blob_in(
+        blob: &mut winmd::Blob,
+        referrer: Option<&winmd::TypeDef>,
+        param: Option<winmd::Param>,
+        generics: &[TypeKind],
+        calling_namespace: &'static str,
+        is_return_type: bool,
     ) -> Option<Self> {
         let modifiers = blob.read_modifiers();
         let mut by_ref = blob.read_expected(0x10);
@@ -69,6 +89,7 @@ impl Type {
         let is_array = blob.read_expected(0x1D);
 
         let mut pointers = 0;
+        let mut array = None;
 
         while blob.read_expected(0x0f) {
             pointers += 1;
@@ -96,21 +117,35 @@ impl Type {
                 let def =
                     winmd::TypeDefOrRef::decode(blob.reader, blob.read_unsigned(), blob.file_index);
 
-                if def.name().0.is_empty() {
-                    // TODO: handle nested types
-                    TypeKind::NotYetSupported
-                } else {
-                    TypeKind::from_type_def_or_ref(&def, generics, calling_namespace)
+                match (&def, referrer) {
+                    (winmd::TypeDefOrRef::TypeRef(nested), Some(referrer))
+                        if matches!(nested.scope(), winmd::ResolutionScope::TypeRef(_)) =>
+                    {
+                        let def = nested
+                            .find_resolved_from(referrer)
+                            .unwrap_or_else(|| nested.resolve());
+
+                        TypeKind::from_type_def(&def, calling_namespace)
+                    }
+                    _ => TypeKind::from_type_def_or_ref(&def, generics, calling_namespace),
                 }
             }
             0x13 => generics[blob.read_unsigned() as usize].clone(),
             0x14 => {
-                // TODO: handle win32 arrays
-                // type
-                // rank (dimensions)
-                // bounds count
-                // bound
-                TypeKind::NotYetSupported
+                let element =
+                    Self::from_blob_in(blob, referrer, None, generics, calling_namespace, false)
+                        .unwrap();
+                let shape = blob.read_array_shape();
+                pointers = element.pointers;
+
+                // Multidimensional arrays are flattened since they have the same layout, while
+                // array parameters decay to a pointer to their first element just as in C.
+                match shape.element_count() {
+                    Some(len) if param.is_none() => array = Some(len),
+                    _ => pointers += 1,
+                }
+
+                element.kind
             }
             0x15 => TypeKind::from_type_name(TypeName::from_type_spec_blob(
                 blob,
@@ -155,7 +190,7 @@ impl Type {
             by_ref,
             kind,
             pointers,
-            array: None,
+            array,
             modifiers,
             param,
             name,
@@ -165,14 +200,22 @@ impl Type {
         })
     }
 
-    pub fn from_field(field: &winmd::Field, calling_namespace: &'static str) -> Self {
+    pub fn from_field(
+        def: &winmd::TypeDef,
+        field: &winmd::Field,
+        calling_namespace: &'static str,
+    ) -> Self {
         let mut blob = field.sig();
         blob.read_unsigned();
         blob.read_modifiers();
-        Self::from_blob(&mut blob, None, &Vec::new(), calling_namespace, false).unwrap()
+        Self::from_blob_in(&mut blob, Some(def), None, &[], calling_namespace, false).unwrap()
     }
 
     pub fn gen_field(&self) -> TokenStream {
+        self.gen_array(self.gen_element())
+    }
+
+    fn gen_element(&self) -> TokenStream {
         let mut tokens = TokenStream::new();
 
         for _ in 0..self.pointers {
@@ -198,6 +241,15 @@ impl Type {
         tokens
     }
 
+    fn gen_array(&self, element: TokenStream) -> TokenStream {
+        if let Some(len) = self.array {
+            let len = Literal::u32_unsuffixed(len as u32);
+            quote! { [#element; #len] }
+        } else {
+            element
+        }
+    }
+
     pub fn gen_clone(&self, name: &TokenStream) -> TokenStream {
         match self.kind {
             TypeKind::Bool
@@ -238,21 +290,63 @@ impl Type {
         let mut tokens = self.gen_abi_pointer_part();
 
         tokens.combine(&self.kind.gen_abi());
-        tokens
+        self.gen_array(tokens)
     }
 
     pub fn gen_full_abi(&self) -> TokenStream {
         let mut tokens = self.gen_abi_pointer_part();
 
         tokens.combine(&self.kind.gen_full_abi());
-        tokens
+        self.gen_array(tokens)
     }
 
-    pub fn gen_default(&self) -> TokenStream {
+    /// Returns `true` if values of the type can be copied bit for bit, as is required of
+    /// the fields of a union.
+    pub fn is_blittable(&self) -> bool {
+        self.pointers > 0 || self.kind.is_blittable()
+    }
+
+    /// Returns `true` if the size of the type is the same on every architecture, that is if
+    /// it doesn't contain pointers or pointer-sized integers.
+    pub fn has_fixed_size(&self) -> bool {
+        self.pointers == 0 && self.kind.has_fixed_size()
+    }
+
+    /// Returns `true` if values of the type can be compared, which isn't the case for unions
+    /// or anything that contains them since it isn't known which of their fields is in use.
+    pub fn is_comparable(&self) -> bool {
+        self.pointers > 0 || self.kind.is_comparable()
+    }
+
+    /// The alignment of the type in bytes where pointers have the given size in bytes.
+    pub fn alignment(&self, pointer_size: u32) -> u32 {
         if self.pointers > 0 {
+            pointer_size
+        } else {
+            self.kind.alignment(pointer_size)
+        }
+    }
+
+    pub fn gen_default(&self) -> TokenStream {
+        let element = if self.pointers > 0 {
             quote! { ::std::ptr::null_mut() }
         } else {
             self.kind.gen_default()
+        };
+
+        // Arrays of values that aren't `Copy`, which includes most structs, can't be repeated
+        // from a single element so they are zeroed instead, which is how those values represent
+        // their defaults.
+        let is_copy = self.pointers > 0
+            || (self.kind.is_blittable() && !matches!(self.kind, TypeKind::Struct(_)));
+
+        match self.array {
+            Some(len) if is_copy => {
+                let len = Literal::u32_unsuffixed(len as u32);
+                quote! { [#element; #len] }
+            }
+            Some(_) => quote! { unsafe { ::std::mem::zeroed() } },
+            None => element,
         }
     }
 }
@@ -311,6 +405,130 @@ impl TypeKind {
         }
     }
 
+    pub fn is_blittable(&self) -> bool {
+        match self {
+            Self::Bool
+            | Self::Char
+            | Self::I8
+            | Self::U8
+            | Self::I16
+            | Self::U16
+            | Self::I32
+            | Self::U32
+            | Self::I64
+            | Self::U64
+            | Self::F32
+            | Self::F64
+            | Self::ISize
+            | Self::USize
+            | Self::Guid
+            | Self::ErrorCode
+            | Self::Bool32
+            | Self::Enum(_)
+            | Self::NotYetSupported => true,
+            Self::Delegate(name) => !name.def.is_winrt(),
+            Self::Struct(name) => {
+                !name.def.is_winrt()
+                    && (name.def.flags().explicit()
+                        || name
+                            .def
+                            .fields()
+                            .filter(|field| !field.flags().literal())
+                            .all(|field| {
+                                Type::from_field(&name.def, &field, name.namespace).is_blittable()
+                            }))
+            }
+            _ => false,
+        }
+    }
+
+    fn has_fixed_size(&self) -> bool {
+        match self {
+            Self::Bool
+            | Self::Char
+            | Self::I8
+            | Self::U8
+            | Self::I16
+            | Self::U16
+            | Self::I32
+            | Self::U32
+            | Self::I64
+            | Self::U64
+            | Self::F32
+            | Self::F64
+            | Self::Guid
+            | Self::ErrorCode
+            | Self::Bool32
+            | Self::Matrix3x2
+            | Self::Enum(_) => true,
+            Self::Struct(name) => name
+                .def
+                .fields()
+                .filter(|field| !field.flags().literal())
+                .all(|field| Type::from_field(&name.def, &field, name.namespace).has_fixed_size()),
+            _ => false,
+        }
+    }
+
+    fn is_comparable(&self) -> bool {
+        match self {
+            // Other explicit layouts are compared as the bytes they occupy.
+            Self::Struct(name) if name.def.flags().explicit() => !name.def.is_union(),
+            Self::Struct(name) => name
+                .def
+                .fields()
+                .filter(|field| !field.flags().literal())
+                .all(|field| Type::from_field(&name.def, &field, name.namespace).is_comparable()),
+            _ => true,
+        }
+    }
+
+    fn alignment(&self, pointer_size: u32) -> u32 {
+        match self {
+            Self::Char | Self::I16 | Self::U16 => 2,
+            Self::I32
+            | Self::U32
+            | Self::F32
+            | Self::Guid
+            | Self::ErrorCode
+            | Self::Bool32
+            | Self::Matrix3x2 => 4,
+            Self::I64 | Self::U64 | Self::F64 => 8,
+            Self::ISize
+            | Self::USize
+            | Self::String
+            | Self::Object
+            | Self::IUnknown
+            | Self::Class(_)
+            | Self::Interface(_)
+            | Self::Delegate(_) => pointer_size,
+            Self::Enum(name) => match name.def.underlying_type() {
+                winmd::ElementType::I16 | winmd::ElementType::U16 => 2,
+                winmd::ElementType::I32 | winmd::ElementType::U32 => 4,
+                winmd::ElementType::I64 | winmd::ElementType::U64 => 8,
+                _ => 1,
+            },
+            Self::Struct(name) => {
+                let alignment = name
+                    .def
+                    .fields()
+                    .filter(|field| !field.flags().literal())
+                    .map(|field| {
+                        Type::from_field(&name.def, &field, name.namespace).alignment(pointer_size)
+                    })
+                    .max()
+                    .unwrap_or(1);
+
+                // Packing lowers the alignment of the fields and so that of the struct.
+                match name.def.class_layout().map(|layout| layout.packing_size()) {
+                    Some(packing) if packing != 0 => alignment.min(packing),
+                    _ => alignment,
+                }
+            }
+            _ => 1,
+        }
+    }
+
     fn from_type_name(name: TypeName) -> Self {
         match name.def.category() {
             winmd::TypeCategory::Interface => Self::Interface(name),
@@ -333,14 +551,8 @@ impl TypeKind {
             ("Windows.Foundation", "HResult") => Self::ErrorCode,
             ("Windows.Win32.Com", "HRESULT") => Self::ErrorCode,
             ("Windows.Win32.SystemServices", "BOOL") => Self::Bool32,
-            // TODO: workaround for https://github.com/microsoft/win32metadata/issues/181
-            ("Windows.Win32.SystemServices", "LARGE_INTEGER") => Self::I64,
-            ("Windows.Win32.SystemServices", "ULARGE_INTEGER") => Self::U64,
             ("Windows.Win32.Direct2D", "D2D_MATRIX_3X2_F") => Self::Matrix3x2,
-            (namespace, name) => Self::from_type_def(
-                &type_ref.reader.expect_type_def((namespace, name)),
-                calling_namespace,
-            ),
+            _ => Self::from_type_def(&type_ref.resolve(), calling_namespace),
         }
     }
 
@@ -568,3 +780,63 @@ impl TypeKind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrays() {
+        let reader = winmd::TypeReader::get();
+        let def = reader.expect_type_def(("Windows.Win32.Storage.FileSystem", "WIN32_FIND_DATAW"));
+
+        let field = def
+            .fields()
+            .find(|field| field.name() == "cFileName")
+            .unwrap();
+
+        let mut blob = field.sig();
+        blob.read_unsigned();
+        assert!(blob.read_expected(0x14));
+        Type::from_blob(
+            &mut blob,
+            None,
+            &[],
+            "Windows.Win32.Storage.FileSystem",
+            false,
+        )
+        .unwrap();
+        let shape = blob.read_array_shape();
+        assert_eq!(shape.rank, 1);
+        assert_eq!(shape.sizes, [260]);
+        assert_eq!(shape.element_count(), Some(260));
+
+        let t = Type::from_field(&def, &field, "Windows.Win32.Storage.FileSystem");
+        assert_eq!((t.array, t.pointers), (Some(260), 0));
+        assert_eq!(t.gen_field().as_str(), "[ u16 ;260 ]");
+        assert_eq!(t.gen_default().as_str(), "[ 0 ;260 ]");
+    }
+
+    #[test]
+    fn alignment() {
+        let reader = winmd::TypeReader::get();
+        let kind = |name| {
+            let def = reader.expect_type_def(("Windows.Win32.Foundation", name));
+            TypeKind::Struct(TypeName::from_type_def(&def, "Windows.Win32.Foundation"))
+        };
+
+        let large_integer = kind("LARGE_INTEGER");
+        assert_eq!(
+            (large_integer.alignment(4), large_integer.alignment(8)),
+            (8, 8)
+        );
+        assert!(!large_integer.is_comparable());
+
+        let unicode_string = kind("UNICODE_STRING");
+        assert_eq!(
+            (unicode_string.alignment(4), unicode_string.alignment(8)),
+            (4, 8)
+        );
+        assert!(unicode_string.is_comparable());
+    }
+}
@@ -79,6 +79,22 @@ impl TypeDefinition {
         }
     }
 
+    /// Removes the required interfaces of a class or interface that aren't available in the
+    /// given versions, such as those a class gained in later releases
+    pub fn retain_available(&mut self, versions: &VersionLimits) {
+        if versions.is_empty() {
+            return;
+        }
+
+        match self {
+            Self::Class(t) => t.interfaces.retain(|i| versions.is_available(&i.name)),
+            Self::Interface(t) => t
+                .interfaces
+                .retain(|i| i.kind == InterfaceKind::Default || versions.is_available(&i.name)),
+            _ => {}
+        }
+    }
+
     // TODO: get rid of this
     pub fn name(&self) -> &TypeName {
         match self {
@@ -1,10 +1,14 @@
 use super::*;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 /// The set of relevant namespaces and types
 pub struct TypeLimits {
     reader: &'static winmd::TypeReader,
     pub inner: BTreeSet<NamespaceTypes>,
+    /// The versions that limit which of the relevant types are available
+    pub versions: VersionLimits,
+    /// The aliases of types, keyed by namespace and name
+    pub renames: BTreeMap<(&'static str, String), String>,
 }
 
 impl TypeLimits {
@@ -12,6 +16,8 @@ impl TypeLimits {
         Self {
             reader,
             inner: BTreeSet::new(),
+            versions: VersionLimits::default(),
+            renames: BTreeMap::new(),
         }
     }
 
@@ -31,9 +37,40 @@ impl TypeLimits {
         }
     }
 
+    /// Insert a type along with the alias it is also generated as
+    pub fn rename(
+        &mut self,
+        namespace: &'static str,
+        name: &str,
+        alias: &str,
+    ) -> Result<(), &'static str> {
+        let namespace = self
+            .reader
+            .find_lowercase_namespace(&namespace.to_lowercase())
+            .ok_or(namespace)?;
+
+        self.insert(NamespaceTypes {
+            namespace,
+            limit: TypeLimit::Some(vec![name.to_string()]),
+        })?;
+
+        self.renames
+            .insert((namespace, name.to_string()), alias.to_string());
+
+        Ok(())
+    }
+
     pub fn limits(&self) -> impl Iterator<Item = &NamespaceTypes> {
         self.inner.iter()
     }
+
+    /// Whether a type is excluded from the types selected by the other limits
+    pub fn excludes(&self, namespace: &str, name: &str) -> bool {
+        self.inner.iter().any(|limit| match &limit.limit {
+            TypeLimit::Except(excluded) => limit.namespace == namespace && excluded.matches(name),
+            _ => false,
+        })
+    }
 }
 
 /// A namespace's relevant types
@@ -50,4 +87,170 @@ pub enum TypeLimit {
     All,
     /// Some types in the namespace
     Some(Vec<String>),
+    /// The types in the namespace whose names match any of the patterns, where `*` matches
+    /// any sequence of characters, e.g. `Create*W`
+    Matching(Vec<String>),
+    /// Types in the namespace that aren't generated even when another limit selects them
+    ///
+    /// Excluded types are still generated if other types depend on them.
+    Except(Box<TypeLimit>),
+}
+
+impl TypeLimit {
+    /// Whether the limit selects the type with the given name
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Some(names) => names.iter().any(|n| n == name),
+            Self::Matching(patterns) => patterns.iter().any(|p| matches_pattern(p, name)),
+            Self::Except(limit) => !limit.matches(name),
+        }
+    }
+}
+
+/// Whether a name matches a pattern in which `*` matches any sequence of characters
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+
+    // The first part must start the name and, if there is no `*`, be the whole name.
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match name.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts: Vec<_> = parts.collect();
+
+    let last = match parts.split_last() {
+        Some((last, middle)) => {
+            for part in middle {
+                match rest.find(part) {
+                    Some(pos) => rest = &rest[pos + part.len()..],
+                    None => return false,
+                }
+            }
+
+            last
+        }
+        None => return rest.is_empty(),
+    };
+
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// The oldest contract and OS versions that the generated APIs must be available on
+///
+/// Types introduced in a later version of a limited contract, or in a later OS version, are
+/// excluded along with any interfaces of other types that depend on them. Types without
+/// version attributes, or only versioned by contracts that aren't limited, are always
+/// available.
+#[derive(Debug, Default)]
+pub struct VersionLimits {
+    /// The minimum version of each contract, keyed by its full name such as
+    /// `Windows.Foundation.UniversalApiContract`, as stored by `ContractVersionAttribute`
+    /// with the major version in the high word and the minor version in the low word
+    pub contracts: BTreeMap<String, u32>,
+    /// The minimum OS version as stored by `VersionAttribute`, e.g. `0x0603_0000` for
+    /// Windows 8.1
+    ///
+    /// APIs introduced with Windows 10 and later are versioned by contract instead.
+    pub os_version: Option<u32>,
+}
+
+impl VersionLimits {
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty() && self.os_version.is_none()
+    }
+
+    /// Whether a type and the type arguments of a generic type are all available
+    pub fn is_available(&self, name: &TypeName) -> bool {
+        self.is_empty()
+            || name
+                .dependencies()
+                .iter()
+                .all(|def| self.is_def_available(def))
+    }
+
+    pub fn is_def_available(&self, def: &winmd::TypeDef) -> bool {
+        self.is_empty() || self.allows(def.attributes())
+    }
+
+    /// Whether an API with the given attributes was introduced no later than the limits
+    pub fn allows<I: Iterator<Item = winmd::Attribute>>(&self, attributes: I) -> bool {
+        for attribute in attributes {
+            // Attributes that can't be read aren't any of the ones that limit availability.
+            let args = match attribute.try_args() {
+                Ok(args) => args,
+                Err(_) => continue,
+            };
+
+            match (attribute.name(), args.as_slice()) {
+                (
+                    ("Windows.Foundation.Metadata", "ContractVersionAttribute"),
+                    [(_, contract), (_, winmd::AttributeArg::U32(version))],
+                ) => {
+                    let contract = match contract_name(contract) {
+                        Some(contract) => contract,
+                        None => continue,
+                    };
+
+                    if let Some(limit) = self.contracts.get(&contract) {
+                        if version > limit {
+                            return false;
+                        }
+                    }
+                }
+                (
+                    ("Windows.Foundation.Metadata", "VersionAttribute"),
+                    [(_, winmd::AttributeArg::U32(version)), ..],
+                ) => {
+                    if let Some(limit) = &self.os_version {
+                        if version > limit {
+                            return false;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        true
+    }
+}
+
+/// The full name of a contract, which `ContractVersionAttribute` and friends refer to either
+/// by type or by name
+pub(crate) fn contract_name(arg: &winmd::AttributeArg) -> Option<String> {
+    match arg {
+        winmd::AttributeArg::TypeDef(def) => {
+            let (namespace, name) = def.name();
+            Some(format!("{}.{}", namespace, name))
+        }
+        winmd::AttributeArg::TypeName(name) | winmd::AttributeArg::String(name) => {
+            Some(name.clone())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns() {
+        assert!(matches_pattern("*", "CreateEventW"));
+        assert!(matches_pattern("Create*W", "CreateEventW"));
+        assert!(matches_pattern("Create*W", "CreateW"));
+        assert!(matches_pattern("*Event*", "CreateEventExW"));
+        assert!(matches_pattern("CreateEventW", "CreateEventW"));
+        assert!(!matches_pattern("Create*W", "CreateEventA"));
+        assert!(!matches_pattern("Create*W", "OpenEventW"));
+        assert!(!matches_pattern("A*BC*C", "ABC"));
+        assert!(!matches_pattern("CreateEvent", "CreateEventW"));
+
+        let limit = TypeLimit::Except(Box::new(TypeLimit::Matching(vec!["I*".to_string()])));
+        assert!(limit.matches("Colors"));
+        assert!(!limit.matches("IColorsStatics"));
+    }
 }
@@ -218,7 +218,7 @@ impl TypeName {
 
         for field in self.def.fields() {
             result.push(';');
-            let t = Type::from_field(&field, &self.calling_namespace);
+            let t = Type::from_field(&self.def, &field, &self.calling_namespace);
             result.push_str(&t.kind.signature());
         }
 
@@ -478,4 +478,22 @@ mod tests {
             TypeKind::Struct(name).signature() == "struct(Windows.Foundation.Rect;f4;f4;f4;f4)"
         );
     }
+
+    #[test]
+    fn nested_names() {
+        let reader = winmd::TypeReader::get();
+
+        for namespace in reader.namespaces() {
+            for t in reader.namespace_types(namespace) {
+                if let winmd::Type::TypeDef(def) = t {
+                    for (index, nested) in def.nested_types().enumerate() {
+                        let name = TypeName::from_type_def(&nested, namespace);
+                        assert!(name.namespace == namespace);
+                        assert!(name.name == format!("{}_{}", def.name().1, index));
+                        assert!(nested.enclosing_type() == Some(def));
+                    }
+                }
+            }
+        }
+    }
 }
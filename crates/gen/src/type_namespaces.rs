@@ -21,6 +21,9 @@ impl TypeNamespaces {
                 // TODO: remove this allowance when https://github.com/microsoft/windows-rs/issues/212 is fixed
                 #[allow(unused_variables)]
                 #[allow(non_upper_case_globals)]
+                // The bindings of deprecated APIs refer to each other but only uses outside of
+                // the bindings should warn.
+                #[allow(deprecated)]
                 pub mod #name {
                     #(#tokens)*
                     #foundation
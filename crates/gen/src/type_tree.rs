@@ -1,5 +1,5 @@
 use crate::*;
-use squote::TokenStream;
+use squote::{quote, TokenStream};
 
 /// A namespaced tree of types
 #[derive(Default)]
@@ -7,64 +7,147 @@ pub struct TypeTree {
     pub types: Vec<TypeDefinition>,
     pub namespaces: TypeNamespaces,
     pub include_foundation: bool,
+    /// The aliases of the tree's types, as pairs of name and alias
+    pub aliases: Vec<(String, String)>,
 }
 
 impl TypeTree {
+    /// Builds the tree of the types within the limits along with their dependencies
+    ///
+    /// Types that aren't available in the limited versions are skipped, even when named
+    /// explicitly, and the interfaces of classes and interfaces are limited accordingly.
+    /// Excluded types are skipped unless other types depend on them.
+    // Type definitions are ordered by their row alone so the lazily built indexes of the
+    // `TypeReader` they refer to never affect the ordering of the set.
+    #[allow(clippy::mutable_key_type)]
     pub fn from_limits(reader: &'static winmd::TypeReader, limits: &TypeLimits) -> Self {
         let mut tree = TypeTree::default();
         let mut set = std::collections::BTreeSet::new();
+        let versions = &limits.versions;
 
         for limit in limits.limits() {
             match &limit.limit {
                 TypeLimit::All => {
                     for def in reader.namespace_types(&limit.namespace) {
-                        tree.insert_if(reader, &mut set, &def);
+                        if !limits.excludes(limit.namespace, def.name()) {
+                            tree.insert_if(reader, versions, &mut set, &def);
+                        }
                     }
                 }
                 TypeLimit::Some(types) => {
+                    // The names given to `build!` are checked as they are parsed so that
+                    // unknown names are reported where they were written.
                     for name in types {
-                        tree.insert_if(
-                            reader,
-                            &mut set,
-                            &reader.expect_type((&limit.namespace, name)),
-                        );
+                        if limits.excludes(limit.namespace, name) {
+                            continue;
+                        }
+
+                        if let Some(def) = reader.find_type((limit.namespace, name)) {
+                            tree.insert_if(reader, versions, &mut set, &def);
+                        }
+                    }
+                }
+                TypeLimit::Matching(_) => {
+                    for def in reader.namespace_types(limit.namespace) {
+                        if limit.limit.matches(def.name())
+                            && !limits.excludes(limit.namespace, def.name())
+                        {
+                            tree.insert_if(reader, versions, &mut set, &def);
+                        }
                     }
                 }
+                TypeLimit::Except(_) => {}
             }
         }
 
+        for ((namespace, name), alias) in &limits.renames {
+            tree.alias(namespace, name, alias);
+        }
+
         tree
     }
 
+    /// Adds an alias for a type of the tree, unless the type wasn't generated
+    fn alias(&mut self, namespace: &str, name: &str, alias: &str) {
+        if let Some(pos) = namespace.find('.') {
+            if let Some(tree) = self.namespaces.0.get_mut(&namespace[..pos]) {
+                tree.alias(&namespace[pos + 1..], name, alias);
+            }
+        } else if let Some(tree) = self.namespaces.0.get_mut(namespace) {
+            if tree.types.iter().any(|t| t.name().name == name) {
+                tree.aliases.push((name.to_string(), alias.to_string()));
+            }
+        }
+    }
+
+    /// Whether the namespace of the tree has the given alias
+    pub fn has_alias(&self, namespace: &str, alias: &str) -> bool {
+        if let Some(pos) = namespace.find('.') {
+            self.namespaces
+                .0
+                .get(&namespace[..pos])
+                .is_some_and(|tree| tree.has_alias(&namespace[pos + 1..], alias))
+        } else {
+            self.namespaces
+                .0
+                .get(namespace)
+                .is_some_and(|tree| tree.aliases.iter().any(|(_, a)| a == alias))
+        }
+    }
+
+    #[allow(clippy::mutable_key_type)]
     fn insert_if(
         &mut self,
         reader: &winmd::TypeReader,
+        versions: &VersionLimits,
         set: &mut std::collections::BTreeSet<winmd::TypeDef>,
         def: &winmd::Type,
     ) {
         match def {
             winmd::Type::TypeDef(def) => match def.category() {
                 winmd::TypeCategory::Contract | winmd::TypeCategory::Attribute => {}
+                _ if !versions.is_def_available(def) => {}
                 _ => {
                     if set.insert(*def) {
-                        let t = TypeDefinition::from_type_def(def);
+                        let mut t = TypeDefinition::from_type_def(def);
+                        t.retain_available(versions);
 
                         for def in t.dependencies() {
-                            self.insert_if(reader, set, &winmd::Type::TypeDef(def));
+                            self.insert_if(reader, versions, set, &winmd::Type::TypeDef(def));
                         }
 
                         self.insert(t.name().namespace, t);
+
+                        // The other per-architecture variants of a type are included with it
+                        // so that the bindings work on every architecture.
+                        if def.enclosing_type().is_none() && type_architectures(def).is_some() {
+                            for variant in def.reader.type_variants(def.name()) {
+                                self.insert_if(reader, versions, set, &variant);
+                            }
+                        }
                     }
                 }
             },
             winmd::Type::MethodDef((def, method)) => {
-                let t = TypeDefinition::from_method_def(def, method);
+                let variants: Vec<_> = if method_architectures(method).is_some() {
+                    def.reader
+                        .type_variants((def.name().0, method.name()))
+                        .collect()
+                } else {
+                    vec![winmd::Type::MethodDef((*def, *method))]
+                };
 
-                for def in t.dependencies() {
-                    self.insert_if(reader, set, &winmd::Type::TypeDef(def));
-                }
+                for variant in variants {
+                    if let winmd::Type::MethodDef((def, method)) = variant {
+                        let t = TypeDefinition::from_method_def(&def, &method);
 
-                self.insert(t.name().namespace, t);
+                        for def in t.dependencies() {
+                            self.insert_if(reader, versions, set, &winmd::Type::TypeDef(def));
+                        }
+
+                        self.insert(t.name().namespace, t);
+                    }
+                }
             }
             winmd::Type::Field((def, field)) => {
                 let t = TypeDefinition::from_field(def, field);
@@ -113,9 +196,17 @@ impl TypeTree {
 
     /// Turn the tree into a token stream for code generation
     pub fn gen<'a>(&'a self) -> impl Iterator<Item = TokenStream> + 'a {
+        let aliases = self.aliases.iter().map(|(name, alias)| {
+            // Generic types are named without their arity, e.g. `IVector` for ``IVector`1``.
+            let name = format_ident(name.split('`').next().unwrap_or(name));
+            let alias = format_ident(alias);
+            quote! { pub use self::#name as #alias; }
+        });
+
         self.types
             .iter()
             .map(|t| t.gen())
+            .chain(aliases)
             .chain(self.namespaces.gen())
     }
 }
@@ -194,4 +285,141 @@ mod tests {
                 == false
         );
     }
+
+    #[test]
+    fn test_version_limits() {
+        let reader = winmd::TypeReader::get();
+        let mut limits = TypeLimits::new(reader);
+        limits
+            .insert(NamespaceTypes {
+                namespace: "windows.globalization",
+                limit: TypeLimit::Some(vec!["Language".to_string()]),
+            })
+            .unwrap();
+        limits.versions.contracts.insert(
+            "Windows.Foundation.UniversalApiContract".to_string(),
+            6 << 16,
+        );
+
+        let root = TypeTree::from_limits(reader, &limits);
+        let globalization = &root.namespaces.0["Windows"].namespaces.0["Globalization"];
+
+        let language = globalization
+            .types
+            .iter()
+            .find_map(|t| match t {
+                TypeDefinition::Class(class) if class.name.name == "Language" => Some(class),
+                _ => None,
+            })
+            .unwrap();
+
+        // ILanguage2 was introduced in version 6 of the contract and ILanguage3 in version 10.
+        let interfaces: Vec<_> = language.interfaces.iter().map(|i| i.name.name).collect();
+        assert!(interfaces.contains(&"ILanguage2"));
+        assert!(!interfaces.contains(&"ILanguage3"));
+        assert!(!globalization
+            .types
+            .iter()
+            .any(|t| t.name().name == "ILanguage3"));
+    }
+
+    #[test]
+    fn test_exclusions_and_renames() {
+        let reader = winmd::TypeReader::get();
+        let mut limits = TypeLimits::new(reader);
+        limits
+            .insert(NamespaceTypes {
+                namespace: "windows.ui",
+                limit: TypeLimit::All,
+            })
+            .unwrap();
+        limits
+            .insert(NamespaceTypes {
+                namespace: "windows.ui",
+                limit: TypeLimit::Except(Box::new(TypeLimit::Some(vec![
+                    "Colors".to_string(),
+                    "IColorsStatics".to_string(),
+                ]))),
+            })
+            .unwrap();
+        limits
+            .rename("windows.ui", "ColorHelper", "Helper")
+            .unwrap();
+
+        let root = TypeTree::from_limits(reader, &limits);
+        let ui = &root.namespaces.0["Windows"].namespaces.0["UI"];
+
+        assert!(ui.types.iter().any(|t| t.name().name == "ColorHelper"));
+        assert!(!ui.types.iter().any(|t| t.name().name == "Colors"));
+        assert!(!ui.types.iter().any(|t| t.name().name == "IColorsStatics"));
+        assert_eq!(
+            ui.aliases,
+            [("ColorHelper".to_string(), "Helper".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_nested_architecture_variants() {
+        let reader = winmd::TypeReader::get();
+        let mut limits = TypeLimits::new(reader);
+        limits
+            .insert(NamespaceTypes {
+                namespace: "windows.win32.system.kernel",
+                limit: TypeLimit::Some(vec!["SLIST_HEADER".to_string()]),
+            })
+            .unwrap();
+        limits
+            .insert(NamespaceTypes {
+                namespace: "windows.win32.system.diagnostics.debug",
+                limit: TypeLimit::Some(vec!["CONTEXT".to_string()]),
+            })
+            .unwrap();
+
+        let root = TypeTree::from_limits(reader, &limits);
+        let system = &root.namespaces.0["Windows"].namespaces.0["Win32"]
+            .namespaces
+            .0["System"];
+        let kernel = &system.namespaces.0["Kernel"];
+        let debug = &system.namespaces.0["Diagnostics"].namespaces.0["Debug"];
+
+        // Each target sees exactly one definition of every struct, and the nested types held
+        // by its fields are the ones generated for that same target. Other types are
+        // referred to by name and all of their variants are generated.
+        for (flag, target) in [(1, "x86"), (2, "x86_64"), (4, "aarch64")] {
+            let is_active =
+                |def: &winmd::TypeDef| type_architectures(def).map_or(true, |a| a & flag != 0);
+
+            for (outer, types) in [("SLIST_HEADER", kernel), ("CONTEXT", debug)] {
+                let structs: Vec<_> = types
+                    .types
+                    .iter()
+                    .filter_map(|t| match t {
+                        TypeDefinition::Struct(t) if is_active(&t.name.def) => Some(t),
+                        _ => None,
+                    })
+                    .collect();
+
+                let mut names: Vec<_> = structs.iter().map(|t| t.name.name).collect();
+                names.sort_unstable();
+                let len = names.len();
+                names.dedup();
+                assert_eq!(names.len(), len, "{} on {}", outer, target);
+                assert!(names.contains(&outer), "{} on {}", outer, target);
+
+                for t in &structs {
+                    for (_, field) in &t.fields {
+                        if let TypeKind::Struct(name) = &field.kind {
+                            if name.def.enclosing_type().is_none() {
+                                continue;
+                            }
+
+                            assert!(is_active(&name.def), "{} on {}", name.name, target);
+                        }
+                    }
+
+                    t.gen();
+                }
+            }
+        }
+    }
 }
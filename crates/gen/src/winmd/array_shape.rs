@@ -0,0 +1,21 @@
+/// The dimensions of an `ELEMENT_TYPE_ARRAY` signature
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArrayShape {
+    /// The number of dimensions
+    pub rank: u32,
+    /// The size of each leading dimension that has a size
+    pub sizes: Vec<u32>,
+    /// The lower bound of each leading dimension that has a lower bound
+    pub lower_bounds: Vec<i32>,
+}
+
+impl ArrayShape {
+    /// The total number of elements, if every dimension has a size
+    pub fn element_count(&self) -> Option<usize> {
+        if self.sizes.len() as u32 != self.rank {
+            return None;
+        }
+
+        Some(self.sizes.iter().map(|size| *size as usize).product())
+    }
+}
@@ -15,6 +15,12 @@ impl Attribute {
     }
 
     pub fn args(&self) -> Vec<(String, AttributeArg)> {
+        self.try_args().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Reads the attribute's arguments, failing when an argument's enum type isn't defined by the
+    /// loaded metadata since the size of its value is then unknown.
+    pub fn try_args(&self) -> Result<Vec<(String, AttributeArg)>, WinmdError> {
         let (mut sig, mut values) = match self.constructor() {
             AttributeType::MethodDef(method) => (
                 self.reader.blob(method.row, 4),
@@ -36,47 +42,8 @@ impl Attribute {
         let mut args: Vec<(String, AttributeArg)> = Vec::with_capacity(fixed_arg_count as usize);
 
         for _ in 0..fixed_arg_count {
-            let arg = match ElementType::from_blob(&mut sig) {
-                ElementType::I8 => AttributeArg::I8(values.read_i8()),
-                ElementType::U8 => AttributeArg::U8(values.read_u8()),
-                ElementType::I16 => AttributeArg::I16(values.read_i16()),
-                ElementType::U16 => AttributeArg::U16(values.read_u16()),
-                ElementType::I32 => AttributeArg::I32(values.read_i32()),
-                ElementType::U32 => AttributeArg::U32(values.read_u32()),
-                ElementType::I64 => AttributeArg::I64(values.read_i64()),
-                ElementType::U64 => AttributeArg::U64(values.read_u64()),
-                ElementType::String => AttributeArg::String(values.read_str().to_string()),
-                ElementType::Struct(type_def_or_ref) | ElementType::Class(type_def_or_ref) => {
-                    let (namespace, type_name) = match type_def_or_ref {
-                        TypeDefOrRef::TypeDef(type_def) => type_def.name(),
-                        TypeDefOrRef::TypeRef(type_ref) => type_ref.name(),
-                        _ => panic!("Expected a TypeDef or TypeRef"),
-                    };
-
-                    if namespace == "System" && type_name == "Type" {
-                        let name = values.read_str();
-                        let index = name.rfind('.').unwrap();
-                        AttributeArg::TypeDef(
-                            self.reader
-                                .expect_type_def((&name[0..index], &name[index + 1..])),
-                        )
-                    } else {
-                        let def = match type_def_or_ref {
-                            TypeDefOrRef::TypeRef(value) => {
-                                self.reader.expect_type_def(value.name())
-                            }
-                            TypeDefOrRef::TypeDef(value) => value,
-                            TypeDefOrRef::TypeSpec(_) => panic!("Unsupported underlying type"),
-                        };
-
-                        let underlying_type = def.underlying_type();
-                        read_enum(&underlying_type, &mut values)
-                    }
-                }
-                _ => panic!("Unexpected fixed attribute argument type"),
-            };
-
-            args.push((String::new(), arg));
+            let arg_type = self.sig_arg_type(&mut sig, &values)?;
+            args.push((String::new(), self.read_arg(&arg_type, &mut values)?));
         }
 
         let named_arg_count = values.read_u16();
@@ -88,31 +55,201 @@ impl Attribute {
                 id == 0x53 || id == 0x54,
                 "A NamedArg must start with an id of 0x53 (Field) or 0x54 (Property)"
             );
-            let arg_type = values.read_u8();
-            let name = values.read_str().to_string();
-            let arg = match arg_type {
-                0x02 => AttributeArg::Bool(values.read_u8() != 0),
-                0x08 => AttributeArg::I32(values.read_i32()),
-                0x0E => AttributeArg::String(values.read_str().to_string()),
-                0x50 => {
-                    let name = values.read_str();
-                    let index = name.rfind('.').unwrap();
-                    AttributeArg::TypeDef(
-                        self.reader
-                            .expect_type_def((&name[0..index], &name[index + 1..])),
+            let arg_type = self.read_arg_type(&mut values)?;
+            let name = read_ser_string(&mut values).unwrap_or_default().to_string();
+            args.push((name, self.read_arg(&arg_type, &mut values)?));
+        }
+
+        Ok(args)
+    }
+
+    /// Reads the type of a fixed argument from the constructor's signature.
+    fn sig_arg_type(&self, sig: &mut Blob, values: &Blob) -> Result<ArgType, WinmdError> {
+        if sig.read_expected(0x1D) {
+            return Ok(ArgType::Array(Box::new(self.sig_arg_type(sig, values)?)));
+        }
+
+        if sig.read_expected(0x1C) {
+            return Ok(ArgType::Object);
+        }
+
+        match ElementType::from_blob(sig) {
+            ElementType::Struct(type_def_or_ref) | ElementType::Class(type_def_or_ref) => {
+                if type_def_or_ref.name() == ("System", "Type") {
+                    return Ok(ArgType::Type);
+                }
+
+                let def = match type_def_or_ref {
+                    TypeDefOrRef::TypeDef(def) => Some(def),
+                    TypeDefOrRef::TypeRef(type_ref) => match type_ref.scope() {
+                        ResolutionScope::TypeRef(_) => type_ref.find_resolved(),
+                        _ => return enum_arg_type(self.reader, type_ref.name(), values),
+                    },
+                    TypeDefOrRef::TypeSpec(_) => None,
+                };
+
+                def.map(ArgType::Enum)
+                    .ok_or_else(|| invalid(values, "attribute argument type is not supported"))
+            }
+            element_type => Ok(ArgType::Element(element_type)),
+        }
+    }
+
+    /// Reads a `FieldOrPropType`, as used by named and boxed arguments.
+    fn read_arg_type(&self, values: &mut Blob) -> Result<ArgType, WinmdError> {
+        match values.read_u8() {
+            0x1D => Ok(ArgType::Array(Box::new(self.read_arg_type(values)?))),
+            0x50 => Ok(ArgType::Type),
+            0x51 => Ok(ArgType::Object),
+            0x55 => {
+                let name = read_ser_string(values)
+                    .ok_or_else(|| invalid(values, "enum argument type has no name"))?;
+                let name = type_name(name);
+
+                match name.rfind('.') {
+                    Some(index) if !name.contains('+') => {
+                        enum_arg_type(self.reader, (&name[0..index], &name[index + 1..]), values)
+                    }
+                    _ => self
+                        .type_def_from_name(name)
+                        .map(ArgType::Enum)
+                        .ok_or_else(|| {
+                            invalid(values, "enum argument type is not defined by the metadata")
+                        }),
+                }
+            }
+            code => Ok(ArgType::Element(ElementType::from_code(code as u32))),
+        }
+    }
+
+    fn read_arg(&self, arg_type: &ArgType, values: &mut Blob) -> Result<AttributeArg, WinmdError> {
+        Ok(match arg_type {
+            ArgType::Element(element_type) => read_element(element_type, values)?,
+            ArgType::Type => match read_ser_string(values) {
+                Some(name) => match self.type_def_from_name(name) {
+                    Some(def) => AttributeArg::TypeDef(def),
+                    None => AttributeArg::TypeName(type_name(name).to_string()),
+                },
+                None => AttributeArg::Null,
+            },
+            ArgType::Object => {
+                let arg_type = self.read_arg_type(values)?;
+                self.read_arg(&arg_type, values)?
+            }
+            ArgType::Enum(def) => AttributeArg::Enum(
+                *def,
+                Box::new(read_element(&def.underlying_type(), values)?),
+            ),
+            ArgType::Array(element_type) => {
+                let len = values.read_u32();
+
+                if len == 0xFFFF_FFFF {
+                    AttributeArg::Null
+                } else {
+                    AttributeArg::Array(
+                        (0..len)
+                            .map(|_| self.read_arg(element_type, values))
+                            .collect::<Result<_, _>>()?,
                     )
                 }
-                _ => panic!("Unexpected named attribute argument type"),
-            };
-            args.push((name, arg));
+            }
+        })
+    }
+
+    /// Resolves a serialized type name such as `Namespace.Outer+Inner, Assembly`, returning `None`
+    /// when the type isn't defined by the loaded metadata.
+    fn type_def_from_name(&self, name: &str) -> Option<TypeDef> {
+        let mut names = type_name(name).split('+');
+        let outer = names.next()?;
+        let index = outer.rfind('.')?;
+        let mut def = self
+            .reader
+            .find_type_def((&outer[0..index], &outer[index + 1..]))?;
+
+        for nested in names {
+            let enclosing = def;
+            def = enclosing
+                .nested_types()
+                .find(|def| self.reader.str(def.row, 1) == nested)?;
+        }
+
+        Some(def)
+    }
+}
+
+/// The type of a custom attribute argument as described by ECMA-335 II.23.3
+enum ArgType {
+    Element(ElementType),
+    Type,
+    Object,
+    Enum(TypeDef),
+    Array(Box<ArgType>),
+}
+
+/// Enums defined outside of the loaded metadata cannot be resolved, so only well-known enums, such
+/// as those in `System.Runtime.InteropServices` and the attribute enums of
+/// `Windows.Foundation.Metadata`, whose underlying type is known to be a signed 32-bit integer are
+/// accepted without a definition.
+fn enum_arg_type(
+    reader: &'static TypeReader,
+    name: (&str, &str),
+    values: &Blob,
+) -> Result<ArgType, WinmdError> {
+    if let Some(def) = reader.find_type_def(name) {
+        return Ok(ArgType::Enum(def));
+    }
+
+    match name {
+        ("System", "AttributeTargets")
+        | ("Windows.Foundation.Metadata", "CompositionType")
+        | ("Windows.Foundation.Metadata", "DeprecationType")
+        | ("Windows.Foundation.Metadata", "MarshalingType")
+        | ("Windows.Foundation.Metadata", "Platform")
+        | ("Windows.Foundation.Metadata", "ThreadingModel")
+        | ("Windows.Win32.Interop", "Architecture")
+        | ("System.Runtime.InteropServices", "CallingConvention")
+        | ("System.Runtime.InteropServices", "CharSet")
+        | ("System.Runtime.InteropServices", "LayoutKind")
+        | ("System.Runtime.InteropServices", "UnmanagedType") => {
+            Ok(ArgType::Element(ElementType::I32))
         }
+        _ => Err(invalid(
+            values,
+            "enum argument type is not defined by the metadata",
+        )),
+    }
+}
 
-        args
+/// Strips the assembly qualification from a serialized type name.
+fn type_name(name: &str) -> &str {
+    name.split(',').next().unwrap_or_default().trim()
+}
+
+fn invalid(blob: &Blob, message: &'static str) -> WinmdError {
+    WinmdError::invalid(
+        &blob.reader.files[blob.file_index as usize].path,
+        WinmdStructure::Blob,
+        blob.offset as u32,
+        message,
+    )
+}
+
+/// Reads a `SerString`, which is `None` when the string is null.
+fn read_ser_string(blob: &mut Blob) -> Option<&'static str> {
+    if blob.peek_u8() == 0xFF {
+        blob.offset += 1;
+        None
+    } else {
+        Some(blob.read_str())
     }
 }
 
-fn read_enum(element_type: &ElementType, blob: &mut Blob) -> AttributeArg {
-    match element_type {
+fn read_element(element_type: &ElementType, blob: &mut Blob) -> Result<AttributeArg, WinmdError> {
+    Ok(match element_type {
+        ElementType::Bool => AttributeArg::Bool(blob.read_u8() != 0),
+        ElementType::Char => AttributeArg::Char(
+            std::char::from_u32(blob.read_u16() as u32).unwrap_or(std::char::REPLACEMENT_CHARACTER),
+        ),
         ElementType::I8 => AttributeArg::I8(blob.read_i8()),
         ElementType::U8 => AttributeArg::U8(blob.read_u8()),
         ElementType::I16 => AttributeArg::I16(blob.read_i16()),
@@ -121,6 +258,101 @@ fn read_enum(element_type: &ElementType, blob: &mut Blob) -> AttributeArg {
         ElementType::U32 => AttributeArg::U32(blob.read_u32()),
         ElementType::I64 => AttributeArg::I64(blob.read_i64()),
         ElementType::U64 => AttributeArg::U64(blob.read_u64()),
-        _ => panic!("Invalid underlying enum type encountered!"),
+        ElementType::F32 => AttributeArg::F32(blob.read_f32()),
+        ElementType::F64 => AttributeArg::F64(blob.read_f64()),
+        ElementType::String => match read_ser_string(blob) {
+            Some(value) => AttributeArg::String(value.to_string()),
+            None => AttributeArg::Null,
+        },
+        _ => return Err(invalid(blob, "attribute argument type is not supported")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enum_arg() {
+        let reader = TypeReader::get();
+        let def = reader.expect_type_def(("Windows.Foundation", "Uri"));
+
+        let attribute = def
+            .attributes()
+            .find(|attribute| {
+                attribute.name() == ("Windows.Foundation.Metadata", "MarshalingBehaviorAttribute")
+            })
+            .unwrap();
+
+        match &attribute.args()[0].1 {
+            AttributeArg::Enum(def, value) => {
+                assert_eq!(
+                    def.name(),
+                    ("Windows.Foundation.Metadata", "MarshalingType")
+                );
+                assert!(matches!(**value, AttributeArg::I32(2)));
+            }
+            arg => panic!("Unexpected argument {:?}", arg),
+        }
+    }
+
+    #[test]
+    fn test_unresolved_args() {
+        use writer::{AttributeDefinition, AttributeValue, Metadata, TypeDefinition, TypeName};
+
+        let attribute = |name: &str, args| AttributeDefinition {
+            name: TypeName::new("Test", name),
+            args,
+            named_args: Vec::new(),
+        };
+
+        let metadata = Metadata {
+            name: "Test".to_string(),
+            types: vec![TypeDefinition {
+                namespace: "Test".to_string(),
+                name: "Thing".to_string(),
+                flags: 0x4101,
+                attributes: vec![
+                    attribute(
+                        "TypeAttribute",
+                        vec![AttributeValue::Type(TypeName::new("Other", "Missing"))],
+                    ),
+                    attribute(
+                        "EnumAttribute",
+                        vec![AttributeValue::Enum(
+                            TypeName::new("Other", "Size"),
+                            Box::new(AttributeValue::U8(1)),
+                        )],
+                    ),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let reader = TypeReader::from_bytes(vec![metadata.write()]).unwrap();
+        let reader: &'static TypeReader = Box::leak(Box::new(reader));
+        let def = reader.expect_type_def(("Test", "Thing"));
+        let mut attributes = def.attributes();
+
+        let args = attributes.next().unwrap().args();
+        assert!(matches!(&args[0].1, AttributeArg::TypeName(name) if name == "Other.Missing"));
+
+        assert!(attributes.next().unwrap().try_args().is_err());
+    }
+
+    #[test]
+    fn test_all_args() {
+        let reader = TypeReader::get();
+
+        for namespace in reader.namespaces() {
+            for t in reader.namespace_types(namespace) {
+                if let Type::TypeDef(def) = t {
+                    for attribute in def.attributes() {
+                        attribute.args();
+                    }
+                }
+            }
+        }
     }
 }
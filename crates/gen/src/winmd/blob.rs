@@ -37,6 +37,41 @@ impl Blob {
         value
     }
 
+    /// Read a compressed signed integer, as used by array lower bounds
+    pub fn read_signed(&mut self) -> i32 {
+        let (value, offset) = self.peek_unsigned();
+        self.offset += offset;
+
+        let bits = match offset {
+            1 => 7,
+            2 => 14,
+            _ => 29,
+        };
+
+        if value & 1 == 0 {
+            (value >> 1) as i32
+        } else {
+            (value >> 1) as i32 - (1 << (bits - 1))
+        }
+    }
+
+    /// Read the `ArrayShape` that follows the element type of an `ELEMENT_TYPE_ARRAY`
+    pub fn read_array_shape(&mut self) -> ArrayShape {
+        let rank = self.read_unsigned();
+        let sizes = (0..self.read_unsigned())
+            .map(|_| self.read_unsigned())
+            .collect();
+        let lower_bounds = (0..self.read_unsigned())
+            .map(|_| self.read_signed())
+            .collect();
+
+        ArrayShape {
+            rank,
+            sizes,
+            lower_bounds,
+        }
+    }
+
     pub fn read_expected(&mut self, expected: u32) -> bool {
         let (value, offset) = self.peek_unsigned();
         if value == expected {
@@ -68,12 +103,27 @@ impl Blob {
     }
 
     pub fn read_str(&mut self) -> &'static str {
+        self.try_read_str()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_read_str(&mut self) -> Result<&'static str, WinmdError> {
+        let offset = self.offset;
         let len = self.read_unsigned() as usize;
         self.offset += len;
-        std::str::from_utf8(
-            &self.reader.files[self.file_index as usize].bytes[self.offset - len..self.offset],
-        )
-        .unwrap()
+        let file = &self.reader.files[self.file_index as usize];
+
+        file.bytes
+            .get(self.offset - len..self.offset)
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .ok_or_else(|| {
+                WinmdError::invalid(
+                    &file.path,
+                    WinmdStructure::Blob,
+                    offset as u32,
+                    "invalid UTF-8 string",
+                )
+            })
     }
 
     pub fn read_utf16(&self) -> String {
@@ -96,6 +146,10 @@ impl Blob {
         }
     }
 
+    pub fn peek_u8(&self) -> u8 {
+        self.bytes()[0]
+    }
+
     pub fn read_i8(&mut self) -> i8 {
         let value = i8::from_le_bytes(self.bytes()[..1].try_into().unwrap());
         self.offset += 1;
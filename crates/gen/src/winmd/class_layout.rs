@@ -0,0 +1,22 @@
+use super::*;
+macros::table!(ClassLayout);
+
+impl ClassLayout {
+    pub fn packing_size(&self) -> u32 {
+        self.reader.u32(self.row, 0)
+    }
+
+    pub fn class_size(&self) -> u32 {
+        self.reader.u32(self.row, 1)
+    }
+
+    pub fn parent(&self) -> TypeDef {
+        let index = self.reader.u32(self.row, 2) - 1;
+        let row = Row::new(index, TableIndex::TypeDef, self.row.file_index);
+
+        TypeDef {
+            reader: self.reader,
+            row,
+        }
+    }
+}
@@ -53,6 +53,12 @@ pub enum MemberForwarded {
     MethodDef,
 }
 
+#[type_code(1)]
+pub enum HasSemantics {
+    Event,
+    Property,
+}
+
 #[type_code(2)]
 pub enum ResolutionScope {
     Module,
@@ -88,3 +94,12 @@ impl MemberRefParent {
         }
     }
 }
+
+impl HasSemantics {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Event(value) => value.name(),
+            Self::Property(value) => value.name(),
+        }
+    }
+}
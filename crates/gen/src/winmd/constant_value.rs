@@ -1,6 +1,6 @@
 use squote::{quote, TokenStream};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum ConstantValue {
     U8(u8),
     I8(i8),
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+/// Reads the summaries of an XML documentation file, in the format produced for .NET
+/// assemblies, keyed by member name such as `T:Windows.Foundation.Uri`
+///
+/// The parameter lists of methods are dropped from their names so that overloads share the
+/// summary of the first one. Markup within a summary is reduced to plain text, with
+/// references to other members kept as their names, and characters that Markdown would
+/// interpret are escaped.
+pub(crate) fn read_summaries(xml: &str) -> HashMap<String, String> {
+    let mut summaries = HashMap::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find("<member name=\"") {
+        rest = &rest[start + 14..];
+
+        let name = match rest.find('"') {
+            Some(end) => &rest[..end],
+            None => break,
+        };
+
+        // A self-closing `<member name="..."/>` has no content, so it must not reach into the
+        // next member for a summary.
+        let member = match rest.find('>') {
+            Some(end) if rest[..end].ends_with('/') => &rest[..end],
+            _ => match rest.find("</member>") {
+                Some(end) => &rest[..end],
+                None => rest,
+            },
+        };
+
+        let summary = member
+            .find("<summary>")
+            .and_then(|start| {
+                let summary = &member[start + 9..];
+                summary.find("</summary>").map(|end| &summary[..end])
+            })
+            .map(plain_text)
+            .filter(|summary| !summary.is_empty());
+
+        if let Some(summary) = summary {
+            let name = match name.find('(') {
+                Some(end) => &name[..end],
+                None => name,
+            };
+
+            summaries.entry(decode_entities(name)).or_insert(summary);
+        }
+
+        rest = &rest[member.len()..];
+    }
+
+    summaries
+}
+
+fn plain_text(markup: &str) -> String {
+    let mut text = String::new();
+    let mut rest = markup;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&escape_markdown(&decode_entities(&rest[..start])));
+        rest = &rest[start..];
+
+        let end = match rest.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        // References like `<see cref="T:Windows.Foundation.Uri"/>` become the name they refer to.
+        if let Some(reference) = attribute(tag, "cref").or_else(|| attribute(tag, "name")) {
+            let reference = match reference.find(':') {
+                Some(1) => &reference[2..],
+                _ => reference,
+            };
+
+            text.push('`');
+            text.push_str(&decode_entities(reference));
+            text.push('`');
+        } else {
+            text.push(' ');
+        }
+    }
+
+    text.push_str(&escape_markdown(&decode_entities(rest)));
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Escapes the characters that would otherwise be read as Markdown or HTML by rustdoc.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>') {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let value = &tag[tag.find(&format!("{}=\"", name))? + name.len() + 2..];
+    Some(&value[..value.find('"')?])
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summaries() {
+        let summaries = read_summaries(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+            <doc>
+              <members>
+                <member name="T:Windows.Foundation.Uri">
+                  <summary>Defines an object that represents a
+                    Uniform Resource Identifier (URI) value.</summary>
+                  <remarks>Not part of the summary.</remarks>
+                </member>
+                <member name="M:Windows.Foundation.Uri.#ctor(System.String)">
+                  <summary>Initializes a new <see cref="T:Windows.Foundation.Uri" /> from
+                    <paramref name="uri"/> &amp; friends.</summary>
+                </member>
+                <member name="M:Windows.Foundation.Uri.#ctor(System.String,System.String)">
+                  <summary>Not used since overloads share a summary.</summary>
+                </member>
+                <member name="P:Windows.Foundation.Uri.Host">
+                  <remarks>Members without a summary are skipped.</remarks>
+                </member>
+                <member name="P:Windows.Foundation.Uri.Port"/>
+                <member name="P:Windows.Foundation.Uri.Query">
+                  <summary>The &lt;query&gt; of [the] URI, or *nothing*.</summary>
+                </member>
+              </members>
+            </doc>"#,
+        );
+
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(
+            summaries["T:Windows.Foundation.Uri"],
+            "Defines an object that represents a Uniform Resource Identifier (URI) value."
+        );
+        assert_eq!(
+            summaries["M:Windows.Foundation.Uri.#ctor"],
+            "Initializes a new `Windows.Foundation.Uri` from `uri` & friends."
+        );
+        assert_eq!(
+            summaries["P:Windows.Foundation.Uri.Query"],
+            r"The \<query\> of \[the\] URI, or \*nothing\*."
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+
+/// The part of a Windows Metadata file that failed to parse
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WinmdStructure {
+    DosHeader,
+    PeHeader,
+    SectionTable,
+    CliHeader,
+    MetadataHeader,
+    StreamTable,
+    TableStream,
+    TableRow,
+    StringHeap,
+    Blob,
+}
+
+/// An error encountered while reading a Windows Metadata file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WinmdError {
+    /// The file could not be read from disk
+    Io { path: PathBuf, message: String },
+    /// The file was read but its contents are malformed
+    Invalid {
+        path: PathBuf,
+        offset: u32,
+        structure: WinmdStructure,
+        message: &'static str,
+    },
+}
+
+impl WinmdError {
+    pub(crate) fn invalid<P: Into<PathBuf>>(
+        path: P,
+        structure: WinmdStructure,
+        offset: u32,
+        message: &'static str,
+    ) -> Self {
+        Self::Invalid {
+            path: path.into(),
+            offset,
+            structure,
+            message,
+        }
+    }
+
+    /// The path of the file that failed to parse
+    pub fn path(&self) -> &std::path::Path {
+        match self {
+            Self::Io { path, .. } => path,
+            Self::Invalid { path, .. } => path,
+        }
+    }
+
+    /// The byte offset into the file where parsing failed, if known
+    pub fn offset(&self) -> Option<u32> {
+        match self {
+            Self::Io { .. } => None,
+            Self::Invalid { offset, .. } => Some(*offset),
+        }
+    }
+
+    /// The structure that failed to parse, if known
+    pub fn structure(&self) -> Option<WinmdStructure> {
+        match self {
+            Self::Io { .. } => None,
+            Self::Invalid { structure, .. } => Some(*structure),
+        }
+    }
+}
+
+impl std::fmt::Display for WinmdStructure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::DosHeader => "DOS header",
+            Self::PeHeader => "PE header",
+            Self::SectionTable => "section table",
+            Self::CliHeader => "CLI header",
+            Self::MetadataHeader => "metadata header",
+            Self::StreamTable => "stream table",
+            Self::TableStream => "table stream",
+            Self::TableRow => "table row",
+            Self::StringHeap => "string heap",
+            Self::Blob => "blob",
+        })
+    }
+}
+
+impl std::fmt::Display for WinmdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, message } => {
+                write!(f, "Could not read file {:?}: {}", path, message)
+            }
+            Self::Invalid {
+                path,
+                offset,
+                structure,
+                message,
+            } => write!(
+                f,
+                "Invalid winmd file {:?}: {} at offset 0x{:X} ({})",
+                path, structure, offset, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WinmdError {}
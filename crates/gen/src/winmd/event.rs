@@ -0,0 +1,38 @@
+use super::*;
+macros::table!(Event);
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        self.reader.str(self.row, 1)
+    }
+
+    pub fn event_type(&self) -> TypeDefOrRef {
+        self.reader.decode(self.row, 2)
+    }
+
+    pub fn methods(&self) -> impl Iterator<Item = MethodSemantics> + '_ {
+        self.reader
+            .equal_range(
+                self.row.file_index,
+                TableIndex::MethodSemantics,
+                2,
+                HasSemantics::Event(*self).encode(),
+            )
+            .map(move |row| MethodSemantics {
+                reader: self.reader,
+                row,
+            })
+    }
+
+    pub fn add_method(&self) -> Option<MethodDef> {
+        self.methods()
+            .find(|method| method.flags().add_on())
+            .map(|method| method.method())
+    }
+
+    pub fn remove_method(&self) -> Option<MethodDef> {
+        self.methods()
+            .find(|method| method.flags().remove_on())
+            .map(|method| method.method())
+    }
+}
@@ -28,4 +28,43 @@ impl Field {
             })
             .next()
     }
+
+    /// The type definition of the value the field holds, either on its own or as the elements
+    /// of an array, unless it holds a pointer or a type the loaded metadata doesn't define
+    ///
+    /// The field belongs to `def`, whose variant defines any nested type the field holds.
+    pub fn value_type(&self, def: &TypeDef) -> Option<TypeDef> {
+        let mut blob = self.sig();
+        blob.read_unsigned();
+        blob.read_modifiers();
+
+        while blob.read_expected(0x14) {
+            blob.read_modifiers();
+        }
+
+        if !blob.read_expected(0x11) {
+            return None;
+        }
+
+        match TypeDefOrRef::decode(blob.reader, blob.read_unsigned(), blob.file_index) {
+            TypeDefOrRef::TypeDef(value) => Some(value),
+            TypeDefOrRef::TypeRef(value) => value.find_resolved_from(def),
+            TypeDefOrRef::TypeSpec(_) => None,
+        }
+    }
+
+    pub fn layout(&self) -> Option<FieldLayout> {
+        self.reader
+            .equal_range(
+                self.row.file_index,
+                TableIndex::FieldLayout,
+                1,
+                self.row.index + 1,
+            )
+            .map(move |row| FieldLayout {
+                reader: self.reader,
+                row,
+            })
+            .next()
+    }
 }
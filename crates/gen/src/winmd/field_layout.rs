@@ -0,0 +1,18 @@
+use super::*;
+macros::table!(FieldLayout);
+
+impl FieldLayout {
+    pub fn offset(&self) -> u32 {
+        self.reader.u32(self.row, 0)
+    }
+
+    pub fn field(&self) -> Field {
+        let index = self.reader.u32(self.row, 1) - 1;
+        let row = Row::new(index, TableIndex::Field, self.row.file_index);
+
+        Field {
+            reader: self.reader,
+            row,
+        }
+    }
+}
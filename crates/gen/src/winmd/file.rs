@@ -1,4 +1,5 @@
 use super::*;
+use std::path::{Path, PathBuf};
 
 #[derive(Default)]
 pub struct TableData {
@@ -11,14 +12,60 @@ pub struct TableData {
 /// A Windows Metadata File
 #[derive(Default)]
 pub struct File {
+    /// The path the file was read from
+    pub(crate) path: PathBuf,
     /// The files raw bytes
-    pub(crate) bytes: Vec<u8>,
-    /// The index of the strings data
-    pub(crate) strings: u32,
-    /// The index of the blobs data
-    pub(crate) blobs: u32,
+    pub(crate) bytes: Bytes,
+    /// The index and size of the strings data
+    pub(crate) strings: (u32, u32),
+    /// The index and size of the blobs data
+    pub(crate) blobs: (u32, u32),
+    /// The index and size of the GUIDs data
+    pub(crate) guids: (u32, u32),
+    /// The index and size of the user strings data
+    pub(crate) user_strings: (u32, u32),
     /// The table data
-    pub(crate) tables: [TableData; 16],
+    pub(crate) tables: [TableData; 23],
+}
+
+/// The raw bytes of a Windows Metadata file
+pub(crate) enum Bytes {
+    /// Bytes embedded in the binary, such as the default metadata
+    Static(&'static [u8]),
+    /// Bytes owned by the file
+    Owned(Vec<u8>),
+    /// A memory-mapped file on disk
+    Mapped(memmap2::Mmap),
+}
+
+impl Default for Bytes {
+    fn default() -> Self {
+        Self::Static(&[])
+    }
+}
+
+impl std::ops::Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Static(bytes) => bytes,
+            Self::Owned(bytes) => bytes,
+            Self::Mapped(bytes) => bytes,
+        }
+    }
+}
+
+impl From<&'static [u8]> for Bytes {
+    fn from(bytes: &'static [u8]) -> Self {
+        Self::Static(bytes)
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Owned(bytes)
+    }
 }
 
 /// A well-known index of data into the winmd tables array
@@ -41,6 +88,13 @@ pub enum TableIndex {
     NestedClass,
     Module,
     AssemblyRef,
+    FieldLayout,
+    ClassLayout,
+    Property,
+    PropertyMap,
+    Event,
+    EventMap,
+    MethodSemantics,
 }
 
 impl TableData {
@@ -74,7 +128,7 @@ impl TableData {
 
     fn set_data(&mut self, data: &mut u32) {
         if self.row_count != 0 {
-            let next = *data + self.row_count * self.row_size;
+            let next = data.saturating_add(self.row_count.saturating_mul(self.row_size));
             self.data = *data;
             *data = next;
         }
@@ -82,76 +136,158 @@ impl TableData {
 }
 
 impl File {
-    pub(crate) fn from_bytes(bytes: Vec<u8>) -> Self {
-        let mut file = Self {
-            bytes,
-            ..Default::default()
+    pub(crate) fn from_bytes<P: Into<PathBuf>, B: Into<Bytes>>(
+        path: P,
+        bytes: B,
+    ) -> Result<Self, WinmdError> {
+        let path = path.into();
+        let bytes = bytes.into();
+        let invalid = |structure, offset, message| {
+            Err(WinmdError::invalid(
+                path.clone(),
+                structure,
+                offset,
+                message,
+            ))
         };
 
-        let dos = file.bytes.view_as::<ImageDosHeader>(0);
+        let dos = match bytes.try_view_as::<ImageDosHeader>(0) {
+            Some(dos) if dos.signature == IMAGE_DOS_SIGNATURE => dos,
+            _ => {
+                return invalid(
+                    WinmdStructure::DosHeader,
+                    0,
+                    "file does not appear to be a winmd file",
+                )
+            }
+        };
 
-        if dos.signature != IMAGE_DOS_SIGNATURE {
-            panic!("Invalid file: file does not appear to be a winmd file");
-        }
+        let pe_offset = dos.lfanew as u32;
 
-        let pe = file.bytes.view_as::<ImageNtHeader>(dos.lfanew as u32);
+        let pe = match bytes.try_view_as::<ImageNtHeader>(pe_offset) {
+            Some(pe) => pe,
+            None => return invalid(WinmdStructure::PeHeader, pe_offset, "truncated PE header"),
+        };
 
-        let (com_virtual_address, sections) = match pe.optional_header.magic {
+        let (com_virtual_address, sections_offset) = match pe.optional_header.magic {
             MAGIC_PE32 => (
                 pe.optional_header.data_directory[IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR as usize]
                     .virtual_address,
-                file.bytes.view_as_slice_of::<ImageSectionHeader>(
-                    dos.lfanew as u32 + sizeof::<ImageNtHeader>(),
-                    pe.file_header.number_of_sections as u32,
-                ),
+                pe_offset + sizeof::<ImageNtHeader>(),
             ),
-            MAGIC_PE32PLUS => (
-                file.bytes
-                    .view_as::<ImageNtHeaderPlus>(dos.lfanew as u32)
-                    .optional_header
-                    .data_directory[IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR as usize]
-                    .virtual_address,
-                file.bytes.view_as_slice_of::<ImageSectionHeader>(
-                    dos.lfanew as u32 + sizeof::<ImageNtHeaderPlus>(),
-                    pe.file_header.number_of_sections as u32,
+            MAGIC_PE32PLUS => match bytes.try_view_as::<ImageNtHeaderPlus>(pe_offset) {
+                Some(pe) => (
+                    pe.optional_header.data_directory
+                        [IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR as usize]
+                        .virtual_address,
+                    pe_offset + sizeof::<ImageNtHeaderPlus>(),
                 ),
-            ),
-            _ => panic!("Invalid file: invalid magic"),
+                None => return invalid(WinmdStructure::PeHeader, pe_offset, "truncated PE header"),
+            },
+            _ => return invalid(WinmdStructure::PeHeader, pe_offset, "invalid magic"),
         };
 
-        let cli = file.bytes.view_as::<ImageCorHeader>(offset_from_rva(
-            section_from_rva(sections, com_virtual_address),
-            com_virtual_address,
-        ));
+        let sections = match bytes.try_view_as_slice_of::<ImageSectionHeader>(
+            sections_offset,
+            pe.file_header.number_of_sections as u32,
+        ) {
+            Some(sections) => sections,
+            None => {
+                return invalid(
+                    WinmdStructure::SectionTable,
+                    sections_offset,
+                    "truncated section table",
+                )
+            }
+        };
 
-        if cli.cb != sizeof::<ImageCorHeader>() {
-            panic!("Invalid file: invalid ImageCorHeader");
-        }
+        let cli_header_offset = match offset_from_rva(sections, com_virtual_address) {
+            Some(offset) => offset,
+            None => {
+                return invalid(
+                    WinmdStructure::CliHeader,
+                    com_virtual_address,
+                    "CLI header is not within any section",
+                )
+            }
+        };
 
-        let cli_offset = offset_from_rva(
-            section_from_rva(sections, cli.meta_data.virtual_address),
-            cli.meta_data.virtual_address,
-        );
+        let cli = match bytes.try_view_as::<ImageCorHeader>(cli_header_offset) {
+            Some(cli) if cli.cb == sizeof::<ImageCorHeader>() => cli,
+            _ => {
+                return invalid(
+                    WinmdStructure::CliHeader,
+                    cli_header_offset,
+                    "invalid ImageCorHeader",
+                )
+            }
+        };
 
-        if file.bytes.copy_as::<u32>(cli_offset) != STORAGE_MAGIC_SIG {
-            panic!("Invalid file: invalid STORAGE_MAGIC_SIG");
+        let cli_offset = match offset_from_rva(sections, cli.meta_data.virtual_address) {
+            Some(offset) => offset,
+            None => {
+                return invalid(
+                    WinmdStructure::MetadataHeader,
+                    cli.meta_data.virtual_address,
+                    "metadata is not within any section",
+                )
+            }
+        };
+
+        if bytes.try_copy_as::<u32>(cli_offset) != Some(STORAGE_MAGIC_SIG) {
+            return invalid(
+                WinmdStructure::MetadataHeader,
+                cli_offset,
+                "invalid STORAGE_MAGIC_SIG",
+            );
         }
 
-        let version_length = file.bytes.copy_as::<u32>(cli_offset + 12);
+        let (version_length, stream_count) = match cli_offset
+            .checked_add(12)
+            .and_then(|offset| bytes.try_copy_as::<u32>(offset))
+            .and_then(|version_length| {
+                let offset = cli_offset.checked_add(version_length)?.checked_add(18)?;
+                Some((version_length, bytes.try_copy_as::<u16>(offset)?))
+            }) {
+            Some(header) => header,
+            None => {
+                return invalid(
+                    WinmdStructure::MetadataHeader,
+                    cli_offset,
+                    "truncated metadata header",
+                )
+            }
+        };
+
         let mut view = cli_offset + version_length + 20;
         let mut tables_data: (u32, u32) = (0, 0);
+        let mut strings_data: (u32, u32) = (0, 0);
+        let mut blobs = (0, 0);
+        let mut guids = (0, 0);
+        let mut user_strings = (0, 0);
+
+        for _ in 0..stream_count {
+            let (stream_offset, stream_size, stream_name) = match (
+                bytes.try_copy_as::<u32>(view),
+                bytes.try_copy_as::<u32>(view + 4),
+                bytes.try_view_as_str(view + 8),
+            ) {
+                (Some(offset), Some(size), Some(name)) => (offset, size, name),
+                _ => return invalid(WinmdStructure::StreamTable, view, "truncated stream header"),
+            };
+
+            let stream_start = match cli_offset.checked_add(stream_offset) {
+                Some(start) if start as usize + stream_size as usize <= bytes.len() => start,
+                _ => return invalid(WinmdStructure::StreamTable, view, "stream is out of bounds"),
+            };
 
-        for _ in 0..file.bytes.copy_as::<u16>(cli_offset + version_length + 18) {
-            let stream_offset = file.bytes.copy_as::<u32>(view);
-            let stream_size = file.bytes.copy_as::<u32>(view + 4);
-            let stream_name = file.bytes.view_as_str(view + 8);
             match stream_name {
-                b"#Strings" => file.strings = cli_offset + stream_offset,
-                b"#Blob" => file.blobs = cli_offset + stream_offset,
-                b"#~" => tables_data = (cli_offset + stream_offset, stream_size),
-                b"#GUID" => {}
-                b"#US" => {}
-                _ => panic!("Invalid file: invalid stream name"),
+                b"#Strings" => strings_data = (stream_start, stream_size),
+                b"#Blob" => blobs = (stream_start, stream_size),
+                b"#~" => tables_data = (stream_start, stream_size),
+                b"#GUID" => guids = (stream_start, stream_size),
+                b"#US" => user_strings = (stream_start, stream_size),
+                _ => return invalid(WinmdStructure::StreamTable, view, "invalid stream name"),
             }
             let mut padding = 4 - stream_name.len() % 4;
             if padding == 0 {
@@ -160,11 +296,38 @@ impl File {
             view += (8 + stream_name.len() + padding) as u32;
         }
 
-        let heap_sizes = file.bytes.copy_as::<u8>(tables_data.0 + 6);
+        let strings =
+            &bytes[strings_data.0 as usize..strings_data.0 as usize + strings_data.1 as usize];
+
+        if let Err(error) = std::str::from_utf8(strings) {
+            return invalid(
+                WinmdStructure::StringHeap,
+                strings_data.0 + error.valid_up_to() as u32,
+                "invalid UTF-8 string",
+            );
+        }
+
+        if tables_data.1 < 24 {
+            return invalid(
+                WinmdStructure::TableStream,
+                tables_data.0,
+                "missing or truncated table stream",
+            );
+        }
+
+        let mut file = Self {
+            strings: strings_data,
+            blobs,
+            guids,
+            user_strings,
+            ..Default::default()
+        };
+
+        let heap_sizes = bytes.copy_as::<u8>(tables_data.0 + 6);
         let string_index_size = if (heap_sizes & 1) == 1 { 4 } else { 2 };
         let guid_index_size = if (heap_sizes >> 1 & 1) == 1 { 4 } else { 2 };
         let blob_index_size = if (heap_sizes >> 2 & 1) == 1 { 4 } else { 2 };
-        let valid_bits = file.bytes.copy_as::<u64>(tables_data.0 + 8);
+        let valid_bits = bytes.copy_as::<u64>(tables_data.0 + 8);
         view = tables_data.0 + 24;
 
         // These tables are unused by WinRT, but needed temporarily to calculate sizes and offsets for subsequent tables.
@@ -174,22 +337,15 @@ impl File {
         let mut unused_assembly_processor = TableData::default();
         let mut unused_assembly_ref_os = TableData::default();
         let mut unused_assembly_ref_processor = TableData::default();
-        let mut unused_class_layout = TableData::default();
         let mut unused_decl_security = TableData::default();
-        let mut unused_event = TableData::default();
-        let mut unused_event_map = TableData::default();
         let mut unused_exported_type = TableData::default();
-        let mut unused_field_layout = TableData::default();
         let mut unused_field_marshal = TableData::default();
         let mut unused_field_rva = TableData::default();
         let mut unused_file = TableData::default();
         let mut unused_generic_param_constraint = TableData::default();
         let mut unused_manifest_resource = TableData::default();
         let mut unused_method_impl = TableData::default();
-        let mut unused_method_semantics = TableData::default();
         let mut unused_method_spec = TableData::default();
-        let mut unused_property = TableData::default();
-        let mut unused_property_map = TableData::default();
         let mut unused_standalone_sig = TableData::default();
 
         for i in 0..64 {
@@ -197,7 +353,12 @@ impl File {
                 continue;
             }
 
-            let row_count = file.bytes.copy_as::<u32>(view);
+            let row_count = match bytes.try_copy_as::<u32>(view) {
+                Some(row_count) => row_count,
+                None => {
+                    return invalid(WinmdStructure::TableStream, view, "truncated table stream")
+                }
+            };
             view += 4;
 
             match i {
@@ -213,14 +374,14 @@ impl File {
                 0x0c => file.tables[TableIndex::CustomAttribute as usize].row_count = row_count,
                 0x0d => unused_field_marshal.row_count = row_count,
                 0x0e => unused_decl_security.row_count = row_count,
-                0x0f => unused_class_layout.row_count = row_count,
-                0x10 => unused_field_layout.row_count = row_count,
+                0x0f => file.tables[TableIndex::ClassLayout as usize].row_count = row_count,
+                0x10 => file.tables[TableIndex::FieldLayout as usize].row_count = row_count,
                 0x11 => unused_standalone_sig.row_count = row_count,
-                0x12 => unused_event_map.row_count = row_count,
-                0x14 => unused_event.row_count = row_count,
-                0x15 => unused_property_map.row_count = row_count,
-                0x17 => unused_property.row_count = row_count,
-                0x18 => unused_method_semantics.row_count = row_count,
+                0x12 => file.tables[TableIndex::EventMap as usize].row_count = row_count,
+                0x14 => file.tables[TableIndex::Event as usize].row_count = row_count,
+                0x15 => file.tables[TableIndex::PropertyMap as usize].row_count = row_count,
+                0x17 => file.tables[TableIndex::Property as usize].row_count = row_count,
+                0x18 => file.tables[TableIndex::MethodSemantics as usize].row_count = row_count,
                 0x19 => unused_method_impl.row_count = row_count,
                 0x1a => file.tables[TableIndex::ModuleRef as usize].row_count = row_count,
                 0x1b => file.tables[TableIndex::TypeSpec as usize].row_count = row_count,
@@ -239,7 +400,7 @@ impl File {
                 0x2a => file.tables[TableIndex::GenericParam as usize].row_count = row_count,
                 0x2b => unused_method_spec.row_count = row_count,
                 0x2c => unused_generic_param_constraint.row_count = row_count,
-                _ => unreachable!(),
+                _ => return invalid(WinmdStructure::TableStream, view - 4, "unknown table"),
             };
         }
 
@@ -252,7 +413,7 @@ impl File {
         let has_constant = composite_index_size(&[
             &file.tables[TableIndex::Field as usize],
             &file.tables[TableIndex::Param as usize],
-            &unused_property,
+            &file.tables[TableIndex::Property as usize],
         ]);
 
         let has_custom_attribute = composite_index_size(&[
@@ -264,8 +425,8 @@ impl File {
             &file.tables[TableIndex::InterfaceImpl as usize],
             &file.tables[TableIndex::MemberRef as usize],
             &file.tables[TableIndex::Module as usize],
-            &unused_property,
-            &unused_event,
+            &file.tables[TableIndex::Property as usize],
+            &file.tables[TableIndex::Event as usize],
             &unused_standalone_sig,
             &file.tables[TableIndex::ModuleRef as usize],
             &file.tables[TableIndex::TypeSpec as usize],
@@ -298,7 +459,10 @@ impl File {
             &file.tables[TableIndex::TypeSpec as usize],
         ]);
 
-        let has_semantics = composite_index_size(&[&unused_event, &unused_property]);
+        let has_semantics = composite_index_size(&[
+            &file.tables[TableIndex::Event as usize],
+            &file.tables[TableIndex::Property as usize],
+        ]);
 
         let method_def_or_ref = composite_index_size(&[
             &file.tables[TableIndex::MethodDef as usize],
@@ -370,7 +534,7 @@ impl File {
             0,
             0,
         );
-        unused_class_layout.set_columns(
+        file.tables[TableIndex::ClassLayout as usize].set_columns(
             2,
             4,
             file.tables[TableIndex::TypeDef as usize].index_size(),
@@ -395,15 +559,22 @@ impl File {
             0,
         );
         unused_decl_security.set_columns(2, has_decl_security, blob_index_size, 0, 0, 0);
-        unused_event_map.set_columns(
+        file.tables[TableIndex::EventMap as usize].set_columns(
             file.tables[TableIndex::TypeDef as usize].index_size(),
-            unused_event.index_size(),
+            file.tables[TableIndex::Event as usize].index_size(),
             0,
             0,
             0,
             0,
         );
-        unused_event.set_columns(2, string_index_size, type_def_or_ref, 0, 0, 0);
+        file.tables[TableIndex::Event as usize].set_columns(
+            2,
+            string_index_size,
+            type_def_or_ref,
+            0,
+            0,
+            0,
+        );
         unused_exported_type.set_columns(
             4,
             4,
@@ -420,7 +591,7 @@ impl File {
             0,
             0,
         );
-        unused_field_layout.set_columns(
+        file.tables[TableIndex::FieldLayout as usize].set_columns(
             4,
             file.tables[TableIndex::Field as usize].index_size(),
             0,
@@ -495,7 +666,7 @@ impl File {
             0,
             0,
         );
-        unused_method_semantics.set_columns(
+        file.tables[TableIndex::MethodSemantics as usize].set_columns(
             2,
             file.tables[TableIndex::MethodDef as usize].index_size(),
             has_semantics,
@@ -522,10 +693,17 @@ impl File {
             0,
         );
         file.tables[TableIndex::Param as usize].set_columns(2, 2, string_index_size, 0, 0, 0);
-        unused_property.set_columns(2, string_index_size, blob_index_size, 0, 0, 0);
-        unused_property_map.set_columns(
+        file.tables[TableIndex::Property as usize].set_columns(
+            2,
+            string_index_size,
+            blob_index_size,
+            0,
+            0,
+            0,
+        );
+        file.tables[TableIndex::PropertyMap as usize].set_columns(
             file.tables[TableIndex::TypeDef as usize].index_size(),
-            unused_property.index_size(),
+            file.tables[TableIndex::Property as usize].index_size(),
             0,
             0,
             0,
@@ -562,14 +740,14 @@ impl File {
         file.tables[TableIndex::CustomAttribute as usize].set_data(&mut view);
         unused_field_marshal.set_data(&mut view);
         unused_decl_security.set_data(&mut view);
-        unused_class_layout.set_data(&mut view);
-        unused_field_layout.set_data(&mut view);
+        file.tables[TableIndex::ClassLayout as usize].set_data(&mut view);
+        file.tables[TableIndex::FieldLayout as usize].set_data(&mut view);
         unused_standalone_sig.set_data(&mut view);
-        unused_event_map.set_data(&mut view);
-        unused_event.set_data(&mut view);
-        unused_property_map.set_data(&mut view);
-        unused_property.set_data(&mut view);
-        unused_method_semantics.set_data(&mut view);
+        file.tables[TableIndex::EventMap as usize].set_data(&mut view);
+        file.tables[TableIndex::Event as usize].set_data(&mut view);
+        file.tables[TableIndex::PropertyMap as usize].set_data(&mut view);
+        file.tables[TableIndex::Property as usize].set_data(&mut view);
+        file.tables[TableIndex::MethodSemantics as usize].set_data(&mut view);
         unused_method_impl.set_data(&mut view);
         file.tables[TableIndex::ModuleRef as usize].set_data(&mut view);
         file.tables[TableIndex::TypeSpec as usize].set_data(&mut view);
@@ -587,14 +765,208 @@ impl File {
         file.tables[TableIndex::NestedClass as usize].set_data(&mut view);
         file.tables[TableIndex::GenericParam as usize].set_data(&mut view);
 
-        file
+        if view as u64 > tables_data.0 as u64 + tables_data.1 as u64 {
+            return invalid(
+                WinmdStructure::TableRow,
+                tables_data.0,
+                "table rows extend beyond the end of the table stream",
+            );
+        }
+
+        file.bytes = bytes;
+
+        // Nested classes are indexed by the rows of their type definitions when the file is
+        // loaded, so null and out of bounds rows are rejected here.
+        let type_defs = file.type_def_table().row_count;
+        let nested_classes = &file.tables[TableIndex::NestedClass as usize];
+
+        for row in 0..nested_classes.row_count {
+            for column in 0..2 {
+                let index = file.u32(TableIndex::NestedClass, row, column);
+
+                if index == 0 || index > type_defs {
+                    return invalid(
+                        WinmdStructure::TableRow,
+                        nested_classes.data + row * nested_classes.row_size,
+                        "nested class refers to a missing type definition",
+                    );
+                }
+            }
+        }
+
+        file.path = path;
+        Ok(file)
+    }
+
+    pub(crate) fn new<P: AsRef<Path>>(path: P) -> Result<Self, WinmdError> {
+        let path = path.as_ref();
+
+        let error = |error: std::io::Error| WinmdError::Io {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        };
+
+        let file = std::fs::File::open(path).map_err(error)?;
+
+        // This is safe as long as the file isn't modified while it is mapped, which is the same
+        // assumption the rest of the build makes about its inputs.
+        let bytes = unsafe { memmap2::Mmap::map(&file) }.map_err(error)?;
+
+        Self::from_bytes(path, Bytes::Mapped(bytes))
+    }
+
+    /// The path the file was read from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the file was read from disk rather than from memory, such as the default
+    /// metadata embedded in this crate
+    pub fn is_on_disk(&self) -> bool {
+        matches!(self.bytes, Bytes::Mapped(_))
+    }
+
+    /// The module version identifier of the file, which uniquely identifies a specific build
+    /// of the metadata
+    pub fn mvid(&self) -> Option<Guid> {
+        if self.tables[TableIndex::Module as usize].row_count == 0 {
+            return None;
+        }
+
+        self.guid(self.u32(TableIndex::Module, 0, 2))
+    }
+
+    /// Read a GUID from the `#GUID` heap given its 1-based index
+    pub fn guid(&self, index: u32) -> Option<Guid> {
+        let offset = index.checked_sub(1)?.checked_mul(16)?;
+
+        if offset.checked_add(16)? > self.guids.1 {
+            return None;
+        }
+
+        let start = self.guids.0.checked_add(offset)? as usize;
+        Some(Guid::from_bytes(self.bytes.get(start..start + 16)?))
+    }
+
+    /// Read a string from the `#Strings` heap given its offset
+    pub(crate) fn string(&self, offset: u32) -> Result<&str, WinmdError> {
+        let (heap, start) = self.heap(self.strings, offset, WinmdStructure::StringHeap)?;
+
+        heap.iter()
+            .position(|c| *c == b'\0')
+            .and_then(|len| std::str::from_utf8(&heap[..len]).ok())
+            .ok_or_else(|| {
+                WinmdError::invalid(
+                    &self.path,
+                    WinmdStructure::StringHeap,
+                    start,
+                    "string is not terminated within the heap",
+                )
+            })
     }
 
-    pub(crate) fn new<P: AsRef<std::path::Path>>(filename: P) -> Self {
-        let bytes = std::fs::read(filename.as_ref())
-            .unwrap_or_else(|e| panic!("Could not read file {:?}: {:?}", filename.as_ref(), e));
+    /// Find a blob in the `#Blob` heap given its offset, returning the offset and size of its
+    /// data within the file
+    pub(crate) fn blob(&self, offset: u32) -> Result<(usize, usize), WinmdError> {
+        let (heap, start) = self.heap(self.blobs, offset, WinmdStructure::Blob)?;
+        let invalid =
+            |message| WinmdError::invalid(&self.path, WinmdStructure::Blob, start, message);
 
-        Self::from_bytes(bytes)
+        let initial_byte = heap[0];
+
+        let (blob_size, blob_size_bytes) = match initial_byte >> 5 {
+            0..=3 => (initial_byte & 0x7f, 1),
+            4..=5 => (initial_byte & 0x3f, 2),
+            6 => (initial_byte & 0x1f, 4),
+            _ => return Err(invalid("invalid blob size")),
+        };
+
+        let mut blob_size = blob_size as usize;
+
+        for byte in heap
+            .get(1..blob_size_bytes)
+            .ok_or_else(|| invalid("truncated blob size"))?
+        {
+            blob_size = (blob_size << 8) + (*byte as usize);
+        }
+
+        if blob_size_bytes + blob_size > heap.len() {
+            return Err(invalid("blob extends beyond the end of the heap"));
+        }
+
+        Ok((start as usize + blob_size_bytes, blob_size))
+    }
+
+    /// The bytes of a heap, given as its index and size, from the given offset to its end
+    /// along with the index of the offset within the file
+    fn heap(
+        &self,
+        (index, size): (u32, u32),
+        offset: u32,
+        structure: WinmdStructure,
+    ) -> Result<(&[u8], u32), WinmdError> {
+        match index.checked_add(offset) {
+            Some(start) if offset < size => {
+                let end = index as usize + size as usize;
+                Ok((&self.bytes[start as usize..end], start))
+            }
+            _ => Err(WinmdError::invalid(
+                &self.path,
+                structure,
+                index,
+                "index is out of bounds of the heap",
+            )),
+        }
+    }
+
+    /// Read a string from the `#US` heap given its offset
+    pub fn user_string(&self, offset: u32) -> Option<String> {
+        if offset >= self.user_strings.1 {
+            return None;
+        }
+
+        let mut blob = self.user_strings.0 as usize + offset as usize;
+        let end = self.user_strings.0 as usize + self.user_strings.1 as usize;
+        let initial_byte = *self.bytes.get(blob)?;
+
+        let (mut len, len_bytes) = match initial_byte >> 5 {
+            0..=3 => ((initial_byte & 0x7f) as usize, 1),
+            4..=5 => ((initial_byte & 0x3f) as usize, 2),
+            6 => ((initial_byte & 0x1f) as usize, 4),
+            _ => return None,
+        };
+
+        for byte in self.bytes.get(blob + 1..blob + len_bytes)? {
+            len = (len << 8) + *byte as usize;
+        }
+
+        blob += len_bytes;
+
+        if blob + len > end {
+            return None;
+        }
+
+        // Each string is stored as UTF-16 followed by a single byte flagging whether any
+        // character requires special handling, which is of no interest here.
+        let chars = self.bytes[blob..blob + len - len % 2]
+            .chunks_exact(2)
+            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]));
+
+        std::char::decode_utf16(chars)
+            .collect::<Result<String, _>>()
+            .ok()
+    }
+
+    /// Read a value from a specific row and column of one of the tables
+    pub(crate) fn u32(&self, table: TableIndex, row: u32, column: u32) -> u32 {
+        let table = &self.tables[table as usize];
+        let offset = table.data + row * table.row_size + table.columns[column as usize].0;
+        match table.columns[column as usize].1 {
+            1 => self.bytes.copy_as::<u8>(offset) as u32,
+            2 => self.bytes.copy_as::<u16>(offset) as u32,
+            4 => self.bytes.copy_as::<u32>(offset),
+            _ => self.bytes.copy_as::<u64>(offset) as u32,
+        }
     }
 
     pub(crate) fn type_def_table(&self) -> &TableData {
@@ -606,17 +978,13 @@ impl File {
     // }
 }
 
-fn section_from_rva(sections: &[ImageSectionHeader], rva: u32) -> &ImageSectionHeader {
-    sections
-        .iter()
-        .find(|&s| {
-            rva >= s.virtual_address && rva < s.virtual_address + s.physical_address_or_virtual_size
-        })
-        .expect("Invalid file")
-}
+fn offset_from_rva(sections: &[ImageSectionHeader], rva: u32) -> Option<u32> {
+    let section = sections.iter().find(|&s| {
+        rva >= s.virtual_address
+            && (rva as u64) < s.virtual_address as u64 + s.physical_address_or_virtual_size as u64
+    })?;
 
-fn offset_from_rva(section: &ImageSectionHeader, rva: u32) -> u32 {
-    rva - section.virtual_address + section.pointer_to_raw_data
+    (rva - section.virtual_address).checked_add(section.pointer_to_raw_data)
 }
 
 fn sizeof<T>() -> u32 {
@@ -664,36 +1032,7 @@ macro_rules! assert_proper_length {
     };
 }
 
-macro_rules! assert_proper_length_and_alignment {
-    ($self:expr, $t:ty, $cli_offset:expr, $size:expr) => {{
-        assert_proper_length!($self, $t, $cli_offset, $size);
-
-        let ptr = &$self[$cli_offset as usize] as *const u8 as *const $t;
-
-        let properly_aligned = ptr.align_offset(std::mem::align_of::<$t>()) == 0;
-
-        assert!(
-            properly_aligned,
-            "Invalid file: offset {} is not properly aligned to T",
-            $cli_offset
-        );
-        ptr
-    }};
-}
-
 impl View for [u8] {
-    fn view_as<T: Pod>(&self, cli_offset: u32) -> &T {
-        let ptr = assert_proper_length_and_alignment!(self, T, cli_offset, sizeof::<T>());
-
-        unsafe { &*ptr }
-    }
-
-    fn view_as_slice_of<T: Pod>(&self, cli_offset: u32, len: u32) -> &[T] {
-        let ptr = assert_proper_length_and_alignment!(self, T, cli_offset, sizeof::<T>() * len);
-
-        unsafe { std::slice::from_raw_parts(ptr, len as usize) }
-    }
-
     fn copy_as<T: CopyPod>(&self, cli_offset: u32) -> T {
         assert_proper_length!(self, T, cli_offset, sizeof::<T>());
 
@@ -710,14 +1049,48 @@ impl View for [u8] {
         }
     }
 
-    fn view_as_str(&self, cli_offset: u32) -> &[u8] {
-        let buffer = &self[cli_offset as usize..];
-        let index = buffer
-            .iter()
-            .position(|c| *c == b'\0')
-            .expect("Invalid file");
-        &self[cli_offset as usize..cli_offset as usize + index]
+    fn try_view_as<T: Pod>(&self, cli_offset: u32) -> Option<&T> {
+        let ptr = checked_ptr::<T>(self, cli_offset, std::mem::size_of::<T>())?;
+
+        unsafe { Some(&*ptr) }
+    }
+
+    fn try_view_as_slice_of<T: Pod>(&self, cli_offset: u32, len: u32) -> Option<&[T]> {
+        let size = std::mem::size_of::<T>().checked_mul(len as usize)?;
+        let ptr = checked_ptr::<T>(self, cli_offset, size)?;
+
+        unsafe { Some(std::slice::from_raw_parts(ptr, len as usize)) }
     }
+
+    fn try_copy_as<T: CopyPod>(&self, cli_offset: u32) -> Option<T> {
+        let end = (cli_offset as usize).checked_add(std::mem::size_of::<T>())?;
+        let bytes = self.get(cli_offset as usize..end)?;
+
+        unsafe { Some(std::ptr::read_unaligned(bytes.as_ptr() as *const T)) }
+    }
+
+    fn try_view_as_str(&self, cli_offset: u32) -> Option<&[u8]> {
+        let buffer = self.get(cli_offset as usize..)?;
+        let index = buffer.iter().position(|c| *c == b'\0')?;
+        Some(&buffer[..index])
+    }
+}
+
+/// Returns a pointer to `size` bytes at `cli_offset` if they are in bounds and properly aligned for `T`
+fn checked_ptr<T>(bytes: &[u8], cli_offset: u32, size: usize) -> Option<*const T> {
+    let end = (cli_offset as usize).checked_add(size)?;
+
+    if end > bytes.len() {
+        return None;
+    }
+
+    let ptr = bytes[cli_offset as usize..].as_ptr() as *const T;
+
+    if ptr.align_offset(std::mem::align_of::<T>()) != 0 {
+        return None;
+    }
+
+    Some(ptr)
 }
 
 const IMAGE_DOS_SIGNATURE: u16 = 0x5A4D;
@@ -915,26 +1288,73 @@ mod tests {
     fn view_bytes_as_type() {
         let bytes = [1u8, 3, 48, 90];
 
-        let foo = bytes.view_as::<Foo>(0);
+        let foo = bytes.try_view_as::<Foo>(0).unwrap();
         assert_eq!(foo.bar, 0x0301);
         assert_eq!(foo.baz, 48)
     }
 
     #[test]
-    #[should_panic]
-    fn panic_on_unaligned_bytes() {
-        let bytes = [1u8, 3, 48, 90, 90];
+    fn error_on_truncated_stream() {
+        let bytes = include_bytes!("../../default/Windows.WinRT.winmd");
+        let error = File::from_bytes("test.winmd", bytes[..bytes.len() * 3 / 4].to_vec())
+            .err()
+            .unwrap();
+
+        assert_eq!(error.structure(), Some(WinmdStructure::StreamTable));
+        assert!(error.to_string().ends_with("(stream is out of bounds)"));
+    }
+
+    #[test]
+    fn error_on_null_nested_class() {
+        let mut bytes = include_bytes!("../../default/Windows.Win32.winmd").to_vec();
+        let file = File::from_bytes("test.winmd", bytes.clone()).unwrap();
+        let nested_classes = &file.tables[TableIndex::NestedClass as usize];
+        assert!(nested_classes.row_count > 0);
+
+        let offset = nested_classes.data as usize;
+        let size = nested_classes.columns[0].1 as usize;
+        bytes[offset..offset + size].fill(0);
+
+        let error = File::from_bytes("test.winmd", bytes).err().unwrap();
+        assert_eq!(error.structure(), Some(WinmdStructure::TableRow));
+        assert_eq!(error.offset(), Some(nested_classes.data));
+    }
+
+    #[test]
+    fn error_on_bad_heap_index() {
+        let file = File {
+            bytes: b"\0ab\0c".to_vec().into(),
+            strings: (0, 5),
+            blobs: (0, 5),
+            ..Default::default()
+        };
+
+        assert_eq!(file.string(1).unwrap(), "ab");
+        assert!(file.string(5).is_err());
+        assert!(file.string(u32::MAX).is_err());
+
+        let error = file.string(4).err().unwrap();
+        assert_eq!(error.structure(), Some(WinmdStructure::StringHeap));
+
+        assert_eq!(file.blob(0).unwrap(), (1, 0));
+        let error = file.blob(1).err().unwrap();
+        assert_eq!(error.structure(), Some(WinmdStructure::Blob));
+
+        // An index that wraps around past the end of the address space
+        let file = File {
+            bytes: b"\0ab\0c".to_vec().into(),
+            strings: (u32::MAX, 5),
+            ..Default::default()
+        };
 
-        let _ = bytes.view_as::<Foo>(0);
-        let _ = bytes.view_as::<Foo>(1);
+        assert!(file.string(2).is_err());
     }
 
     #[test]
-    #[should_panic]
-    fn panic_on_too_few_bytes() {
+    fn none_on_too_few_bytes() {
         let bytes = [1u8, 3];
 
-        let _ = bytes.view_as::<Foo>(0);
+        assert!(bytes.try_view_as::<Foo>(0).is_none());
     }
 
     #[test]
@@ -944,4 +1364,55 @@ mod tests {
         let foo = bytes.copy_as::<u16>(0);
         assert_eq!(foo, 0x0301);
     }
+
+    #[test]
+    fn try_view_out_of_bounds() {
+        let bytes = [1u8, 3, 48, 90];
+
+        assert!(bytes.try_view_as::<Foo>(2).is_none());
+        assert!(bytes.try_copy_as::<u32>(1).is_none());
+        assert!(bytes.try_view_as_str(4).is_none());
+    }
+
+    #[test]
+    fn error_on_truncated_file() {
+        let error = File::from_bytes("test.winmd", vec![0; 4]).err().unwrap();
+
+        assert_eq!(error.structure(), Some(WinmdStructure::DosHeader));
+        assert_eq!(error.path(), Path::new("test.winmd"));
+    }
+
+    #[test]
+    fn error_on_invalid_signature() {
+        let error = File::from_bytes("test.winmd", vec![0; 256]).err().unwrap();
+
+        assert_eq!(error.structure(), Some(WinmdStructure::DosHeader));
+        assert_eq!(error.offset(), Some(0));
+    }
+
+    #[test]
+    fn read_user_string() {
+        let file = File {
+            // "Hi" is stored as a 5-byte blob: two UTF-16 characters and a trailing flag byte.
+            bytes: vec![0, 5, b'H', 0, b'i', 0, 0].into(),
+            user_strings: (0, 7),
+            ..Default::default()
+        };
+
+        assert_eq!(file.user_string(1).as_deref(), Some("Hi"));
+        assert_eq!(file.user_string(0).as_deref(), Some(""));
+        assert!(file.user_string(7).is_none());
+    }
+
+    #[test]
+    fn read_mvid() {
+        let reader = TypeReader::get();
+
+        for (file, module) in reader.files.iter().zip(reader.modules()) {
+            let mvid = file.mvid().unwrap();
+            assert!(!mvid.is_zero());
+            assert_eq!(mvid, module.mvid());
+            assert_eq!(mvid.to_string().len(), 36);
+        }
+    }
 }
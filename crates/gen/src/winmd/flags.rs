@@ -1,6 +1,7 @@
 use super::*;
 
 pub struct MethodFlags(pub u32);
+pub struct MethodSemanticsFlags(pub u32);
 pub struct TypeFlags(pub u32);
 
 #[derive(Default)]
@@ -15,6 +16,21 @@ impl MethodFlags {
     }
 }
 
+impl MethodSemanticsFlags {
+    pub fn setter(&self) -> bool {
+        self.0 & 0x0001 != 0
+    }
+    pub fn getter(&self) -> bool {
+        self.0 & 0x0002 != 0
+    }
+    pub fn add_on(&self) -> bool {
+        self.0 & 0x0008 != 0
+    }
+    pub fn remove_on(&self) -> bool {
+        self.0 & 0x0010 != 0
+    }
+}
+
 impl TypeFlags {
     pub fn windows_runtime(&self) -> bool {
         self.0 & 0b100_0000_0000_0000 != 0
@@ -96,4 +112,11 @@ pub enum AttributeArg {
     F64(f64),
     String(String),
     TypeDef(TypeDef),
+    /// A type that isn't defined by the loaded metadata, by its full name
+    TypeName(String),
+    /// An enum value along with its underlying integral value
+    Enum(TypeDef, Box<AttributeArg>),
+    Array(Vec<AttributeArg>),
+    /// A null string, type, or array
+    Null,
 }
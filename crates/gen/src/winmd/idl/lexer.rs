@@ -0,0 +1,152 @@
+use super::IdlError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum TokenKind {
+    Ident(String),
+    /// The unparsed text of a numeric literal, which may also be part of a GUID
+    Number(String),
+    Str(String),
+    Punct(char),
+    Eof,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Token {
+    pub kind: TokenKind,
+    pub line: usize,
+    pub column: usize,
+    /// The byte range of the token within the source
+    pub span: (usize, usize),
+}
+
+pub(crate) fn tokenize(source: &str) -> Result<Vec<Token>, IdlError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    let mut line = 1;
+    let mut line_start = 0;
+
+    while offset < bytes.len() {
+        let c = bytes[offset];
+        let column = offset - line_start + 1;
+
+        if c == b'\n' {
+            offset += 1;
+            line += 1;
+            line_start = offset;
+            continue;
+        }
+
+        if c.is_ascii_whitespace() {
+            offset += 1;
+            continue;
+        }
+
+        if source[offset..].starts_with("//") {
+            while offset < bytes.len() && bytes[offset] != b'\n' {
+                offset += 1;
+            }
+            continue;
+        }
+
+        if source[offset..].starts_with("/*") {
+            offset += 2;
+            loop {
+                if offset >= bytes.len() {
+                    return Err(IdlError::new(line, column, "unterminated comment"));
+                }
+                if source[offset..].starts_with("*/") {
+                    offset += 2;
+                    break;
+                }
+                if bytes[offset] == b'\n' {
+                    line += 1;
+                    line_start = offset + 1;
+                }
+                offset += 1;
+            }
+            continue;
+        }
+
+        let start = offset;
+
+        let kind = if c.is_ascii_alphabetic() || c == b'_' {
+            while offset < bytes.len()
+                && (bytes[offset].is_ascii_alphanumeric() || bytes[offset] == b'_')
+            {
+                offset += 1;
+            }
+            TokenKind::Ident(source[start..offset].to_string())
+        } else if c.is_ascii_digit() {
+            while offset < bytes.len()
+                && (bytes[offset].is_ascii_alphanumeric() || bytes[offset] == b'.')
+            {
+                offset += 1;
+            }
+            TokenKind::Number(source[start..offset].to_string())
+        } else if c == b'"' {
+            offset += 1;
+            let mut value = String::new();
+            loop {
+                match source[offset..].chars().next() {
+                    None | Some('\n') => {
+                        return Err(IdlError::new(line, column, "unterminated string"))
+                    }
+                    Some('"') => {
+                        offset += 1;
+                        break;
+                    }
+                    Some('\\') => {
+                        offset += 1;
+                        let escaped = match source[offset..].chars().next() {
+                            Some('n') => '\n',
+                            Some('t') => '\t',
+                            Some('\\') => '\\',
+                            Some('"') => '"',
+                            _ => {
+                                return Err(IdlError::new(
+                                    line,
+                                    offset - line_start + 1,
+                                    "invalid escape sequence",
+                                ))
+                            }
+                        };
+                        value.push(escaped);
+                        offset += 1;
+                    }
+                    Some(c) => {
+                        value.push(c);
+                        offset += c.len_utf8();
+                    }
+                }
+            }
+            TokenKind::Str(value)
+        } else if b"{}[]()<>,;:.=-".contains(&c) {
+            offset += 1;
+            TokenKind::Punct(c as char)
+        } else {
+            let c = source[offset..].chars().next().unwrap();
+            return Err(IdlError::new(
+                line,
+                column,
+                format!("unexpected character `{}`", c),
+            ));
+        };
+
+        tokens.push(Token {
+            kind,
+            line,
+            column,
+            span: (start, offset),
+        });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        line,
+        column: offset - line_start + 1,
+        span: (offset, offset),
+    });
+
+    Ok(tokens)
+}
@@ -0,0 +1,1006 @@
+use super::parser::*;
+use super::IdlError;
+use crate::winmd::writer::*;
+use crate::winmd::ConstantValue;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+const METADATA: &str = "Windows.Foundation.Metadata";
+
+const TYPE_PUBLIC_SEALED: u32 = 0x4101;
+const TYPE_STRUCT: u32 = 0x4109;
+const TYPE_INTERFACE: u32 = 0x40A1;
+const METHOD_ABSTRACT: u32 = 0x05C6;
+const METHOD_SPECIAL: u32 = 0x0800;
+const METHOD_RUNTIME: u32 = 0x0003;
+const PARAM_IN: u32 = 0x1;
+const PARAM_OUT: u32 = 0x2;
+
+/// Enums of the `Windows.Foundation.Metadata` namespace that are commonly passed to attributes
+const METADATA_ENUMS: &[(&str, &[(&str, i64)])] = &[
+    ("CompositionType", &[("Protected", 1), ("Public", 2)]),
+    ("DeprecationType", &[("Deprecate", 0), ("Remove", 1)]),
+    (
+        "MarshalingType",
+        &[
+            ("InvalidMarshaling", 0),
+            ("None", 1),
+            ("Agile", 2),
+            ("Standard", 3),
+        ],
+    ),
+    ("Platform", &[("Windows", 0), ("WindowsPhone", 1)]),
+    (
+        "ThreadingModel",
+        &[("InvalidThreading", 0), ("STA", 1), ("MTA", 2), ("Both", 3)],
+    ),
+];
+
+/// What is known about a type declared in the source
+struct Known {
+    namespace: String,
+    name: String,
+    value_type: bool,
+    generics: usize,
+    /// The variants of an enum along with whether its underlying type is unsigned
+    variants: Option<(bool, HashMap<String, i64>)>,
+}
+
+pub(crate) struct Lowering {
+    known: BTreeMap<String, Known>,
+    types: Vec<TypeDefinition>,
+}
+
+impl Lowering {
+    pub fn new(declarations: &[Declaration]) -> Result<Self, IdlError> {
+        let mut known = BTreeMap::new();
+
+        for declaration in declarations {
+            let full_name = format!("{}.{}", declaration.namespace, declaration.name);
+
+            let (value_type, generics, variants) = match &declaration.kind {
+                DeclarationKind::Enum(variants) => {
+                    let unsigned = is_flags(&declaration.attributes);
+                    let values = enum_values(declaration, variants)?;
+                    (true, 0, Some((unsigned, values.into_iter().collect())))
+                }
+                DeclarationKind::Struct(_) => (true, 0, None),
+                DeclarationKind::Interface { generics, .. } => (false, generics.len(), None),
+                DeclarationKind::Forward { value_type } => (*value_type, 0, None),
+                _ => (false, 0, None),
+            };
+
+            let previous = known.insert(
+                full_name.clone(),
+                Known {
+                    namespace: declaration.namespace.clone(),
+                    name: declaration.name.clone(),
+                    value_type,
+                    generics,
+                    variants,
+                },
+            );
+
+            if previous.is_some() {
+                return Err(IdlError::new(
+                    declaration.line,
+                    declaration.column,
+                    format!("`{}` is defined more than once", full_name),
+                ));
+            }
+        }
+
+        Ok(Self {
+            known,
+            types: Vec::new(),
+        })
+    }
+
+    pub fn lower(mut self, declarations: &[Declaration]) -> Result<Vec<TypeDefinition>, IdlError> {
+        for declaration in declarations {
+            match &declaration.kind {
+                DeclarationKind::Enum(variants) => self.enum_type(declaration, variants)?,
+                DeclarationKind::Struct(fields) => self.struct_type(declaration, fields)?,
+                DeclarationKind::Interface {
+                    generics,
+                    requires,
+                    members,
+                } => self.interface(declaration, generics, requires, members)?,
+                DeclarationKind::Class {
+                    interfaces,
+                    members,
+                } => self.class(declaration, interfaces, members)?,
+                DeclarationKind::Delegate {
+                    return_type,
+                    params,
+                } => self.delegate(declaration, return_type, params)?,
+                DeclarationKind::Forward { .. } => {}
+            }
+        }
+
+        Ok(self.types)
+    }
+
+    fn enum_type(
+        &mut self,
+        declaration: &Declaration,
+        variants: &[(String, Option<i64>)],
+    ) -> Result<(), IdlError> {
+        let unsigned = is_flags(&declaration.attributes);
+        let mut attributes = self.attributes(declaration, &["flags"])?;
+        let this = TypeSig::ValueType(TypeName::new(&declaration.namespace, &declaration.name));
+
+        if unsigned {
+            attributes.push(attribute("System", "FlagsAttribute", Vec::new()));
+        }
+
+        let mut fields = vec![FieldDefinition {
+            name: "value__".to_string(),
+            flags: 0x0601,
+            sig: if unsigned { TypeSig::U32 } else { TypeSig::I32 },
+            constant: None,
+            attributes: Vec::new(),
+        }];
+
+        for (name, value) in enum_values(declaration, variants)? {
+            fields.push(FieldDefinition {
+                name,
+                flags: 0x8056,
+                sig: this.clone(),
+                constant: Some(if unsigned {
+                    ConstantValue::U32(value as u32)
+                } else {
+                    ConstantValue::I32(value as i32)
+                }),
+                attributes: Vec::new(),
+            });
+        }
+
+        self.types.push(TypeDefinition {
+            namespace: declaration.namespace.clone(),
+            name: declaration.name.clone(),
+            flags: TYPE_PUBLIC_SEALED,
+            extends: Some(system("Enum")),
+            fields,
+            attributes,
+            ..Default::default()
+        });
+
+        Ok(())
+    }
+
+    fn struct_type(
+        &mut self,
+        declaration: &Declaration,
+        fields: &[(String, Type)],
+    ) -> Result<(), IdlError> {
+        let attributes = self.attributes(declaration, &[])?;
+        let mut definitions = Vec::new();
+
+        for (name, ty) in fields {
+            definitions.push(FieldDefinition {
+                name: name.clone(),
+                flags: 0x0006,
+                sig: self.ty(ty, &declaration.namespace, &[])?,
+                constant: None,
+                attributes: Vec::new(),
+            });
+        }
+
+        self.types.push(TypeDefinition {
+            namespace: declaration.namespace.clone(),
+            name: declaration.name.clone(),
+            flags: TYPE_STRUCT,
+            extends: Some(system("ValueType")),
+            fields: definitions,
+            attributes,
+            ..Default::default()
+        });
+
+        Ok(())
+    }
+
+    fn delegate(
+        &mut self,
+        declaration: &Declaration,
+        return_type: &Type,
+        params: &[Param],
+    ) -> Result<(), IdlError> {
+        let mut attributes = self.attributes(declaration, &["uuid"])?;
+        attributes.push(self.guid(declaration, &declaration.name)?);
+
+        let constructor = MethodDefinition {
+            name: ".ctor".to_string(),
+            flags: 0x1886,
+            impl_flags: METHOD_RUNTIME,
+            return_type: TypeSig::Void,
+            params: vec![
+                ParamDefinition {
+                    name: "object".to_string(),
+                    flags: PARAM_IN,
+                    sig: TypeSig::Object,
+                },
+                ParamDefinition {
+                    name: "method".to_string(),
+                    flags: PARAM_IN,
+                    sig: TypeSig::ISize,
+                },
+            ],
+            attributes: Vec::new(),
+        };
+
+        let invoke = MethodDefinition {
+            name: "Invoke".to_string(),
+            flags: 0x01C6 | METHOD_SPECIAL,
+            impl_flags: METHOD_RUNTIME,
+            return_type: self.ty(return_type, &declaration.namespace, &[])?,
+            params: self.params(params, &declaration.namespace, &[])?,
+            attributes: Vec::new(),
+        };
+
+        self.types.push(TypeDefinition {
+            namespace: declaration.namespace.clone(),
+            name: declaration.name.clone(),
+            flags: TYPE_PUBLIC_SEALED,
+            extends: Some(system("MulticastDelegate")),
+            methods: vec![constructor, invoke],
+            attributes,
+            ..Default::default()
+        });
+
+        Ok(())
+    }
+
+    fn interface(
+        &mut self,
+        declaration: &Declaration,
+        generics: &[String],
+        requires: &[Type],
+        members: &[Member],
+    ) -> Result<(), IdlError> {
+        let mut attributes = self.attributes(declaration, &["uuid"])?;
+        attributes.push(self.guid(declaration, &declaration.name)?);
+
+        let mut interfaces = Vec::new();
+
+        for ty in requires {
+            interfaces.push(self.ty(ty, &declaration.namespace, generics)?.into());
+        }
+
+        let name = if generics.is_empty() {
+            declaration.name.clone()
+        } else {
+            format!("{}`{}", declaration.name, generics.len())
+        };
+
+        let mut def = TypeDefinition {
+            namespace: declaration.namespace.clone(),
+            name,
+            flags: TYPE_INTERFACE,
+            generics: generics.to_vec(),
+            interfaces,
+            attributes,
+            ..Default::default()
+        };
+
+        for member in members {
+            self.member(&mut def, member, generics)?;
+        }
+
+        self.types.push(def);
+        Ok(())
+    }
+
+    fn class(
+        &mut self,
+        declaration: &Declaration,
+        interfaces: &[Type],
+        members: &[Member],
+    ) -> Result<(), IdlError> {
+        let namespace = &declaration.namespace;
+        let class = TypeName::new(namespace, &declaration.name);
+        let mut attributes = self.attributes(declaration, &[])?;
+        let mut implements = Vec::new();
+
+        let instance: Vec<_> = members
+            .iter()
+            .filter(|member| {
+                !member.is_static && !matches!(member.kind, MemberKind::Constructor { .. })
+            })
+            .collect();
+
+        let statics: Vec<_> = members.iter().filter(|member| member.is_static).collect();
+
+        let constructors: Vec<_> = members
+            .iter()
+            .filter_map(|member| match &member.kind {
+                MemberKind::Constructor { params } => Some((member, params)),
+                _ => None,
+            })
+            .collect();
+
+        // Instance members are defined by a synthesized default interface, which is what
+        // MIDL does for classes with members of their own.
+        if !instance.is_empty() {
+            let name = format!("I{}", declaration.name);
+            let mut def = self.exclusive_interface(declaration, &name)?;
+
+            for member in instance {
+                self.member(&mut def, member, &[])?;
+            }
+
+            self.types.push(def);
+            implements.push(TypeSig::Class(TypeName::new(namespace, &name)));
+        }
+
+        for ty in interfaces {
+            implements.push(self.ty(ty, namespace, &[])?);
+        }
+
+        if !statics.is_empty() {
+            let name = format!("I{}Statics", declaration.name);
+            let mut def = self.exclusive_interface(declaration, &name)?;
+
+            for member in statics {
+                self.member(&mut def, member, &[])?;
+            }
+
+            self.types.push(def);
+            attributes.push(attribute(
+                METADATA,
+                "StaticAttribute",
+                vec![
+                    AttributeValue::Type(TypeName::new(namespace, &name)),
+                    AttributeValue::U32(1),
+                ],
+            ));
+        }
+
+        if constructors.iter().any(|(_, params)| params.is_empty()) {
+            attributes.push(attribute(
+                METADATA,
+                "ActivatableAttribute",
+                vec![AttributeValue::U32(1)],
+            ));
+        }
+
+        if constructors.iter().any(|(_, params)| !params.is_empty()) {
+            let name = format!("I{}Factory", declaration.name);
+            let mut def = self.exclusive_interface(declaration, &name)?;
+
+            for (member, params) in constructors.iter().filter(|(_, params)| !params.is_empty()) {
+                let method = MethodDefinition {
+                    name: "CreateInstance".to_string(),
+                    flags: METHOD_ABSTRACT,
+                    impl_flags: 0,
+                    return_type: TypeSig::Class(class.clone()),
+                    params: self.params(params, namespace, &[])?,
+                    attributes: self.member_attributes(member)?,
+                };
+
+                push_method(&mut def, method);
+            }
+
+            self.types.push(def);
+            attributes.push(attribute(
+                METADATA,
+                "ActivatableAttribute",
+                vec![
+                    AttributeValue::Type(TypeName::new(namespace, &name)),
+                    AttributeValue::U32(1),
+                ],
+            ));
+        }
+
+        let mut implements = implements.into_iter().map(InterfaceImplDefinition::from);
+
+        let interfaces = match implements.next() {
+            Some(mut default) => {
+                default
+                    .attributes
+                    .push(attribute(METADATA, "DefaultAttribute", Vec::new()));
+
+                std::iter::once(default).chain(implements).collect()
+            }
+            None => Vec::new(),
+        };
+
+        self.types.push(TypeDefinition {
+            namespace: namespace.clone(),
+            name: declaration.name.clone(),
+            flags: TYPE_PUBLIC_SEALED,
+            extends: Some(system("Object")),
+            interfaces,
+            attributes,
+            ..Default::default()
+        });
+
+        Ok(())
+    }
+
+    /// Creates an interface that is exclusive to the given class
+    fn exclusive_interface(
+        &self,
+        class: &Declaration,
+        name: &str,
+    ) -> Result<TypeDefinition, IdlError> {
+        if self
+            .known
+            .contains_key(&format!("{}.{}", class.namespace, name))
+        {
+            return Err(IdlError::new(
+                class.line,
+                class.column,
+                format!(
+                    "`{}` conflicts with an interface generated for `{}`",
+                    name, class.name
+                ),
+            ));
+        }
+
+        Ok(TypeDefinition {
+            namespace: class.namespace.clone(),
+            name: name.to_string(),
+            flags: TYPE_INTERFACE,
+            attributes: vec![
+                attribute(
+                    METADATA,
+                    "ExclusiveToAttribute",
+                    vec![AttributeValue::Type(TypeName::new(
+                        &class.namespace,
+                        &class.name,
+                    ))],
+                ),
+                self.guid(class, name)?,
+            ],
+            ..Default::default()
+        })
+    }
+
+    /// Adds a method, property or event along with its accessors to an interface
+    fn member(
+        &self,
+        def: &mut TypeDefinition,
+        member: &Member,
+        generics: &[String],
+    ) -> Result<(), IdlError> {
+        let namespace = def.namespace.clone();
+        let attributes = self.member_attributes(member)?;
+
+        match &member.kind {
+            MemberKind::Method {
+                name,
+                return_type,
+                params,
+            } => {
+                let method = MethodDefinition {
+                    name: name.clone(),
+                    flags: METHOD_ABSTRACT,
+                    impl_flags: 0,
+                    return_type: self.ty(return_type, &namespace, generics)?,
+                    params: self.params(params, &namespace, generics)?,
+                    attributes,
+                };
+
+                push_method(def, method);
+            }
+            MemberKind::Property { name, ty, get, set } => {
+                let sig = self.ty(ty, &namespace, generics)?;
+                let mut property = PropertyDefinition {
+                    name: name.clone(),
+                    sig: sig.clone(),
+                    getter: None,
+                    setter: None,
+                };
+
+                if *get {
+                    property.getter = Some(def.methods.len());
+                    def.methods.push(MethodDefinition {
+                        name: format!("get_{}", name),
+                        flags: METHOD_ABSTRACT | METHOD_SPECIAL,
+                        impl_flags: 0,
+                        return_type: sig.clone(),
+                        params: Vec::new(),
+                        attributes: attributes.clone(),
+                    });
+                }
+
+                if *set {
+                    property.setter = Some(def.methods.len());
+                    def.methods.push(MethodDefinition {
+                        name: format!("put_{}", name),
+                        flags: METHOD_ABSTRACT | METHOD_SPECIAL,
+                        impl_flags: 0,
+                        return_type: TypeSig::Void,
+                        params: vec![ParamDefinition {
+                            name: "value".to_string(),
+                            flags: PARAM_IN,
+                            sig,
+                        }],
+                        attributes,
+                    });
+                }
+
+                def.properties.push(property);
+            }
+            MemberKind::Event { name, ty } => {
+                let event_type = self.ty(ty, &namespace, generics)?;
+                let token = TypeSig::ValueType(TypeName::new(
+                    "Windows.Foundation",
+                    "EventRegistrationToken",
+                ));
+                let add = def.methods.len();
+
+                def.methods.push(MethodDefinition {
+                    name: format!("add_{}", name),
+                    flags: METHOD_ABSTRACT | METHOD_SPECIAL,
+                    impl_flags: 0,
+                    return_type: token.clone(),
+                    params: vec![ParamDefinition {
+                        name: "handler".to_string(),
+                        flags: PARAM_IN,
+                        sig: event_type.clone(),
+                    }],
+                    attributes: attributes.clone(),
+                });
+
+                def.methods.push(MethodDefinition {
+                    name: format!("remove_{}", name),
+                    flags: METHOD_ABSTRACT | METHOD_SPECIAL,
+                    impl_flags: 0,
+                    return_type: TypeSig::Void,
+                    params: vec![ParamDefinition {
+                        name: "token".to_string(),
+                        flags: PARAM_IN,
+                        sig: token,
+                    }],
+                    attributes,
+                });
+
+                def.events.push(EventDefinition {
+                    name: name.clone(),
+                    event_type,
+                    add,
+                    remove: add + 1,
+                });
+            }
+            MemberKind::Constructor { .. } => {
+                return Err(IdlError::new(
+                    member.line,
+                    member.column,
+                    "interfaces cannot have constructors",
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn params(
+        &self,
+        params: &[Param],
+        namespace: &str,
+        generics: &[String],
+    ) -> Result<Vec<ParamDefinition>, IdlError> {
+        let mut definitions = Vec::new();
+
+        for param in params {
+            let sig = self.ty(&param.ty, namespace, generics)?;
+
+            definitions.push(if param.out {
+                ParamDefinition {
+                    name: param.name.clone(),
+                    flags: PARAM_OUT,
+                    sig: TypeSig::ByRef(Box::new(sig)),
+                }
+            } else {
+                ParamDefinition {
+                    name: param.name.clone(),
+                    flags: PARAM_IN,
+                    sig,
+                }
+            });
+        }
+
+        Ok(definitions)
+    }
+
+    fn ty(&self, ty: &Type, namespace: &str, generics: &[String]) -> Result<TypeSig, IdlError> {
+        let (path, args) = match &ty.kind {
+            TypeKind::Array(element) => {
+                return Ok(TypeSig::Array(Box::new(
+                    self.ty(element, namespace, generics)?,
+                )))
+            }
+            TypeKind::Named(path, args) => (path, args),
+        };
+
+        if args.is_empty() {
+            if let Some(index) = generics.iter().position(|generic| generic == path) {
+                return Ok(TypeSig::GenericParam(index as u32));
+            }
+
+            if let Some(sig) = primitive(path) {
+                return Ok(sig);
+            }
+        }
+
+        let mut sigs = Vec::new();
+
+        for arg in args {
+            sigs.push(self.ty(arg, namespace, generics)?);
+        }
+
+        let (name, value_type) = match self.resolve(path, namespace) {
+            Some(known) => {
+                if known.generics != sigs.len() {
+                    return Err(IdlError::new(
+                        ty.line,
+                        ty.column,
+                        format!(
+                            "`{}` expects {} type arguments but {} were given",
+                            path,
+                            known.generics,
+                            sigs.len()
+                        ),
+                    ));
+                }
+
+                (
+                    TypeName::new(&known.namespace, &known.name),
+                    known.value_type,
+                )
+            }
+            None => match split_path(path) {
+                Some((namespace, name)) => (TypeName::new(namespace, name), false),
+                None => {
+                    return Err(IdlError::new(
+                        ty.line,
+                        ty.column,
+                        format!("unknown type `{}`", path),
+                    ));
+                }
+            },
+        };
+
+        Ok(if sigs.is_empty() {
+            if value_type {
+                TypeSig::ValueType(name)
+            } else {
+                TypeSig::Class(name)
+            }
+        } else {
+            let name = TypeName {
+                name: format!("{}`{}", name.name, sigs.len()),
+                ..name
+            };
+
+            TypeSig::GenericInstance(name, sigs)
+        })
+    }
+
+    /// Finds a declared type by its qualified name or by its name relative to the given
+    /// namespace or any of its parents
+    fn resolve(&self, path: &str, namespace: &str) -> Option<&Known> {
+        let mut scope = namespace;
+
+        loop {
+            if let Some(known) = self.known.get(&format!("{}.{}", scope, path)) {
+                return Some(known);
+            }
+
+            match scope.rfind('.') {
+                Some(index) => scope = &scope[..index],
+                None => break,
+            }
+        }
+
+        self.known.get(path)
+    }
+
+    fn attributes(
+        &self,
+        declaration: &Declaration,
+        handled: &[&str],
+    ) -> Result<Vec<AttributeDefinition>, IdlError> {
+        let mut definitions = Vec::new();
+
+        for attribute in &declaration.attributes {
+            if !handled.contains(&attribute.name.as_str()) {
+                definitions.push(self.attribute(attribute, &declaration.namespace)?);
+            }
+        }
+
+        Ok(definitions)
+    }
+
+    fn member_attributes(&self, member: &Member) -> Result<Vec<AttributeDefinition>, IdlError> {
+        member
+            .attributes
+            .iter()
+            .map(|attribute| self.attribute(attribute, ""))
+            .collect()
+    }
+
+    fn attribute(
+        &self,
+        attribute: &Attribute,
+        namespace: &str,
+    ) -> Result<AttributeDefinition, IdlError> {
+        let error = |message: String| IdlError::new(attribute.line, attribute.column, message);
+
+        if attribute.name == "uuid" || attribute.name == "flags" {
+            return Err(error(format!(
+                "`{}` cannot be applied here",
+                attribute.name
+            )));
+        }
+
+        let name = match split_path(&attribute.name) {
+            Some((namespace, name)) if name.ends_with("Attribute") => {
+                TypeName::new(namespace, name)
+            }
+            Some((namespace, name)) => TypeName::new(namespace, &format!("{}Attribute", name)),
+            None => TypeName::new(
+                METADATA,
+                &format!("{}Attribute", to_pascal(&attribute.name)),
+            ),
+        };
+
+        let mut args = Vec::new();
+
+        for arg in &attribute.args {
+            args.push(match arg {
+                Literal::Bool(value) => AttributeValue::Bool(*value),
+                Literal::Str(value) => AttributeValue::String(value.clone()),
+                Literal::Float(value) => AttributeValue::F64(*value),
+                Literal::Int(value) if *value < 0 => match i32::try_from(*value) {
+                    Ok(value) => AttributeValue::I32(value),
+                    Err(_) => AttributeValue::I64(*value),
+                },
+                Literal::Int(value) => match u32::try_from(*value) {
+                    Ok(value) => AttributeValue::U32(value),
+                    Err(_) => AttributeValue::U64(*value as u64),
+                },
+                Literal::Path(path) => self
+                    .attribute_path(path, namespace)
+                    .ok_or_else(|| error(format!("unknown type or enum value `{}`", path)))?,
+                Literal::Guid(_) => return Err(error("unexpected GUID".to_string())),
+            });
+        }
+
+        Ok(AttributeDefinition {
+            name,
+            args,
+            named_args: Vec::new(),
+        })
+    }
+
+    /// Resolves an attribute argument naming either an enum variant or a type
+    fn attribute_path(&self, path: &str, namespace: &str) -> Option<AttributeValue> {
+        if let Some((enum_path, variant)) = split_path(path) {
+            if let Some(known) = self.resolve(enum_path, namespace) {
+                if let Some((unsigned, variants)) = &known.variants {
+                    let value = *variants.get(variant)?;
+
+                    let value = if *unsigned {
+                        AttributeValue::U32(value as u32)
+                    } else {
+                        AttributeValue::I32(value as i32)
+                    };
+
+                    return Some(AttributeValue::Enum(
+                        TypeName::new(&known.namespace, &known.name),
+                        Box::new(value),
+                    ));
+                }
+            }
+
+            let enum_name = enum_path
+                .strip_prefix("Windows.Foundation.Metadata.")
+                .unwrap_or(enum_path);
+
+            if let Some((name, variants)) =
+                METADATA_ENUMS.iter().find(|(name, _)| *name == enum_name)
+            {
+                let (_, value) = variants.iter().find(|(name, _)| *name == variant)?;
+
+                return Some(AttributeValue::Enum(
+                    TypeName::new(METADATA, name),
+                    Box::new(AttributeValue::I32(*value as i32)),
+                ));
+            }
+        }
+
+        match self.resolve(path, namespace) {
+            Some(known) => Some(AttributeValue::Type(TypeName::new(
+                &known.namespace,
+                &known.name,
+            ))),
+            None => split_path(path)
+                .map(|(namespace, name)| AttributeValue::Type(TypeName::new(namespace, name))),
+        }
+    }
+
+    /// The `GuidAttribute` of a type, taken from its `uuid` attribute or else derived from
+    /// the given type name so that it is stable from one build to the next
+    fn guid(&self, declaration: &Declaration, name: &str) -> Result<AttributeDefinition, IdlError> {
+        let uuid = declaration
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name == "uuid" && name == declaration.name);
+
+        let bytes = match uuid {
+            Some(attribute) => match attribute.args.as_slice() {
+                [Literal::Guid(text)] => parse_guid(text).ok_or_else(|| {
+                    IdlError::new(
+                        attribute.line,
+                        attribute.column,
+                        format!("invalid GUID `{}`", text),
+                    )
+                })?,
+                _ => {
+                    return Err(IdlError::new(
+                        attribute.line,
+                        attribute.column,
+                        "expected a GUID",
+                    ))
+                }
+            },
+            None => derive_guid(&declaration.namespace, name),
+        };
+
+        let mut args = vec![
+            AttributeValue::U32(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+            AttributeValue::U16(u16::from_be_bytes([bytes[4], bytes[5]])),
+            AttributeValue::U16(u16::from_be_bytes([bytes[6], bytes[7]])),
+        ];
+
+        args.extend(bytes[8..].iter().map(|byte| AttributeValue::U8(*byte)));
+        Ok(attribute(METADATA, "GuidAttribute", args))
+    }
+}
+
+fn attribute(namespace: &str, name: &str, args: Vec<AttributeValue>) -> AttributeDefinition {
+    AttributeDefinition {
+        name: TypeName::new(namespace, name),
+        args,
+        named_args: Vec::new(),
+    }
+}
+
+/// Adds a method to an interface, naming it apart from any earlier overloads
+fn push_method(def: &mut TypeDefinition, mut method: MethodDefinition) {
+    let overloads = def
+        .methods
+        .iter()
+        .filter(|existing| existing.name == method.name)
+        .count();
+
+    if overloads > 0 {
+        let overload = format!("{}{}", method.name, overloads + 1);
+
+        method.attributes.push(attribute(
+            METADATA,
+            "OverloadAttribute",
+            vec![AttributeValue::String(overload)],
+        ));
+    }
+
+    def.methods.push(method);
+}
+
+fn system(name: &str) -> TypeSig {
+    TypeSig::Class(TypeName::new("System", name))
+}
+
+fn primitive(name: &str) -> Option<TypeSig> {
+    Some(match name {
+        "void" => TypeSig::Void,
+        "Boolean" => TypeSig::Bool,
+        "Char" => TypeSig::Char,
+        "Int8" => TypeSig::I8,
+        "UInt8" => TypeSig::U8,
+        "Int16" => TypeSig::I16,
+        "UInt16" => TypeSig::U16,
+        "Int32" => TypeSig::I32,
+        "UInt32" => TypeSig::U32,
+        "Int64" => TypeSig::I64,
+        "UInt64" => TypeSig::U64,
+        "Single" => TypeSig::F32,
+        "Double" => TypeSig::F64,
+        "String" => TypeSig::String,
+        "Object" => TypeSig::Object,
+        "Guid" => TypeSig::ValueType(TypeName::new("System", "Guid")),
+        _ => return None,
+    })
+}
+
+fn is_flags(attributes: &[Attribute]) -> bool {
+    attributes.iter().any(|attribute| attribute.name == "flags")
+}
+
+/// Assigns values to the variants of an enum, counting up from the previous value
+fn enum_values(
+    declaration: &Declaration,
+    variants: &[(String, Option<i64>)],
+) -> Result<Vec<(String, i64)>, IdlError> {
+    let unsigned = is_flags(&declaration.attributes);
+    let mut values = Vec::new();
+    let mut next = 0;
+
+    for (name, value) in variants {
+        let value = value.unwrap_or(next);
+
+        let in_range = if unsigned {
+            u32::try_from(value).is_ok()
+        } else {
+            i32::try_from(value).is_ok()
+        };
+
+        if !in_range {
+            return Err(IdlError::new(
+                declaration.line,
+                declaration.column,
+                format!(
+                    "the value of `{}.{}` is out of range",
+                    declaration.name, name
+                ),
+            ));
+        }
+
+        values.push((name.clone(), value));
+        next = value + 1;
+    }
+
+    Ok(values)
+}
+
+fn to_pascal(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Parses a GUID written as `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`, optionally within braces
+fn parse_guid(text: &str) -> Option<[u8; 16]> {
+    let text = text.trim().trim_start_matches('{').trim_end_matches('}');
+    let groups: Vec<_> = text.split('-').collect();
+
+    if groups.iter().map(|group| group.len()).collect::<Vec<_>>() != [8, 4, 4, 4, 12] {
+        return None;
+    }
+
+    let hex: String = groups.concat();
+    let mut bytes = [0; 16];
+
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+
+    Some(bytes)
+}
+
+fn derive_guid(namespace: &str, name: &str) -> [u8; 16] {
+    let full_name = format!("{}.{}", namespace, name);
+    let mut bytes = [0; 16];
+
+    for (index, chunk) in bytes.chunks_mut(8).enumerate() {
+        let hash = fnv1a(
+            fnv1a(FNV_OFFSET_BASIS, &[index as u8]),
+            full_name.as_bytes(),
+        );
+        chunk.copy_from_slice(&hash.to_le_bytes());
+    }
+
+    // Mark the GUID as a name-based (version 5) RFC 4122 GUID.
+    bytes[6] = (bytes[6] & 0x0F) | 0x50;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+    bytes
+}
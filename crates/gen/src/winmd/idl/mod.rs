@@ -0,0 +1,247 @@
+//! A compiler for a compact interface definition language
+//!
+//! The language is a subset of MIDL 3.0 covering namespaces, enums, structs, delegates,
+//! interfaces and runtime classes along with their attributes:
+//!
+//! ```text
+//! namespace Component
+//! {
+//!     [flags]
+//!     enum Options { None = 0, Fast = 0x1 };
+//!
+//!     delegate void Handler(Object sender, Int32 value);
+//!
+//!     [uuid(1b1b4a2e-5a5a-4c55-9e4c-3a3f8f0f1d7e)]
+//!     interface IStore<T> { T Get(UInt32 index); };
+//!
+//!     runtimeclass Widget : IStore<String>
+//!     {
+//!         Widget();
+//!         Widget(String name);
+//!         static Widget Find(String name);
+//!         String Name { get; };
+//!         Options Options;
+//!         event Handler Changed;
+//!     };
+//! }
+//! ```
+//!
+//! The members of a runtime class are placed on synthesized interfaces in the same way that
+//! MIDL does: instance members on the default `IWidget`, static members on `IWidgetStatics`
+//! and constructors taking parameters on `IWidgetFactory`. Types from other metadata files
+//! are referred to by their qualified names and are assumed to be reference types unless
+//! forward declared with `struct Namespace.Name;` or `enum Namespace.Name;`.
+//!
+//! Unqualified attribute names such as `[exclusive_to(...)]` refer to attributes of the
+//! `Windows.Foundation.Metadata` namespace, whereas `[uuid(...)]` and `[flags]` are understood
+//! by the compiler itself.
+//!
+//! The `winmd-idl` tool compiles an interface definition file into a `.winmd` file that
+//! `build!` can then read from the workspace's `.windows/winmd` directory.
+
+mod lexer;
+mod lower;
+mod parser;
+
+use super::writer::Metadata;
+
+/// An error encountered while compiling an interface definition
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdlError {
+    /// The 1-based line of the source at which the error was found
+    pub line: usize,
+    /// The 1-based column of the source at which the error was found
+    pub column: usize,
+    pub message: String,
+}
+
+impl IdlError {
+    pub(crate) fn new<M: Into<String>>(line: usize, column: usize, message: M) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for IdlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for IdlError {}
+
+/// Compiles an interface definition into the metadata of an assembly with the given name
+///
+/// The result may be written to a `.winmd` file with [`Metadata::write_to_path`].
+pub fn compile(source: &str, name: &str) -> Result<Metadata, IdlError> {
+    let tokens = lexer::tokenize(source)?;
+    let declarations = parser::Parser::new(source, tokens).parse()?;
+    let types = lower::Lowering::new(&declarations)?.lower(&declarations)?;
+
+    Ok(Metadata {
+        name: name.to_string(),
+        types,
+        ..Default::default()
+    })
+}
+
+/// Compiles an interface definition and reads it back on its own, for use by tests
+#[cfg(test)]
+pub(crate) fn test_reader(source: &str) -> &'static super::TypeReader {
+    let bytes = compile(source, "Test").unwrap().write();
+    Box::leak(Box::new(
+        super::TypeReader::from_bytes(vec![bytes]).unwrap(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::winmd::*;
+
+    const SOURCE: &str = r#"
+        namespace Component
+        {
+            [flags]
+            enum Options { None = 0, Fast = 0x1, Small = 0x2 };
+
+            enum Level { Low, Medium = 5, High };
+
+            struct Point { Int32 X; Int32 Y; };
+
+            delegate void Handler(Object sender, Level level);
+
+            [uuid(1b1b4a2e-5a5a-4c55-9e4c-3a3f8f0f1d7e)]
+            interface IStore<T>
+            {
+                T Get(UInt32 index);
+                Boolean TryGet(UInt32 index, out T value);
+            };
+
+            [marshaling_behavior(MarshalingType.Agile)]
+            runtimeclass Widget : IStore<String>, Windows.Foundation.IClosable
+            {
+                Widget();
+                Widget(String name);
+                static Widget Find(String name);
+                String Name { get; };
+                Point Position;
+                [deprecated("Use Position", DeprecationType.Deprecate, 1)]
+                Point Location();
+                event Handler Changed;
+            };
+        }
+    "#;
+
+    #[test]
+    fn compile() {
+        let reader = idl::test_reader(SOURCE);
+
+        let options = reader.expect_type_def(("Component", "Options"));
+        assert!(matches!(options.category(), TypeCategory::Enum));
+        assert!(matches!(options.underlying_type(), ElementType::U32));
+        assert!(options.has_attribute(("System", "FlagsAttribute")));
+
+        let level = reader.expect_type_def(("Component", "Level"));
+        let values: Vec<_> = level
+            .fields()
+            .filter_map(|field| field.constant())
+            .map(|constant| match constant.value() {
+                ConstantValue::I32(value) => value,
+                value => panic!("{:?}", value),
+            })
+            .collect();
+        assert_eq!(values, [0, 5, 6]);
+
+        let point = reader.expect_type_def(("Component", "Point"));
+        assert!(matches!(point.category(), TypeCategory::Struct));
+        assert_eq!(point.fields().count(), 2);
+
+        let handler = reader.expect_type_def(("Component", "Handler"));
+        assert!(matches!(handler.category(), TypeCategory::Delegate));
+        assert!(handler.methods().any(|method| method.name() == "Invoke"));
+
+        let store = reader.expect_type_def(("Component", "IStore`1"));
+        assert!(matches!(store.category(), TypeCategory::Interface));
+        let generics: Vec<_> = store.generics().map(|param| param.name()).collect();
+        assert_eq!(generics, ["T"]);
+        let guid = store
+            .attributes()
+            .find(|attribute| attribute.name() == ("Windows.Foundation.Metadata", "GuidAttribute"));
+        assert!(matches!(
+            guid.unwrap().args()[0].1,
+            AttributeArg::U32(0x1b1b_4a2e)
+        ));
+
+        let widget = reader.expect_type_def(("Component", "Widget"));
+        assert!(matches!(widget.category(), TypeCategory::Class));
+        let interfaces: Vec<_> = widget.interfaces().collect();
+        assert_eq!(interfaces.len(), 3);
+        assert!(interfaces[0].is_default());
+        assert_eq!(interfaces[0].interface().name(), ("Component", "IWidget"));
+        assert_eq!(
+            interfaces[2].interface().name(),
+            ("Windows.Foundation", "IClosable")
+        );
+
+        let attributes: Vec<_> = widget
+            .attributes()
+            .map(|attribute| attribute.name().1)
+            .collect();
+        assert_eq!(
+            attributes,
+            [
+                "MarshalingBehaviorAttribute",
+                "StaticAttribute",
+                "ActivatableAttribute",
+                "ActivatableAttribute"
+            ]
+        );
+
+        let default = reader.expect_type_def(("Component", "IWidget"));
+        let methods: Vec<_> = default.methods().map(|method| method.name()).collect();
+        assert_eq!(
+            methods,
+            [
+                "get_Name",
+                "get_Position",
+                "put_Position",
+                "Location",
+                "add_Changed",
+                "remove_Changed"
+            ]
+        );
+        let properties: Vec<_> = default
+            .properties()
+            .map(|property| property.name())
+            .collect();
+        assert_eq!(properties, ["Name", "Position"]);
+        assert_eq!(default.events().next().unwrap().name(), "Changed");
+
+        let location = default.methods().nth(3).unwrap();
+        let deprecated = location.attributes().next().unwrap();
+        assert_eq!(deprecated.name().1, "DeprecatedAttribute");
+        // DeprecationType is not part of the loaded metadata so its value is read as an I32.
+        assert!(matches!(deprecated.args()[1].1, AttributeArg::I32(0)));
+
+        let factory = reader.expect_type_def(("Component", "IWidgetFactory"));
+        assert_eq!(factory.methods().next().unwrap().name(), "CreateInstance");
+        let statics = reader.expect_type_def(("Component", "IWidgetStatics"));
+        assert_eq!(statics.methods().next().unwrap().name(), "Find");
+    }
+
+    #[test]
+    fn errors() {
+        let error =
+            idl::compile("namespace Test\n{\n    struct S { Unknown X; };\n}", "Test").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 16));
+        assert_eq!(error.message, "unknown type `Unknown`");
+
+        let error =
+            idl::compile("namespace Test\n{\n    runtimeclass C { Int32 }\n}", "Test").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 28));
+        assert_eq!(error.message, "expected an identifier, found `}`");
+    }
+}
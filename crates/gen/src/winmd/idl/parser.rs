@@ -0,0 +1,745 @@
+use super::lexer::{Token, TokenKind};
+use super::IdlError;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Attribute {
+    pub name: String,
+    pub args: Vec<Literal>,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Literal {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    /// A dotted name referring to a type or an enum variant
+    Path(String),
+    /// The raw text of a GUID written without quotes
+    Guid(String),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Type {
+    pub kind: TypeKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum TypeKind {
+    Named(String, Vec<Type>),
+    Array(Box<Type>),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Param {
+    pub name: String,
+    pub ty: Type,
+    pub out: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Member {
+    pub attributes: Vec<Attribute>,
+    pub is_static: bool,
+    pub kind: MemberKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum MemberKind {
+    Method {
+        name: String,
+        return_type: Type,
+        params: Vec<Param>,
+    },
+    Constructor {
+        params: Vec<Param>,
+    },
+    Property {
+        name: String,
+        ty: Type,
+        get: bool,
+        set: bool,
+    },
+    Event {
+        name: String,
+        ty: Type,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Declaration {
+    pub namespace: String,
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    pub kind: DeclarationKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum DeclarationKind {
+    Enum(Vec<(String, Option<i64>)>),
+    Struct(Vec<(String, Type)>),
+    Interface {
+        generics: Vec<String>,
+        requires: Vec<Type>,
+        members: Vec<Member>,
+    },
+    Class {
+        interfaces: Vec<Type>,
+        members: Vec<Member>,
+    },
+    Delegate {
+        return_type: Type,
+        params: Vec<Param>,
+    },
+    /// A type defined elsewhere, declared so that its signatures are encoded correctly
+    Forward {
+        value_type: bool,
+    },
+}
+
+pub(crate) struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str, tokens: Vec<Token>) -> Self {
+        Self {
+            source,
+            tokens,
+            position: 0,
+        }
+    }
+
+    pub fn parse(mut self) -> Result<Vec<Declaration>, IdlError> {
+        let mut declarations = Vec::new();
+
+        while !self.at_eof() {
+            let attributes = self.attributes()?;
+
+            if self.peek_ident("namespace") {
+                if !attributes.is_empty() {
+                    return Err(self.error("attributes cannot be applied to a namespace"));
+                }
+
+                self.namespace("", &mut declarations)?;
+            } else {
+                let kind = self.expect_ident()?;
+                return Err(self.error_at(
+                    self.position - 1,
+                    format!("expected `namespace`, found `{}`", kind),
+                ));
+            }
+        }
+
+        Ok(declarations)
+    }
+
+    fn namespace(
+        &mut self,
+        parent: &str,
+        declarations: &mut Vec<Declaration>,
+    ) -> Result<(), IdlError> {
+        self.expect_keyword("namespace")?;
+        let name = self.path()?;
+
+        let namespace = if parent.is_empty() {
+            name
+        } else {
+            format!("{}.{}", parent, name)
+        };
+
+        self.expect_punct('{')?;
+
+        while !self.eat_punct('}') {
+            if self.at_eof() {
+                return Err(self.error("expected `}`"));
+            }
+
+            let attributes = self.attributes()?;
+
+            if self.peek_ident("namespace") {
+                if !attributes.is_empty() {
+                    return Err(self.error("attributes cannot be applied to a namespace"));
+                }
+
+                self.namespace(&namespace, declarations)?;
+            } else {
+                declarations.push(self.declaration(&namespace, attributes)?);
+            }
+        }
+
+        self.eat_punct(';');
+        Ok(())
+    }
+
+    fn declaration(
+        &mut self,
+        namespace: &str,
+        attributes: Vec<Attribute>,
+    ) -> Result<Declaration, IdlError> {
+        let (line, column) = self.location();
+        let keyword = self.expect_ident()?;
+
+        let (namespace, name, kind) = match keyword.as_str() {
+            "enum" | "struct" | "interface" | "runtimeclass" | "delegate" if self.peek_forward() => {
+                let path = self.path()?;
+                self.expect_punct(';')?;
+
+                // The forward declaration of a qualified name always has a namespace.
+                let (namespace, name) = split_path(&path).unwrap();
+                let value_type = keyword == "enum" || keyword == "struct";
+
+                (
+                    namespace.to_string(),
+                    name.to_string(),
+                    DeclarationKind::Forward { value_type },
+                )
+            }
+            "enum" => {
+                let name = self.expect_ident()?;
+                (namespace.to_string(), name, DeclarationKind::Enum(self.enum_body()?))
+            }
+            "struct" => {
+                let name = self.expect_ident()?;
+                (namespace.to_string(), name, DeclarationKind::Struct(self.struct_body()?))
+            }
+            "interface" => {
+                let name = self.expect_ident()?;
+                let mut generics = Vec::new();
+
+                if self.eat_punct('<') {
+                    loop {
+                        generics.push(self.expect_ident()?);
+                        if !self.eat_punct(',') {
+                            break;
+                        }
+                    }
+                    self.expect_punct('>')?;
+                }
+
+                let mut requires = Vec::new();
+
+                if self.eat_keyword("requires") {
+                    loop {
+                        requires.push(self.ty()?);
+                        if !self.eat_punct(',') {
+                            break;
+                        }
+                    }
+                }
+
+                let members = self.members(None)?;
+
+                (
+                    namespace.to_string(),
+                    name,
+                    DeclarationKind::Interface {
+                        generics,
+                        requires,
+                        members,
+                    },
+                )
+            }
+            "runtimeclass" => {
+                let name = self.expect_ident()?;
+                let mut interfaces = Vec::new();
+
+                if self.eat_punct(':') {
+                    loop {
+                        interfaces.push(self.ty()?);
+                        if !self.eat_punct(',') {
+                            break;
+                        }
+                    }
+                }
+
+                let members = self.members(Some(&name))?;
+                (namespace.to_string(), name, DeclarationKind::Class { interfaces, members })
+            }
+            "delegate" => {
+                let return_type = self.ty()?;
+                let name = self.expect_ident()?;
+                let params = self.params()?;
+                self.expect_punct(';')?;
+                (namespace.to_string(), name, DeclarationKind::Delegate { return_type, params })
+            }
+            _ => {
+                return Err(IdlError::new(
+                    line,
+                    column,
+                    format!(
+                        "expected `enum`, `struct`, `interface`, `runtimeclass` or `delegate`, found `{}`",
+                        keyword
+                    ),
+                ))
+            }
+        };
+
+        Ok(Declaration {
+            namespace,
+            name,
+            attributes,
+            kind,
+            line,
+            column,
+        })
+    }
+
+    fn enum_body(&mut self) -> Result<Vec<(String, Option<i64>)>, IdlError> {
+        let mut variants = Vec::new();
+        self.expect_punct('{')?;
+
+        while !self.eat_punct('}') {
+            let name = self.expect_ident()?;
+
+            let value = if self.eat_punct('=') {
+                Some(self.integer()?)
+            } else {
+                None
+            };
+
+            variants.push((name, value));
+
+            if !self.eat_punct(',') {
+                self.expect_punct('}')?;
+                break;
+            }
+        }
+
+        self.eat_punct(';');
+        Ok(variants)
+    }
+
+    fn struct_body(&mut self) -> Result<Vec<(String, Type)>, IdlError> {
+        let mut fields = Vec::new();
+        self.expect_punct('{')?;
+
+        while !self.eat_punct('}') {
+            let ty = self.ty()?;
+            let name = self.expect_ident()?;
+            self.expect_punct(';')?;
+            fields.push((name, ty));
+        }
+
+        self.eat_punct(';');
+        Ok(fields)
+    }
+
+    /// Parses the members of an interface or, given its name, a runtime class
+    fn members(&mut self, class: Option<&str>) -> Result<Vec<Member>, IdlError> {
+        let mut members = Vec::new();
+        self.expect_punct('{')?;
+
+        while !self.eat_punct('}') {
+            if self.at_eof() {
+                return Err(self.error("expected `}`"));
+            }
+
+            let attributes = self.attributes()?;
+            let (line, column) = self.location();
+            let is_static = self.eat_keyword("static");
+
+            if is_static && class.is_none() {
+                return Err(IdlError::new(
+                    line,
+                    column,
+                    "interfaces cannot have static members",
+                ));
+            }
+
+            let kind = if self.eat_keyword("event") {
+                let ty = self.ty()?;
+                let name = self.expect_ident()?;
+                self.expect_punct(';')?;
+                MemberKind::Event { name, ty }
+            } else {
+                let ty = self.ty()?;
+
+                if self.peek_punct('(') {
+                    // The class name directly followed by a parameter list is a constructor.
+                    match &ty.kind {
+                        TypeKind::Named(name, args)
+                            if args.is_empty() && Some(name.as_str()) == class => {}
+                        _ => return Err(IdlError::new(line, column, "expected a member name")),
+                    }
+
+                    if is_static {
+                        return Err(IdlError::new(line, column, "constructors cannot be static"));
+                    }
+
+                    let params = self.params()?;
+                    self.expect_punct(';')?;
+                    MemberKind::Constructor { params }
+                } else {
+                    let name = self.expect_ident()?;
+
+                    if self.peek_punct('(') {
+                        let params = self.params()?;
+                        self.expect_punct(';')?;
+                        MemberKind::Method {
+                            name,
+                            return_type: ty,
+                            params,
+                        }
+                    } else if self.eat_punct('{') {
+                        let mut get = false;
+                        let mut set = false;
+
+                        while !self.eat_punct('}') {
+                            let (line, column) = self.location();
+                            match self.expect_ident()?.as_str() {
+                                "get" if !get => get = true,
+                                "set" if !set => set = true,
+                                accessor => {
+                                    return Err(IdlError::new(
+                                        line,
+                                        column,
+                                        format!("unexpected property accessor `{}`", accessor),
+                                    ))
+                                }
+                            }
+                            self.expect_punct(';')?;
+                        }
+
+                        if !get {
+                            return Err(IdlError::new(
+                                line,
+                                column,
+                                "properties must have a getter",
+                            ));
+                        }
+
+                        self.eat_punct(';');
+                        MemberKind::Property { name, ty, get, set }
+                    } else {
+                        // A bare property has both a getter and a setter.
+                        self.expect_punct(';')?;
+                        MemberKind::Property {
+                            name,
+                            ty,
+                            get: true,
+                            set: true,
+                        }
+                    }
+                }
+            };
+
+            members.push(Member {
+                attributes,
+                is_static,
+                kind,
+                line,
+                column,
+            });
+        }
+
+        self.eat_punct(';');
+        Ok(members)
+    }
+
+    fn params(&mut self) -> Result<Vec<Param>, IdlError> {
+        let mut params = Vec::new();
+        self.expect_punct('(')?;
+
+        if self.eat_punct(')') {
+            return Ok(params);
+        }
+
+        loop {
+            let out = self.eat_keyword("out");
+            let ty = self.ty()?;
+            let name = self.expect_ident()?;
+            params.push(Param { name, ty, out });
+
+            if !self.eat_punct(',') {
+                break;
+            }
+        }
+
+        self.expect_punct(')')?;
+        Ok(params)
+    }
+
+    fn ty(&mut self) -> Result<Type, IdlError> {
+        let (line, column) = self.location();
+        let name = self.path()?;
+        let mut args = Vec::new();
+
+        if self.eat_punct('<') {
+            loop {
+                args.push(self.ty()?);
+                if !self.eat_punct(',') {
+                    break;
+                }
+            }
+            self.expect_punct('>')?;
+        }
+
+        let mut ty = Type {
+            kind: TypeKind::Named(name, args),
+            line,
+            column,
+        };
+
+        while self.eat_punct('[') {
+            self.expect_punct(']')?;
+            ty = Type {
+                kind: TypeKind::Array(Box::new(ty)),
+                line,
+                column,
+            };
+        }
+
+        Ok(ty)
+    }
+
+    fn attributes(&mut self) -> Result<Vec<Attribute>, IdlError> {
+        let mut attributes = Vec::new();
+
+        while self.eat_punct('[') {
+            loop {
+                let (line, column) = self.location();
+                let name = self.path()?;
+                let mut args = Vec::new();
+
+                if self.eat_punct('(') {
+                    if name == "uuid" {
+                        args.push(self.guid()?);
+                    } else if !self.peek_punct(')') {
+                        loop {
+                            args.push(self.literal()?);
+                            if !self.eat_punct(',') {
+                                break;
+                            }
+                        }
+                    }
+                    self.expect_punct(')')?;
+                }
+
+                attributes.push(Attribute {
+                    name,
+                    args,
+                    line,
+                    column,
+                });
+
+                if !self.eat_punct(',') {
+                    break;
+                }
+            }
+
+            self.expect_punct(']')?;
+        }
+
+        Ok(attributes)
+    }
+
+    fn guid(&mut self) -> Result<Literal, IdlError> {
+        if let TokenKind::Str(value) = &self.tokens[self.position].kind {
+            let value = value.clone();
+            self.position += 1;
+            return Ok(Literal::Guid(value));
+        }
+
+        let start = self.tokens[self.position].span.0;
+        let mut end = start;
+
+        while !self.peek_punct(')') && !self.at_eof() {
+            end = self.tokens[self.position].span.1;
+            self.position += 1;
+        }
+
+        Ok(Literal::Guid(self.source[start..end].to_string()))
+    }
+
+    fn literal(&mut self) -> Result<Literal, IdlError> {
+        let (line, column) = self.location();
+
+        match self.tokens[self.position].kind.clone() {
+            TokenKind::Str(value) => {
+                self.position += 1;
+                Ok(Literal::Str(value))
+            }
+            TokenKind::Number(text) if text.contains('.') => {
+                self.position += 1;
+                text.parse()
+                    .map(Literal::Float)
+                    .map_err(|_| IdlError::new(line, column, format!("invalid number `{}`", text)))
+            }
+            TokenKind::Number(_) | TokenKind::Punct('-') => Ok(Literal::Int(self.integer()?)),
+            TokenKind::Ident(ref value) if value == "true" => {
+                self.position += 1;
+                Ok(Literal::Bool(true))
+            }
+            TokenKind::Ident(ref value) if value == "false" => {
+                self.position += 1;
+                Ok(Literal::Bool(false))
+            }
+            TokenKind::Ident(_) => Ok(Literal::Path(self.path()?)),
+            _ => Err(self.error("expected an attribute argument")),
+        }
+    }
+
+    fn integer(&mut self) -> Result<i64, IdlError> {
+        let negative = self.eat_punct('-');
+        let token = self.tokens[self.position].clone();
+
+        let text = match token.kind {
+            TokenKind::Number(text) => text,
+            _ => return Err(self.error("expected an integer")),
+        };
+
+        self.position += 1;
+
+        let value = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+            u64::from_str_radix(hex, 16).ok()
+        } else {
+            text.parse().ok()
+        };
+
+        // Values up to u64::MAX are accepted so that unsigned constants can be written in full
+        // and reinterpreted by their underlying type.
+        match value {
+            Some(value) if negative => Ok((value as i64).wrapping_neg()),
+            Some(value) => Ok(value as i64),
+            None => Err(IdlError::new(
+                token.line,
+                token.column,
+                format!("invalid integer `{}`", text),
+            )),
+        }
+    }
+
+    fn path(&mut self) -> Result<String, IdlError> {
+        let mut path = self.expect_ident()?;
+
+        while self.peek_punct('.') {
+            self.position += 1;
+            path.push('.');
+            path.push_str(&self.expect_ident()?);
+        }
+
+        Ok(path)
+    }
+
+    /// Checks whether the next tokens are a qualified name followed by `;`
+    fn peek_forward(&self) -> bool {
+        let mut position = self.position;
+        let mut qualified = false;
+
+        loop {
+            if !matches!(self.tokens[position].kind, TokenKind::Ident(_)) {
+                return false;
+            }
+
+            position += 1;
+
+            match self.tokens[position].kind {
+                TokenKind::Punct('.') => {
+                    qualified = true;
+                    position += 1;
+                }
+                TokenKind::Punct(';') => return qualified,
+                _ => return false,
+            }
+        }
+    }
+
+    fn location(&self) -> (usize, usize) {
+        let token = &self.tokens[self.position];
+        (token.line, token.column)
+    }
+
+    fn at_eof(&self) -> bool {
+        self.tokens[self.position].kind == TokenKind::Eof
+    }
+
+    fn peek_punct(&self, c: char) -> bool {
+        self.tokens[self.position].kind == TokenKind::Punct(c)
+    }
+
+    fn peek_ident(&self, value: &str) -> bool {
+        matches!(&self.tokens[self.position].kind, TokenKind::Ident(ident) if ident == value)
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        if self.peek_punct(c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, value: &str) -> bool {
+        if self.peek_ident(value) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), IdlError> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", c)))
+        }
+    }
+
+    fn expect_keyword(&mut self, value: &str) -> Result<(), IdlError> {
+        if self.eat_keyword(value) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", value)))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, IdlError> {
+        if let TokenKind::Ident(value) = &self.tokens[self.position].kind {
+            let value = value.clone();
+            self.position += 1;
+            Ok(value)
+        } else {
+            Err(self.error("expected an identifier"))
+        }
+    }
+
+    fn error<M: Into<String>>(&self, message: M) -> IdlError {
+        self.error_at(self.position, message)
+    }
+
+    fn error_at<M: Into<String>>(&self, position: usize, message: M) -> IdlError {
+        let token = &self.tokens[position];
+        let message = message.into();
+
+        let found = match &token.kind {
+            TokenKind::Eof => " at end of input".to_string(),
+            _ => format!(", found `{}`", &self.source[token.span.0..token.span.1]),
+        };
+
+        // Messages that already name the offending token are reported as is.
+        if message.contains("found") {
+            IdlError::new(token.line, token.column, message)
+        } else {
+            IdlError::new(token.line, token.column, format!("{}{}", message, found))
+        }
+    }
+}
+
+/// Splits a qualified name into its namespace and name
+pub(crate) fn split_path(path: &str) -> Option<(&str, &str)> {
+    path.rfind('.')
+        .map(|index| (&path[..index], &path[index + 1..]))
+}
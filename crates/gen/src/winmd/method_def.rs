@@ -23,20 +23,28 @@ impl MethodDef {
         self.reader.blob(self.row, 4)
     }
 
+    pub fn semantics(&self) -> Option<MethodSemantics> {
+        self.reader
+            .method_semantics(self.row)
+            .map(|row| MethodSemantics {
+                reader: self.reader,
+                row,
+            })
+    }
+
     pub fn category(&self) -> MethodCategory {
-        if self.flags().special() {
-            let name = self.name();
+        if let Some(semantics) = self.semantics() {
+            let flags = semantics.flags();
 
-            if name.starts_with("get") {
+            if flags.getter() {
                 MethodCategory::Get
-            } else if name.starts_with("put") {
+            } else if flags.setter() {
                 MethodCategory::Set
-            } else if name.starts_with("add") {
+            } else if flags.add_on() {
                 MethodCategory::Add
-            } else if name.starts_with("remove") {
+            } else if flags.remove_on() {
                 MethodCategory::Remove
             } else {
-                // A delegate's 'Invoke' method is "special" but lacks a preamble.
                 MethodCategory::Normal
             }
         } else {
@@ -0,0 +1,22 @@
+use super::*;
+macros::table!(MethodSemantics);
+
+impl MethodSemantics {
+    pub fn flags(&self) -> MethodSemanticsFlags {
+        MethodSemanticsFlags(self.reader.u32(self.row, 0))
+    }
+
+    pub fn method(&self) -> MethodDef {
+        let index = self.reader.u32(self.row, 1) - 1;
+        let row = Row::new(index, TableIndex::MethodDef, self.row.file_index);
+
+        MethodDef {
+            reader: self.reader,
+            row,
+        }
+    }
+
+    pub fn association(&self) -> HasSemantics {
+        self.reader.decode(self.row, 2)
+    }
+}
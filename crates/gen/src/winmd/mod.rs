@@ -1,22 +1,32 @@
+mod array_shape;
 mod assembly_ref;
 mod attribute;
 mod blob;
+mod class_layout;
 mod codes;
 mod constant;
 mod constant_value;
+mod docs;
 mod element_type;
+mod error;
+mod event;
 mod field;
+mod field_layout;
 mod file;
 mod flags;
 mod generic_param;
+mod guid;
+pub mod idl;
 mod impl_map;
 mod interface_impl;
 mod member_ref;
 mod method_def;
+mod method_semantics;
 mod module;
 mod module_ref;
 mod nested_class;
 mod param;
+mod property;
 mod row;
 mod traits;
 mod type_def;
@@ -24,26 +34,35 @@ mod type_reader;
 mod type_ref;
 mod type_spec;
 mod workspace;
+pub mod writer;
 
+pub use array_shape::*;
 pub use assembly_ref::*;
 pub use attribute::*;
 pub use blob::*;
+pub use class_layout::*;
 pub use codes::*;
 pub use constant::*;
 pub use constant_value::*;
 pub use element_type::*;
+pub use error::*;
+pub use event::*;
 pub use field::*;
+pub use field_layout::*;
 pub use file::*;
 pub use flags::*;
 pub use generic_param::*;
+pub use guid::*;
 pub use impl_map::*;
 pub use interface_impl::*;
 pub use member_ref::*;
 pub use method_def::*;
+pub use method_semantics::*;
 pub use module::*;
 pub use module_ref::*;
 pub use nested_class::*;
 pub use param::*;
+pub use property::*;
 pub use row::*;
 pub use traits::*;
 pub use type_def::*;
@@ -1 +1,13 @@
+use super::*;
 macros::table!(Module);
+
+impl Module {
+    pub fn name(&self) -> &'static str {
+        self.reader.str(self.row, 1)
+    }
+
+    /// The module version identifier, which uniquely identifies a specific build of the metadata
+    pub fn mvid(&self) -> Guid {
+        self.reader.guid(self.row, 2)
+    }
+}
@@ -0,0 +1,38 @@
+use super::*;
+macros::table!(Property);
+
+impl Property {
+    pub fn name(&self) -> &'static str {
+        self.reader.str(self.row, 1)
+    }
+
+    pub fn sig(&self) -> Blob {
+        self.reader.blob(self.row, 2)
+    }
+
+    pub fn methods(&self) -> impl Iterator<Item = MethodSemantics> + '_ {
+        self.reader
+            .equal_range(
+                self.row.file_index,
+                TableIndex::MethodSemantics,
+                2,
+                HasSemantics::Property(*self).encode(),
+            )
+            .map(move |row| MethodSemantics {
+                reader: self.reader,
+                row,
+            })
+    }
+
+    pub fn getter(&self) -> Option<MethodDef> {
+        self.methods()
+            .find(|method| method.flags().getter())
+            .map(|method| method.method())
+    }
+
+    pub fn setter(&self) -> Option<MethodDef> {
+        self.methods()
+            .find(|method| method.flags().setter())
+            .map(|method| method.method())
+    }
+}
@@ -6,8 +6,35 @@ impl TypeDef {
         TypeFlags(self.reader.u32(self.row, 0))
     }
 
+    /// The namespace and name of the type definition
+    ///
+    /// Nested types take the namespace of their outermost enclosing type and are named
+    /// after their enclosing type and their position within it, e.g. `OUTER_0`.
     pub fn name(&self) -> (&'static str, &'static str) {
-        (self.reader.str(self.row, 2), self.reader.str(self.row, 1))
+        if let Some((enclosing, name)) = self.reader.nested_type(self.row) {
+            let enclosing = TypeDef {
+                reader: self.reader,
+                row: enclosing,
+            };
+
+            (enclosing.name().0, name)
+        } else {
+            (self.reader.str(self.row, 2), self.reader.str(self.row, 1))
+        }
+    }
+
+    pub fn enclosing_type(&self) -> Option<TypeDef> {
+        self.reader.nested_type(self.row).map(|(row, _)| TypeDef {
+            reader: self.reader,
+            row,
+        })
+    }
+
+    pub fn nested_types(&self) -> impl Iterator<Item = TypeDef> + '_ {
+        self.reader.nested_types(self.row).map(move |row| TypeDef {
+            reader: self.reader,
+            row,
+        })
     }
 
     pub fn extends(&self) -> TypeDefOrRef {
@@ -32,6 +59,28 @@ impl TypeDef {
             })
     }
 
+    pub fn properties(&self) -> impl Iterator<Item = Property> + '_ {
+        self.reader
+            .property_map(self.row)
+            .into_iter()
+            .flat_map(move |map| self.reader.list(map, TableIndex::Property, 1))
+            .map(move |row| Property {
+                reader: self.reader,
+                row,
+            })
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        self.reader
+            .event_map(self.row)
+            .into_iter()
+            .flat_map(move |map| self.reader.list(map, TableIndex::Event, 1))
+            .map(move |row| Event {
+                reader: self.reader,
+                row,
+            })
+    }
+
     pub fn generics(&self) -> impl Iterator<Item = GenericParam> + '_ {
         self.reader
             .equal_range(
@@ -74,6 +123,21 @@ impl TypeDef {
             })
     }
 
+    pub fn class_layout(&self) -> Option<ClassLayout> {
+        self.reader
+            .equal_range(
+                self.row.file_index,
+                TableIndex::ClassLayout,
+                2,
+                self.row.index + 1,
+            )
+            .map(move |row| ClassLayout {
+                reader: self.reader,
+                row,
+            })
+            .next()
+    }
+
     pub fn has_attribute(&self, name: (&str, &str)) -> bool {
         self.attributes().any(|attribute| attribute.name() == name)
     }
@@ -82,6 +146,16 @@ impl TypeDef {
         self.flags().windows_runtime()
     }
 
+    /// Whether the type is a C union, that is an explicit layout whose fields all start at
+    /// offset zero
+    pub fn is_union(&self) -> bool {
+        self.flags().explicit()
+            && self
+                .fields()
+                .filter(|field| !field.flags().literal())
+                .all(|field| field.layout().map(|layout| layout.offset()) == Some(0))
+    }
+
     pub fn category(&self) -> TypeCategory {
         if self.flags().interface() {
             TypeCategory::Interface
@@ -1,7 +1,26 @@
 use super::*;
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Types that are either hand-written in the `windows` crate or not yet supported
+const EXCLUDED_TYPES: &[(&str, &str)] = &[
+    ("Windows.Foundation", "HResult"),
+    ("Windows.Win32.Com", "IUnknown"),
+    ("Windows.Win32.SystemServices", "BOOL"),
+    ("Windows.Win32.Direct2D", "D2D_MATRIX_3X2_F"),
+    // TODO: map BSTR
+    // TODO: remove once this is fixed: https://github.com/microsoft/win32metadata/issues/30
+    ("Windows.Win32", "CFunctionDiscoveryNotificationWrapper"),
+];
+
+/// The default metadata is aligned so that its headers may be viewed in place
+#[repr(C, align(8))]
+struct Aligned<T: ?Sized>(T);
+
+static WIN32: &Aligned<[u8]> = &Aligned(*include_bytes!("../../default/Windows.Win32.winmd"));
+static WINRT: &Aligned<[u8]> = &Aligned(*include_bytes!("../../default/Windows.WinRT.winmd"));
 
 /// A reader of type information from Windows Metadata
 pub struct TypeReader {
@@ -11,13 +30,49 @@ pub struct TypeReader {
     /// Types known to this [`TypeReader`]
     ///
     /// This is a mapping between namespace names and the types inside
-    /// that namespace. The keys are the namespace and the values is a mapping
-    /// of type names to type definitions
-    types: BTreeMap<String, BTreeMap<String, TypeRow>>,
+    /// that namespace. Each namespace is only indexed on first lookup.
+    types: BTreeMap<String, Namespace>,
+    /// A mapping between lowercase namespace names and namespace names
+    lowercase: HashMap<String, String>,
+    /// Nested types known to this [`TypeReader`]
+    ///
+    /// This is a mapping between a nested type definition and its enclosing
+    /// type definition along with the name generated for the nested type
+    nested: BTreeMap<Row, (Row, String)>,
+    /// A mapping between type definitions and the nested type definitions they enclose
+    enclosed: BTreeMap<Row, Vec<Row>>,
+    /// A mapping between methods and the `MethodSemantics` rows that associate
+    /// them with a property or event, built on first lookup
+    semantics: OnceLock<BTreeMap<Row, Row>>,
+    /// Mappings between type definitions and the `PropertyMap` and `EventMap` rows that list
+    /// their properties and events, built on first lookup since neither table is sorted
+    property_maps: OnceLock<BTreeMap<Row, Row>>,
+    event_maps: OnceLock<BTreeMap<Row, Row>>,
+    /// The summaries of the XML documentation files found next to the metadata files,
+    /// read on first lookup
+    docs: OnceLock<HashMap<String, String>>,
+    /// A mapping between the names of types, functions and constants and the namespaces
+    /// that define them, built on first lookup
+    names: OnceLock<HashMap<String, Vec<String>>>,
+    /// The type definitions whose values are fields of unions, directly or within other
+    /// structs, built on first lookup
+    union_fields: OnceLock<BTreeSet<Row>>,
     // TODO: store Row objects and turn them into TypeDef on request.
     // When turning into TypeDef they add the &'static TypeReader
 }
 
+/// The type definitions of a namespace along with a lazily built index of the
+/// types, functions and constants they define
+#[derive(Default)]
+struct Namespace {
+    defs: Vec<Row>,
+    /// The rows defining each name, in the order they were found
+    ///
+    /// The Win32 metadata defines some types and functions more than once, with a variant for
+    /// each architecture, but other lookups only see the first definition of a name.
+    index: OnceLock<BTreeMap<String, Vec<TypeRow>>>,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
 enum TypeRow {
     TypeDef(Row),
@@ -33,6 +88,15 @@ pub enum Type {
 }
 
 impl Type {
+    /// The name of the type, function or constant
+    pub fn name(&self) -> &'static str {
+        match self {
+            Type::TypeDef(def) => def.name().1,
+            Type::MethodDef((_, method)) => method.name(),
+            Type::Field((_, field)) => field.name(),
+        }
+    }
+
     fn new(reader: &'static TypeReader, row: TypeRow) -> Self {
         match row {
             TypeRow::TypeDef(def) => Type::TypeDef(TypeDef { reader, row: def }),
@@ -51,140 +115,305 @@ impl Type {
 }
 
 impl TypeReader {
+    /// Get the [`TypeReader`] for the current workspace
+    ///
+    /// # Panics
+    ///
+    /// This function panics if any of the workspace's metadata files cannot be read or parsed.
     pub fn get() -> &'static Self {
-        use std::{mem::MaybeUninit, sync::Once};
-        static ONCE: Once = Once::new();
-        static mut VALUE: MaybeUninit<TypeReader> = MaybeUninit::uninit();
+        Self::try_get().unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        ONCE.call_once(|| {
-            // This is safe because `Once` provides thread-safe one-time initialization
-            unsafe { VALUE = MaybeUninit::new(Self::from_iter(winmd_paths())) }
-        });
+    /// Get the [`TypeReader`] for the current workspace or the error describing the first
+    /// metadata file that could not be read or parsed
+    pub fn try_get() -> Result<&'static Self, WinmdError> {
+        static VALUE: OnceLock<Result<TypeReader, WinmdError>> = OnceLock::new();
 
-        // This is safe because `call_once` has already been called.
-        unsafe { &*VALUE.as_ptr() }
+        VALUE
+            .get_or_init(|| Self::from_workspace(&[]))
+            .as_ref()
+            .map_err(|error| error.clone())
     }
 
-    /// Insert WinRT metadata at the given paths
-    ///
-    /// # Panics
+    /// Get a [`TypeReader`] for the current workspace that also reads the given metadata
+    /// files, or the metadata files in the given directories
     ///
-    /// This function panics if the if the files where the windows metadata are stored cannot be read.
-    fn from_iter<I: IntoIterator<Item = PathBuf>>(files: I) -> Self {
-        let mut files: Vec<File> = files.into_iter().map(|file| File::new(file)).collect();
-
-        if files.is_empty() {
-            files.push(File::from_bytes(
-                include_bytes!("../../default/Windows.Win32.winmd").to_vec(),
-            ));
-            files.push(File::from_bytes(
-                include_bytes!("../../default/Windows.WinRT.winmd").to_vec(),
-            ));
+    /// Without extra paths this is the same as [`TypeReader::try_get`]. Otherwise a new
+    /// reader is created, and leaked, on each call.
+    pub fn try_get_with(paths: &[PathBuf]) -> Result<&'static Self, WinmdError> {
+        if paths.is_empty() {
+            Self::try_get()
+        } else {
+            Ok(Box::leak(Box::new(Self::from_workspace(paths)?)))
         }
+    }
 
-        let reader = Self {
-            files,
-            types: BTreeMap::default(),
-        };
+    /// Read the metadata of the current workspace, falling back to the default metadata
+    /// embedded in this crate if the workspace has none, along with the metadata listed by
+    /// the environment and the given extra paths
+    fn from_workspace(extra: &[PathBuf]) -> Result<Self, WinmdError> {
+        let mut paths = vec![];
 
-        let mut types = BTreeMap::<String, BTreeMap<String, TypeRow>>::default();
+        if let Some(windows_dir) = workspace_windows_dir() {
+            push_winmd_paths(windows_dir.join("winmd"), &mut paths);
+        }
 
-        for (index, file) in reader.files.iter().enumerate() {
-            let row_count = file.type_def_table().row_count;
+        let mut files = if paths.is_empty() {
+            vec![
+                File::from_bytes("Windows.Win32.winmd", &WIN32.0)?,
+                File::from_bytes("Windows.WinRT.winmd", &WINRT.0)?,
+            ]
+        } else {
+            vec![]
+        };
 
-            for row in 0..row_count {
-                let def = Row::new(row, TableIndex::TypeDef, index as u16);
-                let namespace = reader.str(def, 2).to_string();
-                let name = reader.str(def, 1).to_string();
+        for path in env_winmd_paths().iter().chain(extra) {
+            if path.is_dir() {
+                push_winmd_paths(path.clone(), &mut paths);
+            } else if !paths.contains(path) {
+                // Missing files are kept so that reading them reports the error.
+                paths.push(path.clone());
+            }
+        }
 
-                types
-                    .entry(namespace.to_string())
-                    .or_default()
-                    .entry(name.to_string())
-                    .or_insert(TypeRow::TypeDef(def));
+        for path in &paths {
+            files.push(File::new(path)?);
+        }
 
-                let flags = TypeFlags(reader.u32(def, 0));
+        Ok(Self::from_files(files))
+    }
 
-                if flags.interface() || flags.windows_runtime() {
-                    continue;
-                }
+    /// Create a [`TypeReader`] for the metadata files at the given paths
+    ///
+    /// Unlike [`TypeReader::get`], the reader only knows about the given files and may be
+    /// created any number of times. Rows such as [`TypeDef`] borrow the reader for `'static`
+    /// so it must be leaked, e.g. with [`Box::leak`], before they can be read from it.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if any of the files cannot be read or parsed.
+    pub fn from_paths<P: AsRef<Path>>(paths: &[P]) -> Result<Self, WinmdError> {
+        let files = paths
+            .iter()
+            .map(File::new)
+            .collect::<Result<Vec<File>, WinmdError>>()?;
 
-                let extends = reader.u32(def, 3);
+        Ok(Self::from_files(files))
+    }
 
-                if extends == 0 {
-                    continue;
-                }
+    /// Create a [`TypeReader`] for the given in-memory metadata files
+    ///
+    /// See [`TypeReader::from_paths`] for how the reader may be used.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if any of the files cannot be parsed. The path of the error is
+    /// the position of the file in `files`.
+    pub fn from_bytes(files: Vec<Vec<u8>>) -> Result<Self, WinmdError> {
+        let files = files
+            .into_iter()
+            .enumerate()
+            .map(|(index, bytes)| File::from_bytes(index.to_string(), bytes))
+            .collect::<Result<Vec<File>, WinmdError>>()?;
+
+        Ok(Self::from_files(files))
+    }
 
-                let extends = Row::new((extends >> 2) - 1, TableIndex::TypeRef, index as u16);
+    fn from_files(files: Vec<File>) -> Self {
+        let reader = Self {
+            files,
+            types: BTreeMap::default(),
+            lowercase: HashMap::default(),
+            nested: BTreeMap::default(),
+            enclosed: BTreeMap::default(),
+            semantics: OnceLock::new(),
+            property_maps: OnceLock::new(),
+            event_maps: OnceLock::new(),
+            docs: OnceLock::new(),
+            names: OnceLock::new(),
+            union_fields: OnceLock::new(),
+        };
 
-                if (reader.str(extends, 2), reader.str(extends, 1)) != ("System", "Object") {
-                    continue;
-                }
+        let mut types = BTreeMap::<String, Namespace>::default();
+        let mut enclosing = BTreeMap::<Row, (Row, usize)>::default();
 
-                for field in reader.list(def, TableIndex::Field, 4) {
-                    let name = reader.str(field, 1);
+        for (index, file) in reader.files.iter().enumerate() {
+            let mut counts = BTreeMap::<Row, usize>::default();
+
+            for row in 0..file.tables[TableIndex::NestedClass as usize].row_count {
+                let row = Row::new(row, TableIndex::NestedClass, index as u16);
+                let nested = Row::new(reader.u32(row, 0) - 1, TableIndex::TypeDef, index as u16);
+                let outer = Row::new(reader.u32(row, 1) - 1, TableIndex::TypeDef, index as u16);
+                let count = counts.entry(outer).or_default();
+                enclosing.insert(nested, (outer, *count));
+                *count += 1;
+            }
 
-                    types
-                        .entry(namespace.to_string())
-                        .or_default()
-                        .entry(name.to_string())
-                        .or_insert(TypeRow::Field((def, field)));
+            for row in 0..file.type_def_table().row_count {
+                let def = Row::new(row, TableIndex::TypeDef, index as u16);
+
+                // Nested types are only reachable through their enclosing types.
+                if enclosing.contains_key(&def) {
+                    continue;
                 }
 
-                for method in reader.list(def, TableIndex::MethodDef, 5) {
-                    let name = reader.str(method, 3);
+                let namespace = reader.str(def, 2);
 
+                if let Some(types) = types.get_mut(namespace) {
+                    types.defs.push(def);
+                } else {
                     types
                         .entry(namespace.to_string())
                         .or_default()
-                        .entry(name.to_string())
-                        .or_insert(TypeRow::MethodDef((def, method)));
+                        .defs
+                        .push(def);
                 }
             }
         }
 
-        fn remove_excluded_type(
-            types: &mut BTreeMap<String, BTreeMap<String, TypeRow>>,
-            (namespace, type_name): (&str, &str),
-        ) {
-            if let Some(value) = types.get_mut(namespace) {
-                value.remove(type_name);
+        let lowercase = types
+            .keys()
+            .map(|namespace| (namespace.to_lowercase(), namespace.clone()))
+            .collect();
+
+        // Nested types are named after their enclosing types since their metadata names,
+        // like `_Anonymous_e__Union`, are neither unique nor meaningful.
+        fn nested_name(
+            reader: &TypeReader,
+            enclosing: &BTreeMap<Row, (Row, usize)>,
+            row: Row,
+        ) -> String {
+            match enclosing.get(&row) {
+                Some((outer, index)) => {
+                    format!("{}_{}", nested_name(reader, enclosing, *outer), index)
+                }
+                None => reader.str(row, 1).to_string(),
             }
         }
 
-        remove_excluded_type(&mut types, ("Windows.Foundation", "HResult"));
-        remove_excluded_type(&mut types, ("Windows.Win32.Com", "IUnknown"));
-        remove_excluded_type(&mut types, ("Windows.Win32.SystemServices", "BOOL"));
-        remove_excluded_type(&mut types, ("Windows.Win32.Direct2D", "D2D_MATRIX_3X2_F"));
-
-        remove_excluded_type(
-            &mut types,
-            ("Windows.Win32.SystemServices", "LARGE_INTEGER"),
-        );
-
-        remove_excluded_type(
-            &mut types,
-            ("Windows.Win32.SystemServices", "ULARGE_INTEGER"),
-        );
+        let nested = enclosing
+            .iter()
+            .map(|(row, (outer, _))| (*row, (*outer, nested_name(&reader, &enclosing, *row))))
+            .collect();
 
-        // TODO: map BSTR
+        let mut enclosed = BTreeMap::<Row, Vec<Row>>::default();
 
-        // TODO: remove once this is fixed: https://github.com/microsoft/win32metadata/issues/30
-        remove_excluded_type(
-            &mut types,
-            ("Windows.Win32", "CFunctionDiscoveryNotificationWrapper"),
-        );
+        for (row, (outer, _)) in &enclosing {
+            enclosed.entry(*outer).or_default().push(*row);
+        }
 
         Self {
             files: reader.files,
             types,
+            lowercase,
+            nested,
+            enclosed,
+            semantics: OnceLock::new(),
+            property_maps: OnceLock::new(),
+            event_maps: OnceLock::new(),
+            docs: OnceLock::new(),
+            names: OnceLock::new(),
+            union_fields: OnceLock::new(),
         }
     }
 
-    pub fn find_lowercase_namespace(&'static self, lowercase: &str) -> Option<&'static str> {
-        self.types
-            .keys()
-            .find(|namespace| namespace.to_lowercase() == lowercase)
+    /// Get the index of a namespace's types, functions and constants, building it on first use
+    fn namespace_index(&self, namespace: &str) -> Option<&BTreeMap<String, Vec<TypeRow>>> {
+        let types = self.types.get(namespace)?;
+        Some(
+            types
+                .index
+                .get_or_init(|| self.index_namespace(namespace, &types.defs)),
+        )
+    }
+
+    fn index_namespace(&self, namespace: &str, defs: &[Row]) -> BTreeMap<String, Vec<TypeRow>> {
+        let mut types = BTreeMap::<String, Vec<TypeRow>>::default();
+
+        for def in defs {
+            let def = *def;
+
+            types
+                .entry(self.str(def, 1).to_string())
+                .or_default()
+                .push(TypeRow::TypeDef(def));
+
+            let flags = TypeFlags(self.u32(def, 0));
+
+            if flags.interface() || flags.windows_runtime() {
+                continue;
+            }
+
+            let extends = self.u32(def, 3);
+
+            if extends == 0 {
+                continue;
+            }
+
+            let extends = Row::new((extends >> 2) - 1, TableIndex::TypeRef, def.file_index);
+
+            if (self.str(extends, 2), self.str(extends, 1)) != ("System", "Object") {
+                continue;
+            }
+
+            for field in self.list(def, TableIndex::Field, 4) {
+                types
+                    .entry(self.str(field, 1).to_string())
+                    .or_default()
+                    .push(TypeRow::Field((def, field)));
+            }
+
+            for method in self.list(def, TableIndex::MethodDef, 5) {
+                types
+                    .entry(self.str(method, 3).to_string())
+                    .or_default()
+                    .push(TypeRow::MethodDef((def, method)));
+            }
+        }
+
+        for (excluded_namespace, type_name) in EXCLUDED_TYPES {
+            if *excluded_namespace == namespace {
+                types.remove(*type_name);
+            }
+        }
+
+        types
+    }
+
+    /// Get the summary of a member from the XML documentation files next to the metadata
+    /// files, if any
+    ///
+    /// Members are named as in .NET documentation files but without parameter lists, such
+    /// as `T:Windows.Foundation.Uri` for a type or `M:Windows.Foundation.IClosable.Close`
+    /// for a method. The documentation file for `Windows.Foundation.winmd` is expected to
+    /// be `Windows.Foundation.xml` in the same directory.
+    pub fn doc_summary(&self, member: &str) -> Option<&str> {
+        self.docs
+            .get_or_init(|| {
+                let mut docs = HashMap::new();
+
+                for file in &self.files {
+                    // Only files read from disk have a directory to look in.
+                    if !matches!(file.bytes, Bytes::Mapped(_)) {
+                        continue;
+                    }
+
+                    if let Ok(xml) = std::fs::read_to_string(file.path.with_extension("xml")) {
+                        for (name, summary) in docs::read_summaries(&xml) {
+                            docs.entry(name).or_insert(summary);
+                        }
+                    }
+                }
+
+                docs
+            })
+            .get(member)
+            .map(|summary| summary.as_str())
+    }
+
+    pub fn find_lowercase_namespace(&self, lowercase: &str) -> Option<&str> {
+        self.lowercase
+            .get(lowercase)
             .map(|namespace| namespace.as_str())
     }
 
@@ -193,62 +422,247 @@ impl TypeReader {
         self.types.keys()
     }
 
+    /// Get the namespaces that define a type, function or constant with the given name, in
+    /// alphabetical order
+    ///
+    /// The first lookup indexes every namespace.
+    pub fn find_type_namespaces(&self, type_name: &str) -> impl Iterator<Item = &str> {
+        self.names_index()
+            .get(type_name)
+            .into_iter()
+            .flatten()
+            .map(|namespace| namespace.as_str())
+    }
+
+    /// Get the names of the types, functions and constants of every namespace
+    pub fn type_names(&self) -> impl Iterator<Item = &str> {
+        self.names_index().keys().map(|name| name.as_str())
+    }
+
+    fn names_index(&self) -> &HashMap<String, Vec<String>> {
+        self.names.get_or_init(|| {
+            let mut names = HashMap::<String, Vec<String>>::new();
+
+            for namespace in self.types.keys() {
+                for name in self
+                    .namespace_index(namespace)
+                    .into_iter()
+                    .flat_map(|index| index.keys())
+                {
+                    names
+                        .entry(name.clone())
+                        .or_default()
+                        .push(namespace.clone());
+                }
+            }
+
+            names
+        })
+    }
+
+    /// Whether values of the type definition are fields of a union, directly or within other
+    /// structs
+    ///
+    /// The first lookup walks the fields of every union.
+    pub fn is_union_field(&'static self, def: &TypeDef) -> bool {
+        self.union_fields
+            .get_or_init(|| {
+                let mut pending: Vec<TypeDef> = self
+                    .files
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, file)| {
+                        (0..file.type_def_table().row_count).map(move |row| TypeDef {
+                            reader: self,
+                            row: Row::new(row, TableIndex::TypeDef, index as u16),
+                        })
+                    })
+                    .filter(|def| def.is_union())
+                    .collect();
+
+                let mut fields = BTreeSet::new();
+
+                while let Some(def) = pending.pop() {
+                    for field in def.fields().filter(|field| !field.flags().literal()) {
+                        if let Some(def) = field.value_type(&def) {
+                            if fields.insert(def.row) {
+                                pending.push(def);
+                            }
+                        }
+                    }
+                }
+
+                fields
+            })
+            .contains(&def.row)
+    }
+
     /// Get all type definitions ([`TypeDef`]s) for a given namespace
     ///
     /// # Panics
     ///
     /// Panics if the namespace does not exist
     pub fn namespace_types(&'static self, namespace: &str) -> impl Iterator<Item = Type> + '_ {
-        self.types[namespace]
+        self.namespace_index(namespace)
+            .unwrap_or_else(|| panic!("Could not find namespace `{}`", namespace))
             .values()
-            .map(move |row| Type::new(self, *row))
+            .map(move |rows| Type::new(self, rows[0]))
+    }
+
+    pub fn find_type(&'static self, (namespace, type_name): (&str, &str)) -> Option<Type> {
+        let rows = self.namespace_index(namespace)?.get(type_name)?;
+        Some(Type::new(self, rows[0]))
     }
 
     pub fn expect_type(&'static self, (namespace, type_name): (&str, &str)) -> Type {
-        if let Some(types) = self.types.get(namespace) {
-            if let Some(row) = types.get(type_name) {
-                return Type::new(self, *row);
-            }
+        if let Some(def) = self.find_type((namespace, type_name)) {
+            return def;
         }
 
         panic!("Could not find type `{}.{}`", namespace, type_name);
     }
 
+    pub fn find_type_def(&'static self, (namespace, type_name): (&str, &str)) -> Option<TypeDef> {
+        if let TypeRow::TypeDef(row) = self.namespace_index(namespace)?.get(type_name)?[0] {
+            Some(TypeDef { reader: self, row })
+        } else {
+            None
+        }
+    }
+
+    /// Get every definition of a type, function or constant, such as the per-architecture
+    /// variants of a Win32 struct, starting with the one returned by [`TypeReader::expect_type`]
+    ///
+    /// Definitions of other kinds that happen to share the name are not included.
+    pub fn type_variants(
+        &'static self,
+        (namespace, type_name): (&str, &str),
+    ) -> impl Iterator<Item = Type> + 'static {
+        let rows = self
+            .namespace_index(namespace)
+            .and_then(|types| types.get(type_name))
+            .map_or(&[][..], |rows| rows.as_slice());
+
+        rows.iter()
+            .filter(move |row| std::mem::discriminant(*row) == std::mem::discriminant(&rows[0]))
+            .map(move |row| Type::new(self, *row))
+    }
+
     pub fn expect_type_def(&'static self, (namespace, type_name): (&str, &str)) -> TypeDef {
-        if let Some(types) = self.types.get(namespace) {
-            if let Some(TypeRow::TypeDef(row)) = types.get(type_name) {
-                return TypeDef {
-                    reader: self,
-                    row: *row,
-                };
-            }
+        if let Some(def) = self.find_type_def((namespace, type_name)) {
+            return def;
         }
 
         panic!("Could not find type def `{}.{}`", namespace, type_name);
     }
 
+    /// Get the enclosing type definition and generated name of a nested type definition
+    pub(crate) fn nested_type(&self, row: Row) -> Option<(Row, &str)> {
+        self.nested
+            .get(&row)
+            .map(|(enclosing, name)| (*enclosing, name.as_str()))
+    }
+
+    /// Get the `MethodSemantics` row that associates a method with a property or event
+    pub(crate) fn method_semantics(&self, method: Row) -> Option<Row> {
+        self.semantics
+            .get_or_init(|| {
+                let mut semantics = BTreeMap::default();
+
+                for (index, file) in self.files.iter().enumerate() {
+                    for row in 0..file.tables[TableIndex::MethodSemantics as usize].row_count {
+                        let row = Row::new(row, TableIndex::MethodSemantics, index as u16);
+                        let method =
+                            Row::new(self.u32(row, 1) - 1, TableIndex::MethodDef, index as u16);
+                        semantics.insert(method, row);
+                    }
+                }
+
+                semantics
+            })
+            .get(&method)
+            .copied()
+    }
+
+    /// Get the `PropertyMap` row that lists the properties of a type definition
+    pub(crate) fn property_map(&self, def: Row) -> Option<Row> {
+        self.property_maps
+            .get_or_init(|| self.map_index(TableIndex::PropertyMap))
+            .get(&def)
+            .copied()
+    }
+
+    /// Get the `EventMap` row that lists the events of a type definition
+    pub(crate) fn event_map(&self, def: Row) -> Option<Row> {
+        self.event_maps
+            .get_or_init(|| self.map_index(TableIndex::EventMap))
+            .get(&def)
+            .copied()
+    }
+
+    /// Index the rows of a `PropertyMap` or `EventMap` table by their parent type definition
+    fn map_index(&self, table: TableIndex) -> BTreeMap<Row, Row> {
+        let mut maps = BTreeMap::default();
+
+        for (index, file) in self.files.iter().enumerate() {
+            for row in 0..file.tables[table as usize].row_count {
+                let row = Row::new(row, table, index as u16);
+                let parent = Row::new(self.u32(row, 0) - 1, TableIndex::TypeDef, index as u16);
+                maps.insert(parent, row);
+            }
+        }
+
+        maps
+    }
+
+    /// Get the nested type definitions directly enclosed by a type definition
+    pub(crate) fn nested_types(&self, enclosing: Row) -> impl Iterator<Item = Row> + '_ {
+        self.enclosed.get(&enclosing).into_iter().flatten().copied()
+    }
+
     /// Read a [`u32`] value from a specific [`Row`] and column
     pub fn u32(&self, row: Row, column: u32) -> u32 {
-        let file = &self.files[row.file_index as usize];
-        let table = &file.tables[row.table_index as usize];
-        let offset = table.data + row.index * table.row_size + table.columns[column as usize].0;
-        match table.columns[column as usize].1 {
-            1 => file.bytes.copy_as::<u8>(offset) as u32,
-            2 => file.bytes.copy_as::<u16>(offset) as u32,
-            4 => file.bytes.copy_as::<u32>(offset) as u32,
-            _ => file.bytes.copy_as::<u64>(offset) as u32,
-        }
+        self.files[row.file_index as usize].u32(row.table_index, row.index, column)
     }
 
     /// Read a [`&str`] value from a specific [`Row`] and column
+    ///
+    /// # Panics
+    ///
+    /// Panics with the error of [`TypeReader::try_str`] if the string is malformed.
     pub fn str(&self, row: Row, column: u32) -> &str {
-        let file = &self.files[row.file_index as usize];
-        let offset = (file.strings + self.u32(row, column)) as usize;
-        let last = file.bytes[offset..]
-            .iter()
-            .position(|c| *c == b'\0')
-            .unwrap();
-        std::str::from_utf8(&file.bytes[offset..offset + last]).unwrap()
+        self.try_str(row, column)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Read a [`&str`] value from a specific [`Row`] and column, or the error describing
+    /// why the string index is malformed
+    pub fn try_str(&self, row: Row, column: u32) -> Result<&str, WinmdError> {
+        self.files[row.file_index as usize].string(self.u32(row, column))
+    }
+
+    /// Read a [`Guid`] value from a specific [`Row`] and column
+    pub fn guid(&self, row: Row, column: u32) -> Guid {
+        self.files[row.file_index as usize]
+            .guid(self.u32(row, column))
+            .unwrap_or_default()
+    }
+
+    /// Read a string from the `#US` heap of a specific file
+    pub fn user_string(&self, file_index: u16, offset: u32) -> Option<String> {
+        self.files[file_index as usize].user_string(offset)
+    }
+
+    /// Get the `Module` of each metadata file the [`TypeReader`] has access to
+    pub fn modules(&'static self) -> impl Iterator<Item = Module> {
+        (0..self.files.len())
+            .filter(move |file_index| {
+                self.files[*file_index].tables[TableIndex::Module as usize].row_count > 0
+            })
+            .map(move |file_index| Module {
+                reader: self,
+                row: Row::new(0, TableIndex::Module, file_index as u16),
+            })
     }
 
     /// Read a `T: Decode` value from a specific [`Row`] and column
@@ -275,26 +689,26 @@ impl TypeReader {
     }
 
     /// Read a blob for a given row and column
+    ///
+    /// # Panics
+    ///
+    /// Panics with the error of [`TypeReader::try_blob`] if the blob is malformed.
     pub fn blob(&'static self, row: Row, column: u32) -> Blob {
-        let file = &self.files[row.file_index as usize];
-        let offset = (file.blobs + self.u32(row, column)) as usize;
-        let initial_byte = file.bytes[offset];
-        let (blob_size, blob_size_bytes) = match initial_byte >> 5 {
-            0..=3 => (initial_byte & 0x7f, 1),
-            4..=5 => (initial_byte & 0x3f, 2),
-            6 => (initial_byte & 0x1f, 4),
-            _ => panic!("Invalid blob size"),
-        };
-        let mut blob_size = blob_size as usize;
-        for byte in &file.bytes[offset + 1..offset + blob_size_bytes] {
-            blob_size = blob_size.checked_shl(8).unwrap_or(0) + (*byte as usize);
-        }
-        Blob {
+        self.try_blob(row, column)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Read a blob for a given row and column, or the error describing why its size or
+    /// bounds are malformed
+    pub fn try_blob(&'static self, row: Row, column: u32) -> Result<Blob, WinmdError> {
+        let (offset, size) = self.files[row.file_index as usize].blob(self.u32(row, column))?;
+
+        Ok(Blob {
             reader: self,
             file_index: row.file_index,
-            offset: offset + blob_size_bytes,
-            size: blob_size,
-        }
+            offset,
+            size,
+        })
     }
 
     pub(crate) fn equal_range(
@@ -401,16 +815,7 @@ impl TypeReader {
     }
 }
 
-fn winmd_paths() -> Vec<std::path::PathBuf> {
-    let mut windows_path = workspace_windows_dir();
-    windows_path.push("winmd");
-
-    let mut paths = vec![];
-    push_winmd_paths(windows_path, &mut paths);
-    paths
-}
-
-fn push_winmd_paths(dir: std::path::PathBuf, paths: &mut Vec<std::path::PathBuf>) {
+fn push_winmd_paths(dir: PathBuf, paths: &mut Vec<PathBuf>) {
     if let Ok(files) = std::fs::read_dir(dir) {
         for file in files.filter_map(|file| file.ok()) {
             if let Ok(file_type) = file.file_type() {
@@ -418,10 +823,70 @@ fn push_winmd_paths(dir: std::path::PathBuf, paths: &mut Vec<std::path::PathBuf>
                     let path = file.path();
                     if let Some("winmd") = path.extension().and_then(|extension| extension.to_str())
                     {
-                        paths.push(file.path());
+                        if !paths.contains(&path) {
+                            paths.push(path);
+                        }
                     }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn independent_readers() {
+        let first = TypeReader::from_bytes(vec![WINRT.0.to_vec()]).unwrap();
+        let second = TypeReader::from_bytes(vec![WINRT.0.to_vec(), WIN32.0.to_vec()]).unwrap();
+
+        assert_eq!(first.files.len(), 1);
+        assert_eq!(second.files.len(), 2);
+        assert!(first.namespaces().count() < second.namespaces().count());
+
+        let first: &'static TypeReader = Box::leak(Box::new(first));
+        let def = first.expect_type_def(("Windows.Foundation", "Uri"));
+        assert_eq!(def.name(), ("Windows.Foundation", "Uri"));
+
+        let error = TypeReader::from_bytes(vec![vec![0; 4]]).err().unwrap();
+        assert_eq!(error.path(), Path::new("0"));
+    }
+
+    #[test]
+    fn extra_paths() {
+        let source = "namespace Contoso.Widgets { runtimeclass Widget { Int32 Length(); }; }";
+        let bytes = idl::compile(source, "Contoso.Widgets").unwrap().write();
+
+        let dir = std::env::temp_dir().join("windows_gen_extra_paths");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Contoso.Widgets.winmd"), bytes).unwrap();
+
+        let reader = TypeReader::try_get_with(std::slice::from_ref(&dir)).unwrap();
+        assert!(reader
+            .find_type_def(("Contoso.Widgets", "Widget"))
+            .is_some());
+        assert!(reader
+            .find_type_def(("Windows.Foundation", "Uri"))
+            .is_some());
+
+        let error = TypeReader::try_get_with(&[dir.join("Missing.winmd")])
+            .err()
+            .unwrap();
+        assert_eq!(error.path(), dir.join("Missing.winmd"));
+    }
+
+    #[test]
+    fn type_namespaces() {
+        let reader = TypeReader::get();
+
+        let namespaces: Vec<_> = reader.find_type_namespaces("Uri").collect();
+        assert_eq!(namespaces, ["Windows.Foundation"]);
+
+        let namespaces: Vec<_> = reader.find_type_namespaces("GetLastError").collect();
+        assert_eq!(namespaces, ["Windows.Win32.Foundation"]);
+
+        assert_eq!(reader.find_type_namespaces("Missing").count(), 0);
+    }
+}
@@ -11,6 +11,73 @@ impl TypeRef {
     }
 
     pub fn resolve(&self) -> TypeDef {
-        self.reader.expect_type_def(self.name())
+        self.find_resolved().unwrap_or_else(|| {
+            let (namespace, name) = self.name();
+            panic!("Could not find type def `{}.{}`", namespace, name)
+        })
+    }
+
+    /// Resolves the reference to its definition, if it's defined by the loaded metadata
+    ///
+    /// A nested type is looked up in every per-architecture variant of its enclosing type,
+    /// as the variants don't necessarily share their nested types.
+    pub fn find_resolved(&self) -> Option<TypeDef> {
+        if let ResolutionScope::TypeRef(_) = self.scope() {
+            return self.resolve_variants().into_iter().next();
+        }
+
+        self.reader.find_type_def(self.name())
+    }
+
+    /// Resolves the reference as seen from the type definition that holds it
+    ///
+    /// Each per-architecture variant of a type defines its own nested types, so a reference
+    /// to a nested type of the referrer's outermost type resolves within that same variant.
+    pub fn find_resolved_from(&self, referrer: &TypeDef) -> Option<TypeDef> {
+        match self.scope() {
+            ResolutionScope::TypeRef(enclosing) => {
+                let name = self.reader.str(self.row, 1);
+
+                enclosing
+                    .find_resolved_from(referrer)?
+                    .nested_types()
+                    .find(|nested| nested.reader.str(nested.row, 1) == name)
+            }
+            _ => {
+                let mut outermost = *referrer;
+
+                while let Some(enclosing) = outermost.enclosing_type() {
+                    outermost = enclosing;
+                }
+
+                if outermost.name() == self.name() {
+                    Some(outermost)
+                } else {
+                    self.find_resolved()
+                }
+            }
+        }
+    }
+
+    /// Resolves the reference to every per-architecture variant of its definition
+    fn resolve_variants(&self) -> Vec<TypeDef> {
+        if let ResolutionScope::TypeRef(enclosing) = self.scope() {
+            let name = self.reader.str(self.row, 1);
+
+            return enclosing
+                .resolve_variants()
+                .into_iter()
+                .flat_map(|enclosing| enclosing.nested_types().collect::<Vec<_>>())
+                .filter(|nested| nested.reader.str(nested.row, 1) == name)
+                .collect();
+        }
+
+        self.reader
+            .type_variants(self.name())
+            .filter_map(|variant| match variant {
+                Type::TypeDef(def) => Some(def),
+                _ => None,
+            })
+            .collect()
     }
 }
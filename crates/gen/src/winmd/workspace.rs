@@ -1,41 +1,149 @@
-/// Returns the build's `.windows` directory in the root of the workspace as a `PathBuf`.
-pub fn workspace_windows_dir() -> std::path::PathBuf {
-    let mut path = workspace_dir();
-    path.push(".windows");
-    path
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The environment variable naming the workspace directory, which overrides discovery
+pub const WORKSPACE_DIR_VAR: &str = "WINDOWS_WORKSPACE_DIR";
+
+/// Returns the build's `.windows` directory in the root of the workspace as a `PathBuf`, or
+/// `None` if the workspace can't be found.
+pub fn workspace_windows_dir() -> Option<PathBuf> {
+    workspace_dir().map(|dir| dir.join(".windows"))
+}
+
+/// Finds the root of the workspace, in order, from:
+///
+/// * the `WINDOWS_WORKSPACE_DIR` environment variable
+/// * the `Cargo.toml` files in and above `CARGO_MANIFEST_DIR`, which Cargo sets when building
+/// * the `Cargo.toml` files in and above the current directory, when not building with Cargo
+fn workspace_dir() -> Option<PathBuf> {
+    static VALUE: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+    VALUE
+        .get_or_init(|| {
+            if let Some(dir) = std::env::var_os(WORKSPACE_DIR_VAR).filter(|dir| !dir.is_empty()) {
+                return Some(dir.into());
+            }
+
+            if let Some(dir) = std::env::var_os("CARGO_MANIFEST_DIR") {
+                return Some(find_workspace_dir(Path::new(&dir)));
+            }
+
+            let current_dir = std::env::current_dir().ok()?;
+
+            current_dir
+                .ancestors()
+                .find(|dir| dir.join("Cargo.toml").is_file())
+                .map(find_workspace_dir)
+        })
+        .clone()
 }
 
-fn workspace_dir() -> std::path::PathBuf {
-    use std::{mem::MaybeUninit, sync::Once};
-    static ONCE: Once = Once::new();
-    static mut VALUE: MaybeUninit<std::path::PathBuf> = MaybeUninit::uninit();
-
-    ONCE.call_once(|| {
-        let output = std::process::Command::new(env!("CARGO"))
-            .arg("metadata")
-            .arg("--format-version=1")
-            .arg("--no-deps")
-            .output()
-            .expect("Failed to run `cargo metadata`");
-
-        const JSON_KEY: &str = r#""workspace_root":"#;
-        let json = String::from_utf8(output.stdout).expect("Cargo metadata is not utf-8");
-        let beginning_index = json
-            .find(JSON_KEY)
-            .expect("Cargo metadata did not contain `workspace_root` key.")
-            + JSON_KEY.len()
-            + 1;
-
-        let ending_index = json[beginning_index..]
-            .find("\"")
-            .expect("Cargo metadata ended before closing '\"' in `workspace_root` value");
-
-        let workspace_root = &json[beginning_index..beginning_index + ending_index];
-
-        // This is safe because `Once` provides thread-safe one-time initialization
-        unsafe { VALUE = MaybeUninit::new(workspace_root.into()) }
+/// Finds the root of the workspace that the package in `manifest_dir` belongs to
+///
+/// This is the package's `workspace` key if it has one, and otherwise the nearest directory at
+/// or above the package with a `Cargo.toml` that has a `[workspace]` table. A package that
+/// isn't part of a workspace is its own root.
+fn find_workspace_dir(manifest_dir: &Path) -> PathBuf {
+    let workspace = read_manifest(manifest_dir).and_then(|manifest| {
+        manifest
+            .get("package")?
+            .get("workspace")?
+            .as_str()
+            .map(str::to_string)
     });
 
-    // This is safe because `call_once` has already been called.
-    unsafe { (*VALUE.as_ptr()).clone() }
+    if let Some(workspace) = workspace {
+        return manifest_dir.join(workspace);
+    }
+
+    manifest_dir
+        .ancestors()
+        .find(|dir| {
+            read_manifest(dir).is_some_and(|manifest| {
+                manifest
+                    .get("workspace")
+                    .is_some_and(|workspace| workspace.is_table())
+            })
+        })
+        .unwrap_or(manifest_dir)
+        .to_path_buf()
+}
+
+/// Reads the `Cargo.toml` in a directory, if there is one and it's valid
+fn read_manifest(dir: &Path) -> Option<toml::Value> {
+    std::fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// The environment variable listing extra metadata files, or directories of metadata files,
+/// separated like `PATH`
+pub const WINMD_PATH_VAR: &str = "WINDOWS_WINMD_PATH";
+
+/// The environment variable listing extra directories of DLLs, separated like `PATH`
+///
+/// Each directory is laid out like the `.windows` directory, with a subdirectory for each
+/// architecture such as `x64` or `arm64`.
+pub const DLL_PATH_VAR: &str = "WINDOWS_DLL_PATH";
+
+/// Returns the metadata paths listed by the `WINDOWS_WINMD_PATH` environment variable
+///
+/// The `DEP_<links>_WINMD` variables of dependencies that package metadata aren't read since
+/// Cargo only sets them when build scripts run, whereas the metadata is read when `build!` is
+/// expanded as the build script is compiled.
+pub fn env_winmd_paths() -> Vec<PathBuf> {
+    split_env_paths(WINMD_PATH_VAR)
+}
+
+fn split_env_paths(name: &str) -> Vec<PathBuf> {
+    std::env::var_os(name)
+        .map(|value| {
+            std::env::split_paths(&value)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_discovery() {
+        let root = std::env::temp_dir().join("windows_gen_workspace_discovery");
+        let member = root.join("crates").join("member");
+        let detached = root.join("detached");
+        let standalone = std::env::temp_dir().join("windows_gen_standalone");
+
+        for dir in &[&member, &detached, &standalone] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace.package]\nversion = \"0.1.0\"\n\n[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        std::fs::write(member.join("Cargo.toml"), "[package]\nname = \"member\"\n").unwrap();
+        std::fs::write(
+            detached.join("Cargo.toml"),
+            "[package]\nname = \"detached\"\nworkspace = '../crates/member' # elsewhere\n",
+        )
+        .unwrap();
+        std::fs::write(
+            standalone.join("Cargo.toml"),
+            "[package]\nname = \"standalone\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(find_workspace_dir(&member), root);
+        assert_eq!(find_workspace_dir(&root), root);
+        assert_eq!(
+            find_workspace_dir(&detached),
+            detached.join("../crates/member")
+        );
+        assert_eq!(find_workspace_dir(&standalone), standalone);
+    }
 }
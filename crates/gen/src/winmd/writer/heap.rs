@@ -0,0 +1,109 @@
+use super::*;
+use std::collections::HashMap;
+
+/// The `#Strings` heap, where index 0 is always the empty string
+pub(crate) struct Strings {
+    bytes: Vec<u8>,
+    map: HashMap<String, u32>,
+}
+
+impl Strings {
+    pub fn new() -> Self {
+        Self {
+            bytes: vec![0],
+            map: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, value: &str) -> u32 {
+        if value.is_empty() {
+            return 0;
+        }
+
+        if let Some(index) = self.map.get(value) {
+            return *index;
+        }
+
+        let index = self.bytes.len() as u32;
+        self.bytes.extend_from_slice(value.as_bytes());
+        self.bytes.push(0);
+        self.map.insert(value.to_string(), index);
+        index
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// The `#Blob` heap, where index 0 is always the empty blob
+pub(crate) struct Blobs {
+    bytes: Vec<u8>,
+    map: HashMap<Vec<u8>, u32>,
+}
+
+impl Blobs {
+    pub fn new() -> Self {
+        Self {
+            bytes: vec![0],
+            map: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, value: &[u8]) -> u32 {
+        if value.is_empty() {
+            return 0;
+        }
+
+        if let Some(index) = self.map.get(value) {
+            return *index;
+        }
+
+        let index = self.bytes.len() as u32;
+        write_compressed(&mut self.bytes, value.len() as u32);
+        self.bytes.extend_from_slice(value);
+        self.map.insert(value.to_vec(), index);
+        index
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// The `#GUID` heap, whose indexes are 1-based
+pub(crate) struct Guids {
+    bytes: Vec<u8>,
+}
+
+impl Guids {
+    pub fn new() -> Self {
+        Self { bytes: Vec::new() }
+    }
+
+    pub fn insert(&mut self, value: &Guid) -> u32 {
+        self.bytes.extend_from_slice(&value.0);
+        (self.bytes.len() / 16) as u32
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Writes an unsigned integer using the compressed encoding of ECMA-335 II.23.2
+pub(crate) fn write_compressed(buffer: &mut Vec<u8>, value: u32) {
+    if value < 0x80 {
+        buffer.push(value as u8);
+    } else if value < 0x4000 {
+        buffer.extend_from_slice(&(0x8000 | value as u16).to_be_bytes());
+    } else {
+        buffer.extend_from_slice(&(0xC000_0000 | value).to_be_bytes());
+    }
+}
+
+/// Writes a `SerString` as used by custom attribute values
+pub(crate) fn write_ser_string(buffer: &mut Vec<u8>, value: &str) {
+    write_compressed(buffer, value.len() as u32);
+    buffer.extend_from_slice(value.as_bytes());
+}
@@ -0,0 +1,1144 @@
+//! A writer of Windows Metadata files
+//!
+//! The writer serializes an in-memory model of types into an ECMA-335 `.winmd` file that
+//! can in turn be read by [`TypeReader`](super::TypeReader).
+
+mod heap;
+mod pe;
+mod table;
+
+use super::{ConstantValue, Guid};
+use heap::*;
+use pe::{align, put_u16, put_u32};
+use std::collections::HashMap;
+use table::*;
+
+/// A metadata file made up of type definitions
+#[derive(Clone, Debug, Default)]
+pub struct Metadata {
+    /// The name of the assembly and module, e.g. `TestComponent`
+    pub name: String,
+    /// The module version identifier, which is derived from the contents of the file when zero
+    pub mvid: Guid,
+    pub types: Vec<TypeDefinition>,
+}
+
+/// A type definition along with its members
+#[derive(Clone, Debug, Default)]
+pub struct TypeDefinition {
+    pub namespace: String,
+    pub name: String,
+    /// The `TypeAttributes` of ECMA-335 II.23.1.15
+    pub flags: u32,
+    /// The base type, which is either a named type or a generic instance
+    pub extends: Option<TypeSig>,
+    /// The names of the type's generic parameters
+    pub generics: Vec<String>,
+    pub interfaces: Vec<InterfaceImplDefinition>,
+    pub fields: Vec<FieldDefinition>,
+    pub methods: Vec<MethodDefinition>,
+    pub properties: Vec<PropertyDefinition>,
+    pub events: Vec<EventDefinition>,
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+/// An interface implemented by a type
+#[derive(Clone, Debug)]
+pub struct InterfaceImplDefinition {
+    pub interface: TypeSig,
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+impl From<TypeSig> for InterfaceImplDefinition {
+    fn from(interface: TypeSig) -> Self {
+        Self {
+            interface,
+            attributes: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldDefinition {
+    pub name: String,
+    /// The `FieldAttributes` of ECMA-335 II.23.1.5
+    pub flags: u32,
+    pub sig: TypeSig,
+    pub constant: Option<ConstantValue>,
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MethodDefinition {
+    pub name: String,
+    /// The `MethodAttributes` of ECMA-335 II.23.1.10
+    pub flags: u32,
+    /// The `MethodImplAttributes` of ECMA-335 II.23.1.11
+    pub impl_flags: u32,
+    pub return_type: TypeSig,
+    pub params: Vec<ParamDefinition>,
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParamDefinition {
+    pub name: String,
+    /// The `ParamAttributes` of ECMA-335 II.23.1.13
+    pub flags: u32,
+    pub sig: TypeSig,
+}
+
+/// A property whose accessors are methods of the same type
+#[derive(Clone, Debug)]
+pub struct PropertyDefinition {
+    pub name: String,
+    pub sig: TypeSig,
+    /// The index of the getter within the type's methods
+    pub getter: Option<usize>,
+    /// The index of the setter within the type's methods
+    pub setter: Option<usize>,
+}
+
+/// An event whose accessors are methods of the same type
+#[derive(Clone, Debug)]
+pub struct EventDefinition {
+    pub name: String,
+    /// The delegate type of the event
+    pub event_type: TypeSig,
+    /// The index of the `add` method within the type's methods
+    pub add: usize,
+    /// The index of the `remove` method within the type's methods
+    pub remove: usize,
+}
+
+/// A custom attribute applied to a type or member
+#[derive(Clone, Debug)]
+pub struct AttributeDefinition {
+    /// The attribute type, e.g. `Windows.Foundation.Metadata.GuidAttribute`
+    pub name: TypeName,
+    /// The constructor arguments, from which the constructor's signature is derived
+    pub args: Vec<AttributeValue>,
+    /// The named arguments, which are written as properties
+    pub named_args: Vec<(String, AttributeValue)>,
+}
+
+/// The value of a custom attribute argument
+#[derive(Clone, Debug)]
+pub enum AttributeValue {
+    Bool(bool),
+    Char(char),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Type(TypeName),
+    /// An enum value along with its underlying integral value
+    Enum(TypeName, Box<AttributeValue>),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TypeName {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl TypeName {
+    pub fn new(namespace: &str, name: &str) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
+
+/// A type as it appears in a signature
+#[derive(Clone, Debug)]
+pub enum TypeSig {
+    Void,
+    Bool,
+    Char,
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    ISize,
+    USize,
+    String,
+    Object,
+    Class(TypeName),
+    ValueType(TypeName),
+    /// An instantiation of a generic class, interface, or delegate
+    GenericInstance(TypeName, Vec<TypeSig>),
+    /// A generic parameter of the enclosing type given its position
+    GenericParam(u32),
+    Array(Box<TypeSig>),
+    /// An array with the given size of each dimension, as the fixed-size array fields of
+    /// Win32 structs are encoded
+    FixedArray(Box<TypeSig>, Vec<u32>),
+    Pointer(Box<TypeSig>),
+    ByRef(Box<TypeSig>),
+}
+
+impl Metadata {
+    /// Serializes the metadata as the bytes of a `.winmd` file
+    pub fn write(&self) -> Vec<u8> {
+        Writer::new(self).write()
+    }
+
+    /// Writes the metadata to a `.winmd` file at the given path
+    pub fn write_to_path<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.write())
+    }
+}
+
+struct Writer<'a> {
+    metadata: &'a Metadata,
+    strings: Strings,
+    blobs: Blobs,
+    /// The 1-based rows of the type definitions by name
+    type_defs: HashMap<TypeName, u32>,
+    type_ref_rows: HashMap<TypeName, u32>,
+    assembly_ref_rows: HashMap<String, u32>,
+    type_spec_rows: HashMap<Vec<u8>, u32>,
+    member_ref_rows: HashMap<(u32, Vec<u8>), u32>,
+    module: Table,
+    type_ref: Table,
+    type_def: Table,
+    field: Table,
+    method_def: Table,
+    param: Table,
+    interface_impl: Table,
+    member_ref: Table,
+    constant: Table,
+    custom_attribute: Table,
+    type_spec: Table,
+    assembly: Table,
+    assembly_ref: Table,
+    generic_param: Table,
+    event_map: Table,
+    event: Table,
+    property_map: Table,
+    property: Table,
+    method_semantics: Table,
+}
+
+impl<'a> Writer<'a> {
+    fn new(metadata: &'a Metadata) -> Self {
+        use Column::*;
+
+        Self {
+            metadata,
+            strings: Strings::new(),
+            blobs: Blobs::new(),
+            type_defs: HashMap::new(),
+            type_ref_rows: HashMap::new(),
+            assembly_ref_rows: HashMap::new(),
+            type_spec_rows: HashMap::new(),
+            member_ref_rows: HashMap::new(),
+            module: Table::new(MODULE, &[U16, String, Guid, Guid, Guid]),
+            type_ref: Table::new(TYPE_REF, &[Coded(RESOLUTION_SCOPE), String, String]),
+            type_def: Table::new(
+                TYPE_DEF,
+                &[
+                    U32,
+                    String,
+                    String,
+                    Coded(TYPE_DEF_OR_REF),
+                    Index(FIELD),
+                    Index(METHOD_DEF),
+                ],
+            ),
+            field: Table::new(FIELD, &[U16, String, Blob]),
+            method_def: Table::new(METHOD_DEF, &[U32, U16, U16, String, Blob, Index(PARAM)]),
+            param: Table::new(PARAM, &[U16, U16, String]),
+            interface_impl: Table::new(INTERFACE_IMPL, &[Index(TYPE_DEF), Coded(TYPE_DEF_OR_REF)]),
+            member_ref: Table::new(MEMBER_REF, &[Coded(MEMBER_REF_PARENT), String, Blob]),
+            constant: Table::new(CONSTANT, &[U16, Coded(HAS_CONSTANT), Blob]),
+            custom_attribute: Table::new(
+                CUSTOM_ATTRIBUTE,
+                &[
+                    Coded(HAS_CUSTOM_ATTRIBUTE),
+                    Coded(CUSTOM_ATTRIBUTE_TYPE),
+                    Blob,
+                ],
+            ),
+            type_spec: Table::new(TYPE_SPEC, &[Blob]),
+            assembly: Table::new(
+                ASSEMBLY,
+                &[U32, U16, U16, U16, U16, U32, Blob, String, String],
+            ),
+            assembly_ref: Table::new(
+                ASSEMBLY_REF,
+                &[U16, U16, U16, U16, U32, Blob, String, String, Blob],
+            ),
+            generic_param: Table::new(
+                GENERIC_PARAM,
+                &[U16, U16, Coded(TYPE_OR_METHOD_DEF), String],
+            ),
+            event_map: Table::new(EVENT_MAP, &[Index(TYPE_DEF), Index(EVENT)]),
+            event: Table::new(EVENT, &[U16, String, Coded(TYPE_DEF_OR_REF)]),
+            property_map: Table::new(PROPERTY_MAP, &[Index(TYPE_DEF), Index(PROPERTY)]),
+            property: Table::new(PROPERTY, &[U16, String, Blob]),
+            method_semantics: Table::new(
+                METHOD_SEMANTICS,
+                &[U16, Index(METHOD_DEF), Coded(HAS_SEMANTICS)],
+            ),
+        }
+    }
+
+    fn write(mut self) -> Vec<u8> {
+        let metadata = self.metadata;
+
+        // The first type definition is always the pseudo type `<Module>`, which holds global members.
+        let module_name = self.strings.insert("<Module>");
+        self.type_def.push(vec![0, module_name, 0, 0, 1, 1]);
+
+        for (index, def) in metadata.types.iter().enumerate() {
+            self.type_defs
+                .insert(TypeName::new(&def.namespace, &def.name), index as u32 + 2);
+        }
+
+        let mut attributes = Vec::new();
+
+        for (index, def) in metadata.types.iter().enumerate() {
+            let row = index as u32 + 2;
+
+            let extends = match &def.extends {
+                Some(extends) => self.type_def_or_ref(extends),
+                None => 0,
+            };
+
+            let name = self.strings.insert(&def.name);
+            let namespace = self.strings.insert(&def.namespace);
+
+            self.type_def.push(vec![
+                def.flags,
+                name,
+                namespace,
+                extends,
+                self.field.len() + 1,
+                self.method_def.len() + 1,
+            ]);
+
+            let parent = coded(HAS_CUSTOM_ATTRIBUTE, TYPE_DEF, row);
+            attributes.extend(def.attributes.iter().map(|attribute| (parent, attribute)));
+
+            for (number, generic) in def.generics.iter().enumerate() {
+                let name = self.strings.insert(generic);
+                self.generic_param.push(vec![
+                    number as u32,
+                    0,
+                    coded(TYPE_OR_METHOD_DEF, TYPE_DEF, row),
+                    name,
+                ]);
+            }
+
+            for interface in &def.interfaces {
+                let sig = self.type_def_or_ref(&interface.interface);
+                let interface_row = self.interface_impl.push(vec![row, sig]);
+
+                let parent = coded(HAS_CUSTOM_ATTRIBUTE, INTERFACE_IMPL, interface_row);
+                attributes.extend(
+                    interface
+                        .attributes
+                        .iter()
+                        .map(|attribute| (parent, attribute)),
+                );
+            }
+
+            for field in &def.fields {
+                let mut sig = vec![0x06];
+                self.type_sig(&field.sig, &mut sig);
+
+                let name = self.strings.insert(&field.name);
+                let sig = self.blobs.insert(&sig);
+                let field_row = self.field.push(vec![field.flags, name, sig]);
+
+                if let Some(constant) = &field.constant {
+                    let (element_type, value) = constant_blob(constant);
+                    let value = self.blobs.insert(&value);
+                    self.constant.push(vec![
+                        element_type,
+                        coded(HAS_CONSTANT, FIELD, field_row),
+                        value,
+                    ]);
+                }
+
+                let parent = coded(HAS_CUSTOM_ATTRIBUTE, FIELD, field_row);
+                attributes.extend(field.attributes.iter().map(|attribute| (parent, attribute)));
+            }
+
+            let first_method = self.method_def.len() + 1;
+
+            for method in &def.methods {
+                // Instance methods have an implicit `this` parameter.
+                let mut sig = vec![if method.flags & 0x10 == 0 { 0x20 } else { 0x00 }];
+                write_compressed(&mut sig, method.params.len() as u32);
+                self.type_sig(&method.return_type, &mut sig);
+
+                for param in &method.params {
+                    self.type_sig(&param.sig, &mut sig);
+                }
+
+                let name = self.strings.insert(&method.name);
+                let sig = self.blobs.insert(&sig);
+
+                let method_row = self.method_def.push(vec![
+                    0,
+                    method.impl_flags,
+                    method.flags,
+                    name,
+                    sig,
+                    self.param.len() + 1,
+                ]);
+
+                for (sequence, param) in method.params.iter().enumerate() {
+                    let name = self.strings.insert(&param.name);
+                    self.param
+                        .push(vec![param.flags, sequence as u32 + 1, name]);
+                }
+
+                let parent = coded(HAS_CUSTOM_ATTRIBUTE, METHOD_DEF, method_row);
+                attributes.extend(
+                    method
+                        .attributes
+                        .iter()
+                        .map(|attribute| (parent, attribute)),
+                );
+            }
+
+            if !def.properties.is_empty() {
+                self.property_map.push(vec![row, self.property.len() + 1]);
+            }
+
+            for property in &def.properties {
+                let mut sig = vec![0x28, 0];
+                self.type_sig(&property.sig, &mut sig);
+
+                let name = self.strings.insert(&property.name);
+                let sig = self.blobs.insert(&sig);
+                let property_row = self.property.push(vec![0, name, sig]);
+                let association = coded(HAS_SEMANTICS, PROPERTY, property_row);
+
+                if let Some(getter) = property.getter {
+                    self.method_semantics.push(vec![
+                        0x2,
+                        first_method + getter as u32,
+                        association,
+                    ]);
+                }
+
+                if let Some(setter) = property.setter {
+                    self.method_semantics.push(vec![
+                        0x1,
+                        first_method + setter as u32,
+                        association,
+                    ]);
+                }
+            }
+
+            if !def.events.is_empty() {
+                self.event_map.push(vec![row, self.event.len() + 1]);
+            }
+
+            for event in &def.events {
+                let name = self.strings.insert(&event.name);
+                let event_type = self.type_def_or_ref(&event.event_type);
+                let event_row = self.event.push(vec![0, name, event_type]);
+                let association = coded(HAS_SEMANTICS, EVENT, event_row);
+
+                self.method_semantics
+                    .push(vec![0x8, first_method + event.add as u32, association]);
+                self.method_semantics.push(vec![
+                    0x10,
+                    first_method + event.remove as u32,
+                    association,
+                ]);
+            }
+        }
+
+        // The method semantics table must be sorted by association.
+        self.method_semantics.rows.sort_by_key(|row| row[2]);
+
+        // The custom attribute table must be sorted by parent.
+        attributes.sort_by_key(|(parent, _)| *parent);
+
+        for (parent, attribute) in attributes {
+            let (constructor, value) = self.attribute(attribute);
+            let value = self.blobs.insert(&value);
+            self.custom_attribute.push(vec![parent, constructor, value]);
+        }
+
+        self.constant.rows.sort_by_key(|row| row[1]);
+
+        let name = self.strings.insert(&metadata.name);
+        self.assembly
+            .push(vec![0x8004, 255, 255, 255, 255, 0x200, 0, name, 0]);
+
+        let module_name = self.strings.insert(&format!("{}.winmd", metadata.name));
+
+        // The MVID is written last so that it can be derived from everything else.
+        let tables = self.tables(&Guid::default(), module_name);
+
+        let mvid = if metadata.mvid.is_zero() {
+            derive_mvid(&tables)
+        } else {
+            metadata.mvid
+        };
+
+        pe::write(&self.tables(&mvid, module_name))
+    }
+
+    /// Writes the metadata root and its streams
+    fn tables(&mut self, mvid: &Guid, module_name: u32) -> Vec<u8> {
+        let mut guids = Guids::new();
+        let mvid = guids.insert(mvid);
+
+        self.module.rows.clear();
+        self.module.push(vec![0, module_name, mvid, 0, 0]);
+
+        let tables = write_tables(
+            &[
+                &self.module,
+                &self.type_ref,
+                &self.type_def,
+                &self.field,
+                &self.method_def,
+                &self.param,
+                &self.interface_impl,
+                &self.member_ref,
+                &self.constant,
+                &self.custom_attribute,
+                &self.event_map,
+                &self.event,
+                &self.property_map,
+                &self.property,
+                &self.method_semantics,
+                &self.type_spec,
+                &self.assembly,
+                &self.assembly_ref,
+                &self.generic_param,
+            ],
+            (
+                self.strings.bytes().len(),
+                guids.bytes().len(),
+                self.blobs.bytes().len(),
+            ),
+        );
+
+        let streams: [(&[u8], &[u8]); 5] = [
+            (b"#~", &tables),
+            (b"#Strings", self.strings.bytes()),
+            (b"#US", &[0]),
+            (b"#GUID", guids.bytes()),
+            (b"#Blob", self.blobs.bytes()),
+        ];
+
+        let version = b"WindowsRuntime 1.4";
+        let version_len = align(version.len() as u32 + 1, 4);
+
+        let headers_len: u32 = streams
+            .iter()
+            .map(|(name, _)| 8 + align(name.len() as u32 + 1, 4))
+            .sum();
+
+        let mut buffer = Vec::new();
+        put_u32(&mut buffer, 0x424A_5342); // signature
+        put_u16(&mut buffer, 1); // major version
+        put_u16(&mut buffer, 1); // minor version
+        put_u32(&mut buffer, 0); // reserved
+        put_u32(&mut buffer, version_len);
+        buffer.extend_from_slice(version);
+        buffer.resize(16 + version_len as usize, 0);
+        put_u16(&mut buffer, 0); // flags
+        put_u16(&mut buffer, streams.len() as u16);
+
+        let mut offset = buffer.len() as u32 + headers_len;
+
+        for (name, bytes) in &streams {
+            let size = align(bytes.len() as u32, 4);
+            put_u32(&mut buffer, offset);
+            put_u32(&mut buffer, size);
+            buffer.extend_from_slice(name);
+            buffer.resize(
+                buffer.len() + (align(name.len() as u32 + 1, 4) as usize - name.len()),
+                0,
+            );
+            offset += size;
+        }
+
+        for (_, bytes) in &streams {
+            buffer.extend_from_slice(bytes);
+            buffer.resize(align(buffer.len() as u32, 4) as usize, 0);
+        }
+
+        buffer
+    }
+
+    /// Encodes a reference to a type as a `TypeDefOrRef` coded index
+    fn type_def_or_ref(&mut self, sig: &TypeSig) -> u32 {
+        match sig {
+            TypeSig::Class(name) | TypeSig::ValueType(name) => match self.type_defs.get(name) {
+                Some(row) => coded(TYPE_DEF_OR_REF, TYPE_DEF, *row),
+                None => {
+                    let row = self.type_ref(name);
+                    coded(TYPE_DEF_OR_REF, TYPE_REF, row)
+                }
+            },
+            _ => {
+                let mut blob = Vec::new();
+                self.type_sig(sig, &mut blob);
+
+                let row = match self.type_spec_rows.get(&blob) {
+                    Some(row) => *row,
+                    None => {
+                        let index = self.blobs.insert(&blob);
+                        let row = self.type_spec.push(vec![index]);
+                        self.type_spec_rows.insert(blob, row);
+                        row
+                    }
+                };
+
+                coded(TYPE_DEF_OR_REF, TYPE_SPEC, row)
+            }
+        }
+    }
+
+    /// Gets the row of the `TypeRef` for a type that isn't defined in this file
+    fn type_ref(&mut self, name: &TypeName) -> u32 {
+        if let Some(row) = self.type_ref_rows.get(name) {
+            return *row;
+        }
+
+        let scope = self.assembly_ref(&name.namespace);
+        let type_name = self.strings.insert(&name.name);
+        let namespace = self.strings.insert(&name.namespace);
+        let row = self.type_ref.push(vec![
+            coded(RESOLUTION_SCOPE, ASSEMBLY_REF, scope),
+            type_name,
+            namespace,
+        ]);
+
+        self.type_ref_rows.insert(name.clone(), row);
+        row
+    }
+
+    /// Gets the row of the `AssemblyRef` for the assembly that is assumed to define a namespace:
+    /// `mscorlib` for `System` namespaces and otherwise an assembly named after the root namespace
+    fn assembly_ref(&mut self, namespace: &str) -> u32 {
+        let name = if namespace == "System" || namespace.starts_with("System.") {
+            "mscorlib"
+        } else {
+            namespace.split('.').next().unwrap()
+        };
+
+        if let Some(row) = self.assembly_ref_rows.get(name) {
+            return *row;
+        }
+
+        let row = if name == "mscorlib" {
+            let token = self
+                .blobs
+                .insert(&[0xB7, 0x7A, 0x5C, 0x56, 0x19, 0x34, 0xE0, 0x89]);
+            let name = self.strings.insert(name);
+            self.assembly_ref
+                .push(vec![4, 0, 0, 0, 0, token, name, 0, 0])
+        } else {
+            let name = self.strings.insert(name);
+            self.assembly_ref
+                .push(vec![255, 255, 255, 255, 0x200, 0, name, 0, 0])
+        };
+
+        self.assembly_ref_rows.insert(name.to_string(), row);
+        row
+    }
+
+    fn type_sig(&mut self, sig: &TypeSig, blob: &mut Vec<u8>) {
+        match sig {
+            TypeSig::Void => blob.push(0x01),
+            TypeSig::Bool => blob.push(0x02),
+            TypeSig::Char => blob.push(0x03),
+            TypeSig::I8 => blob.push(0x04),
+            TypeSig::U8 => blob.push(0x05),
+            TypeSig::I16 => blob.push(0x06),
+            TypeSig::U16 => blob.push(0x07),
+            TypeSig::I32 => blob.push(0x08),
+            TypeSig::U32 => blob.push(0x09),
+            TypeSig::I64 => blob.push(0x0A),
+            TypeSig::U64 => blob.push(0x0B),
+            TypeSig::F32 => blob.push(0x0C),
+            TypeSig::F64 => blob.push(0x0D),
+            TypeSig::String => blob.push(0x0E),
+            TypeSig::Pointer(sig) => {
+                blob.push(0x0F);
+                self.type_sig(sig, blob);
+            }
+            TypeSig::ByRef(sig) => {
+                blob.push(0x10);
+                self.type_sig(sig, blob);
+            }
+            TypeSig::ValueType(_) => {
+                blob.push(0x11);
+                self.type_def_or_ref_encoded(sig, blob);
+            }
+            TypeSig::Class(_) => {
+                blob.push(0x12);
+                self.type_def_or_ref_encoded(sig, blob);
+            }
+            TypeSig::GenericParam(number) => {
+                blob.push(0x13);
+                write_compressed(blob, *number);
+            }
+            TypeSig::GenericInstance(name, args) => {
+                blob.push(0x15);
+                blob.push(0x12);
+                self.type_def_or_ref_encoded(&TypeSig::Class(name.clone()), blob);
+                write_compressed(blob, args.len() as u32);
+
+                for arg in args {
+                    self.type_sig(arg, blob);
+                }
+            }
+            TypeSig::ISize => blob.push(0x18),
+            TypeSig::USize => blob.push(0x19),
+            TypeSig::Object => blob.push(0x1C),
+            TypeSig::Array(sig) => {
+                blob.push(0x1D);
+                self.type_sig(sig, blob);
+            }
+            TypeSig::FixedArray(sig, sizes) => {
+                blob.push(0x14);
+                self.type_sig(sig, blob);
+                write_compressed(blob, sizes.len() as u32);
+                write_compressed(blob, sizes.len() as u32);
+
+                for size in sizes {
+                    write_compressed(blob, *size);
+                }
+
+                // No lower bounds
+                write_compressed(blob, 0);
+            }
+        }
+    }
+
+    /// Writes a `TypeDefOrRefOrSpecEncoded` value as used within signatures
+    fn type_def_or_ref_encoded(&mut self, sig: &TypeSig, blob: &mut Vec<u8>) {
+        let value = self.type_def_or_ref(sig);
+        write_compressed(blob, value);
+    }
+
+    /// Gets the `CustomAttributeType` coded index of an attribute's constructor and the
+    /// attribute's value blob
+    fn attribute(&mut self, attribute: &AttributeDefinition) -> (u32, Vec<u8>) {
+        let mut sig = vec![0x20];
+        write_compressed(&mut sig, attribute.args.len() as u32);
+        sig.push(0x01);
+
+        for arg in &attribute.args {
+            let arg_sig = match arg {
+                AttributeValue::Type(_) => TypeSig::Class(TypeName::new("System", "Type")),
+                AttributeValue::Enum(name, _) => TypeSig::ValueType(name.clone()),
+                _ => value_sig(arg),
+            };
+
+            self.type_sig(&arg_sig, &mut sig);
+        }
+
+        let parent = match self.type_defs.get(&attribute.name) {
+            Some(row) => coded(MEMBER_REF_PARENT, TYPE_DEF, *row),
+            None => {
+                let row = self.type_ref(&attribute.name);
+                coded(MEMBER_REF_PARENT, TYPE_REF, row)
+            }
+        };
+
+        let constructor = match self.member_ref_rows.get(&(parent, sig.clone())) {
+            Some(row) => *row,
+            None => {
+                let name = self.strings.insert(".ctor");
+                let index = self.blobs.insert(&sig);
+                let row = self.member_ref.push(vec![parent, name, index]);
+                self.member_ref_rows.insert((parent, sig), row);
+                row
+            }
+        };
+
+        let mut value = vec![0x01, 0x00];
+
+        for arg in &attribute.args {
+            write_value(&mut value, arg);
+        }
+
+        value.extend_from_slice(&(attribute.named_args.len() as u16).to_le_bytes());
+
+        for (name, arg) in &attribute.named_args {
+            value.push(0x54);
+            write_field_or_prop_type(&mut value, arg);
+            write_ser_string(&mut value, name);
+            write_value(&mut value, arg);
+        }
+
+        (coded(CUSTOM_ATTRIBUTE_TYPE, MEMBER_REF, constructor), value)
+    }
+}
+
+/// The signature of a primitive attribute value
+fn value_sig(value: &AttributeValue) -> TypeSig {
+    match value {
+        AttributeValue::Bool(_) => TypeSig::Bool,
+        AttributeValue::Char(_) => TypeSig::Char,
+        AttributeValue::I8(_) => TypeSig::I8,
+        AttributeValue::U8(_) => TypeSig::U8,
+        AttributeValue::I16(_) => TypeSig::I16,
+        AttributeValue::U16(_) => TypeSig::U16,
+        AttributeValue::I32(_) => TypeSig::I32,
+        AttributeValue::U32(_) => TypeSig::U32,
+        AttributeValue::I64(_) => TypeSig::I64,
+        AttributeValue::U64(_) => TypeSig::U64,
+        AttributeValue::F32(_) => TypeSig::F32,
+        AttributeValue::F64(_) => TypeSig::F64,
+        AttributeValue::String(_) => TypeSig::String,
+        AttributeValue::Type(_) | AttributeValue::Enum(..) => {
+            panic!("Attribute value is not a primitive")
+        }
+    }
+}
+
+/// Writes the `FieldOrPropType` of a named attribute argument
+fn write_field_or_prop_type(buffer: &mut Vec<u8>, value: &AttributeValue) {
+    match value {
+        AttributeValue::Type(_) => buffer.push(0x50),
+        AttributeValue::Enum(name, _) => {
+            buffer.push(0x55);
+            write_ser_string(buffer, &format!("{}.{}", name.namespace, name.name));
+        }
+        _ => buffer.push(match value_sig(value) {
+            TypeSig::Bool => 0x02,
+            TypeSig::Char => 0x03,
+            TypeSig::I8 => 0x04,
+            TypeSig::U8 => 0x05,
+            TypeSig::I16 => 0x06,
+            TypeSig::U16 => 0x07,
+            TypeSig::I32 => 0x08,
+            TypeSig::U32 => 0x09,
+            TypeSig::I64 => 0x0A,
+            TypeSig::U64 => 0x0B,
+            TypeSig::F32 => 0x0C,
+            TypeSig::F64 => 0x0D,
+            _ => 0x0E,
+        }),
+    }
+}
+
+fn write_value(buffer: &mut Vec<u8>, value: &AttributeValue) {
+    match value {
+        AttributeValue::Bool(value) => buffer.push(*value as u8),
+        AttributeValue::Char(value) => {
+            let mut units = [0; 2];
+            buffer.extend_from_slice(&value.encode_utf16(&mut units)[0].to_le_bytes())
+        }
+        AttributeValue::I8(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+        AttributeValue::U8(value) => buffer.push(*value),
+        AttributeValue::I16(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+        AttributeValue::U16(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+        AttributeValue::I32(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+        AttributeValue::U32(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+        AttributeValue::I64(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+        AttributeValue::U64(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+        AttributeValue::F32(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+        AttributeValue::F64(value) => buffer.extend_from_slice(&value.to_le_bytes()),
+        AttributeValue::String(value) => write_ser_string(buffer, value),
+        AttributeValue::Type(name) => {
+            write_ser_string(buffer, &format!("{}.{}", name.namespace, name.name))
+        }
+        AttributeValue::Enum(_, value) => write_value(buffer, value),
+    }
+}
+
+/// The element type and value blob of a constant
+fn constant_blob(value: &ConstantValue) -> (u32, Vec<u8>) {
+    match value {
+        ConstantValue::U8(value) => (0x05, value.to_le_bytes().to_vec()),
+        ConstantValue::I8(value) => (0x04, value.to_le_bytes().to_vec()),
+        ConstantValue::U16(value) => (0x07, value.to_le_bytes().to_vec()),
+        ConstantValue::I16(value) => (0x06, value.to_le_bytes().to_vec()),
+        ConstantValue::U32(value) => (0x09, value.to_le_bytes().to_vec()),
+        ConstantValue::I32(value) => (0x08, value.to_le_bytes().to_vec()),
+        ConstantValue::U64(value) => (0x0B, value.to_le_bytes().to_vec()),
+        ConstantValue::I64(value) => (0x0A, value.to_le_bytes().to_vec()),
+        ConstantValue::F32(value) => (0x0C, value.to_le_bytes().to_vec()),
+        ConstantValue::F64(value) => (0x0D, value.to_le_bytes().to_vec()),
+        ConstantValue::String(value) => (
+            0x0E,
+            value
+                .encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes().to_vec())
+                .collect(),
+        ),
+    }
+}
+
+/// Derives a module version identifier from the metadata so that writing the same
+/// metadata always produces the same file
+fn derive_mvid(bytes: &[u8]) -> Guid {
+    let mut guid = Guid::default();
+
+    for (index, chunk) in guid.0.chunks_mut(8).enumerate() {
+        let hash = fnv1a(fnv1a(FNV_OFFSET_BASIS, &[index as u8]), bytes);
+        chunk.copy_from_slice(&hash.to_le_bytes());
+    }
+
+    // Mark the GUID as a version 4 variant 1 GUID as it isn't based on a timestamp.
+    guid.0[7] = (guid.0[7] & 0x0F) | 0x40;
+    guid.0[8] = (guid.0[8] & 0x3F) | 0x80;
+    guid
+}
+
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+
+/// Continues a 64-bit FNV-1a hash, which unlike the standard library's hashers is guaranteed
+/// to produce the same value with every version of Rust
+pub(crate) fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::winmd::{AttributeArg, TypeDefOrRef, TypeReader};
+
+    fn attribute(namespace: &str, name: &str, args: Vec<AttributeValue>) -> AttributeDefinition {
+        AttributeDefinition {
+            name: TypeName::new(namespace, name),
+            args,
+            named_args: Vec::new(),
+        }
+    }
+
+    fn metadata() -> Metadata {
+        let color = TypeDefinition {
+            namespace: "Test".to_string(),
+            name: "Color".to_string(),
+            flags: 0x4101,
+            extends: Some(TypeSig::Class(TypeName::new("System", "Enum"))),
+            fields: vec![
+                FieldDefinition {
+                    name: "value__".to_string(),
+                    flags: 0x0601,
+                    sig: TypeSig::I32,
+                    constant: None,
+                    attributes: Vec::new(),
+                },
+                FieldDefinition {
+                    name: "Red".to_string(),
+                    flags: 0x8056,
+                    sig: TypeSig::ValueType(TypeName::new("Test", "Color")),
+                    constant: Some(ConstantValue::I32(7)),
+                    attributes: Vec::new(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let interface = TypeDefinition {
+            namespace: "Test".to_string(),
+            name: "IThing".to_string(),
+            flags: 0x40A1,
+            generics: vec!["T".to_string()],
+            methods: vec![MethodDefinition {
+                name: "Get".to_string(),
+                flags: 0x05C6,
+                impl_flags: 0,
+                return_type: TypeSig::GenericParam(0),
+                params: vec![ParamDefinition {
+                    name: "index".to_string(),
+                    flags: 1,
+                    sig: TypeSig::U32,
+                }],
+                attributes: Vec::new(),
+            }],
+            attributes: vec![attribute(
+                "Windows.Foundation.Metadata",
+                "GuidAttribute",
+                vec![
+                    AttributeValue::U32(0x1234_5678),
+                    AttributeValue::U16(1),
+                    AttributeValue::U16(2),
+                    AttributeValue::U8(3),
+                    AttributeValue::U8(4),
+                    AttributeValue::U8(5),
+                    AttributeValue::U8(6),
+                    AttributeValue::U8(7),
+                    AttributeValue::U8(8),
+                    AttributeValue::U8(9),
+                    AttributeValue::U8(10),
+                ],
+            )],
+            ..Default::default()
+        };
+
+        let mut custom = attribute(
+            "Test",
+            "CustomAttribute",
+            vec![
+                AttributeValue::String("Hello".to_string()),
+                AttributeValue::Enum(
+                    TypeName::new("Test", "Color"),
+                    Box::new(AttributeValue::I32(7)),
+                ),
+            ],
+        );
+        custom.named_args = vec![
+            ("Flag".to_string(), AttributeValue::Bool(true)),
+            (
+                "Kind".to_string(),
+                AttributeValue::Type(TypeName::new("Test", "IThing")),
+            ),
+        ];
+
+        let class = TypeDefinition {
+            namespace: "Test".to_string(),
+            name: "Thing".to_string(),
+            flags: 0x4101,
+            extends: Some(TypeSig::Class(TypeName::new("System", "Object"))),
+            interfaces: vec![TypeSig::GenericInstance(
+                TypeName::new("Test", "IThing"),
+                vec![TypeSig::String],
+            )
+            .into()],
+            attributes: vec![custom],
+            ..Default::default()
+        };
+
+        Metadata {
+            name: "Test".to_string(),
+            types: vec![color, interface, class],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fixed_arrays() {
+        let field = |name: &str, element: TypeSig, sizes: &[u32]| FieldDefinition {
+            name: name.to_string(),
+            flags: 0x0006,
+            sig: TypeSig::FixedArray(Box::new(element), sizes.to_vec()),
+            constant: None,
+            attributes: Vec::new(),
+        };
+
+        let metadata = Metadata {
+            name: "Test".to_string(),
+            types: vec![TypeDefinition {
+                namespace: "Test".to_string(),
+                name: "Arrays".to_string(),
+                flags: 0x0109,
+                extends: Some(TypeSig::Class(TypeName::new("System", "ValueType"))),
+                fields: vec![
+                    field("Matrix", TypeSig::F32, &[4, 4]),
+                    field("Data", TypeSig::U8, &[2, 4096]),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let reader = TypeReader::from_bytes(vec![metadata.write()]).unwrap();
+        let reader: &'static TypeReader = Box::leak(Box::new(reader));
+        let def = reader.expect_type_def(("Test", "Arrays"));
+
+        let mut blob = def.fields().next().unwrap().sig();
+        blob.read_unsigned();
+        assert!(blob.read_expected(0x14));
+        assert!(blob.read_expected(0x0C));
+        let shape = blob.read_array_shape();
+        assert_eq!(shape.rank, 2);
+        assert_eq!(shape.sizes, [4, 4]);
+        assert_eq!(shape.element_count(), Some(16));
+
+        let fields: Vec<_> = def
+            .fields()
+            .map(|field| crate::Type::from_field(&def, &field, "Test").gen_field())
+            .map(|tokens| tokens.as_str().to_string())
+            .collect();
+        assert_eq!(fields, ["[ f32 ;16 ]", "[ u8 ;8192 ]"]);
+    }
+
+    #[test]
+    fn fnv() {
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b""), 0xCBF2_9CE4_8422_2325);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"a"), 0xAF63_DC4C_8601_EC8C);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"foobar"), 0x8594_4171_F739_67E8);
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = metadata().write();
+        assert_eq!(bytes, metadata().write());
+
+        let reader = TypeReader::from_bytes(vec![bytes]).unwrap();
+        let reader: &'static TypeReader = Box::leak(Box::new(reader));
+
+        let mvid = reader.files[0].mvid().unwrap();
+        assert!(!mvid.is_zero());
+
+        let color = reader.expect_type_def(("Test", "Color"));
+        let fields: Vec<_> = color.fields().map(|field| field.name()).collect();
+        assert_eq!(fields, ["value__", "Red"]);
+        let red = color.fields().nth(1).unwrap();
+        assert!(matches!(
+            red.constant().unwrap().value(),
+            ConstantValue::I32(7)
+        ));
+
+        let interface = reader.expect_type_def(("Test", "IThing"));
+        let generics: Vec<_> = interface.generics().map(|param| param.name()).collect();
+        assert_eq!(generics, ["T"]);
+        let method = interface.methods().next().unwrap();
+        assert_eq!(method.name(), "Get");
+        let params: Vec<_> = method.params().map(|param| param.name()).collect();
+        assert_eq!(params, ["index"]);
+
+        let guid = interface.attributes().next().unwrap();
+        assert_eq!(
+            guid.name(),
+            ("Windows.Foundation.Metadata", "GuidAttribute")
+        );
+        let args = guid.args();
+        assert_eq!(args.len(), 11);
+        assert!(matches!(args[0].1, AttributeArg::U32(0x1234_5678)));
+
+        let class = reader.expect_type_def(("Test", "Thing"));
+        let interface = class.interfaces().next().unwrap().interface();
+        assert!(matches!(interface, TypeDefOrRef::TypeSpec(_)));
+
+        let custom = class.attributes().next().unwrap();
+        assert_eq!(custom.name(), ("Test", "CustomAttribute"));
+        let args = custom.args();
+        assert_eq!(args.len(), 4);
+        assert!(matches!(&args[0].1, AttributeArg::String(value) if value == "Hello"));
+        assert!(
+            matches!(&args[1].1, AttributeArg::Enum(def, _) if def.name() == ("Test", "Color"))
+        );
+        assert!(matches!(args[2], (ref name, AttributeArg::Bool(true)) if name == "Flag"));
+        assert!(
+            matches!(&args[3].1, AttributeArg::TypeDef(def) if def.name() == ("Test", "IThing"))
+        );
+    }
+}
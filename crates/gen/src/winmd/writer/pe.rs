@@ -0,0 +1,113 @@
+/// The relative virtual address of the only section in the image
+const SECTION_RVA: u32 = 0x2000;
+const SECTION_ALIGNMENT: u32 = 0x2000;
+const FILE_ALIGNMENT: u32 = 0x200;
+const PE_OFFSET: u32 = 0x80;
+const CLI_HEADER_SIZE: u32 = 72;
+
+/// Wraps the metadata in a minimal PE32 image containing a single `.text` section that
+/// holds the CLI header followed by the metadata itself
+pub(crate) fn write(metadata: &[u8]) -> Vec<u8> {
+    let section_size = CLI_HEADER_SIZE + metadata.len() as u32;
+    let raw_size = align(section_size, FILE_ALIGNMENT);
+    let image_size = SECTION_RVA + align(section_size, SECTION_ALIGNMENT);
+
+    let mut buffer = Vec::with_capacity((FILE_ALIGNMENT + raw_size) as usize);
+
+    // DOS header
+    buffer.extend_from_slice(b"MZ");
+    buffer.resize(0x3C, 0);
+    buffer.extend_from_slice(&PE_OFFSET.to_le_bytes());
+    buffer.resize(PE_OFFSET as usize, 0);
+
+    // PE signature and file header
+    buffer.extend_from_slice(b"PE\0\0");
+    put_u16(&mut buffer, 0x14C); // machine: i386
+    put_u16(&mut buffer, 1); // number of sections
+    put_u32(&mut buffer, 0); // time date stamp
+    put_u32(&mut buffer, 0); // pointer to symbol table
+    put_u32(&mut buffer, 0); // number of symbols
+    put_u16(&mut buffer, 224); // size of optional header
+    put_u16(&mut buffer, 0x2102); // characteristics: executable, 32-bit, DLL
+
+    // PE32 optional header
+    put_u16(&mut buffer, 0x10B); // magic
+    buffer.extend_from_slice(&[11, 0]); // linker version
+    put_u32(&mut buffer, raw_size); // size of code
+    put_u32(&mut buffer, 0); // size of initialized data
+    put_u32(&mut buffer, 0); // size of uninitialized data
+    put_u32(&mut buffer, 0); // address of entry point
+    put_u32(&mut buffer, SECTION_RVA); // base of code
+    put_u32(&mut buffer, 0); // base of data
+    put_u32(&mut buffer, 0x40_0000); // image base
+    put_u32(&mut buffer, SECTION_ALIGNMENT);
+    put_u32(&mut buffer, FILE_ALIGNMENT);
+    put_u16(&mut buffer, 4); // operating system version
+    put_u16(&mut buffer, 0);
+    put_u16(&mut buffer, 0); // image version
+    put_u16(&mut buffer, 0);
+    put_u16(&mut buffer, 4); // subsystem version
+    put_u16(&mut buffer, 0);
+    put_u32(&mut buffer, 0); // win32 version value
+    put_u32(&mut buffer, image_size);
+    put_u32(&mut buffer, FILE_ALIGNMENT); // size of headers
+    put_u32(&mut buffer, 0); // checksum
+    put_u16(&mut buffer, 3); // subsystem: console
+    put_u16(&mut buffer, 0x8540); // DLL characteristics: dynamic base, NX compatible, no SEH, terminal server aware
+    put_u32(&mut buffer, 0x10_0000); // size of stack reserve
+    put_u32(&mut buffer, 0x1000); // size of stack commit
+    put_u32(&mut buffer, 0x10_0000); // size of heap reserve
+    put_u32(&mut buffer, 0x1000); // size of heap commit
+    put_u32(&mut buffer, 0); // loader flags
+    put_u32(&mut buffer, 16); // number of data directories
+
+    for index in 0..16 {
+        if index == 14 {
+            // The COM descriptor, which is the CLI header
+            put_u32(&mut buffer, SECTION_RVA);
+            put_u32(&mut buffer, CLI_HEADER_SIZE);
+        } else {
+            put_u32(&mut buffer, 0);
+            put_u32(&mut buffer, 0);
+        }
+    }
+
+    // Section table
+    buffer.extend_from_slice(b".text\0\0\0");
+    put_u32(&mut buffer, section_size); // virtual size
+    put_u32(&mut buffer, SECTION_RVA); // virtual address
+    put_u32(&mut buffer, raw_size); // size of raw data
+    put_u32(&mut buffer, FILE_ALIGNMENT); // pointer to raw data
+    put_u32(&mut buffer, 0); // pointer to relocations
+    put_u32(&mut buffer, 0); // pointer to line numbers
+    put_u16(&mut buffer, 0); // number of relocations
+    put_u16(&mut buffer, 0); // number of line numbers
+    put_u32(&mut buffer, 0x6000_0020); // characteristics: code, execute, read
+
+    buffer.resize(FILE_ALIGNMENT as usize, 0);
+
+    // CLI header
+    put_u32(&mut buffer, CLI_HEADER_SIZE);
+    put_u16(&mut buffer, 2); // runtime version
+    put_u16(&mut buffer, 5);
+    put_u32(&mut buffer, SECTION_RVA + CLI_HEADER_SIZE); // metadata
+    put_u32(&mut buffer, metadata.len() as u32);
+    put_u32(&mut buffer, 1); // flags: IL only
+    buffer.resize((FILE_ALIGNMENT + CLI_HEADER_SIZE) as usize, 0);
+
+    buffer.extend_from_slice(metadata);
+    buffer.resize((FILE_ALIGNMENT + raw_size) as usize, 0);
+    buffer
+}
+
+pub(crate) fn put_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn align(value: u32, alignment: u32) -> u32 {
+    (value + alignment - 1) & !(alignment - 1)
+}
@@ -0,0 +1,211 @@
+/// The physical table numbers of ECMA-335 II.22
+pub(crate) const MODULE: usize = 0x00;
+pub(crate) const TYPE_REF: usize = 0x01;
+pub(crate) const TYPE_DEF: usize = 0x02;
+pub(crate) const FIELD: usize = 0x04;
+pub(crate) const METHOD_DEF: usize = 0x06;
+pub(crate) const PARAM: usize = 0x08;
+pub(crate) const INTERFACE_IMPL: usize = 0x09;
+pub(crate) const MEMBER_REF: usize = 0x0A;
+pub(crate) const CONSTANT: usize = 0x0B;
+pub(crate) const CUSTOM_ATTRIBUTE: usize = 0x0C;
+pub(crate) const EVENT_MAP: usize = 0x12;
+pub(crate) const EVENT: usize = 0x14;
+pub(crate) const PROPERTY_MAP: usize = 0x15;
+pub(crate) const PROPERTY: usize = 0x17;
+pub(crate) const METHOD_SEMANTICS: usize = 0x18;
+pub(crate) const TYPE_SPEC: usize = 0x1B;
+pub(crate) const ASSEMBLY: usize = 0x20;
+pub(crate) const ASSEMBLY_REF: usize = 0x23;
+pub(crate) const GENERIC_PARAM: usize = 0x2A;
+
+/// A placeholder for the unused tags of a coded index
+const NONE: usize = 0x3F;
+
+pub(crate) const TYPE_DEF_OR_REF: &[usize] = &[TYPE_DEF, TYPE_REF, TYPE_SPEC];
+pub(crate) const HAS_CONSTANT: &[usize] = &[FIELD, PARAM, PROPERTY];
+pub(crate) const HAS_CUSTOM_ATTRIBUTE: &[usize] = &[
+    METHOD_DEF,
+    FIELD,
+    TYPE_REF,
+    TYPE_DEF,
+    PARAM,
+    INTERFACE_IMPL,
+    MEMBER_REF,
+    MODULE,
+    0x0E,
+    PROPERTY,
+    EVENT,
+    0x11,
+    0x1A,
+    TYPE_SPEC,
+    ASSEMBLY,
+    ASSEMBLY_REF,
+    0x26,
+    0x27,
+    0x28,
+    GENERIC_PARAM,
+    0x2C,
+    0x2B,
+];
+pub(crate) const MEMBER_REF_PARENT: &[usize] = &[TYPE_DEF, TYPE_REF, 0x1A, METHOD_DEF, TYPE_SPEC];
+pub(crate) const CUSTOM_ATTRIBUTE_TYPE: &[usize] = &[NONE, NONE, METHOD_DEF, MEMBER_REF, NONE];
+pub(crate) const RESOLUTION_SCOPE: &[usize] = &[MODULE, 0x1A, ASSEMBLY_REF, TYPE_REF];
+pub(crate) const TYPE_OR_METHOD_DEF: &[usize] = &[TYPE_DEF, METHOD_DEF];
+pub(crate) const HAS_SEMANTICS: &[usize] = &[EVENT, PROPERTY];
+
+/// The kind of value stored in a column of a table
+#[derive(Copy, Clone)]
+pub(crate) enum Column {
+    U16,
+    U32,
+    String,
+    Guid,
+    Blob,
+    /// A 1-based index into another table
+    Index(usize),
+    /// A coded index into one of the given tables
+    Coded(&'static [usize]),
+}
+
+/// The rows of a single table, each holding one value per column
+pub(crate) struct Table {
+    pub number: usize,
+    pub columns: &'static [Column],
+    pub rows: Vec<Vec<u32>>,
+}
+
+impl Table {
+    pub fn new(number: usize, columns: &'static [Column]) -> Self {
+        Self {
+            number,
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds a row and returns its 1-based index
+    pub fn push(&mut self, row: Vec<u32>) -> u32 {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+        self.rows.len() as u32
+    }
+
+    pub fn len(&self) -> u32 {
+        self.rows.len() as u32
+    }
+}
+
+/// Encodes a coded index given the position of its table within the coded index's tables
+pub(crate) fn coded(tables: &[usize], table: usize, row: u32) -> u32 {
+    let tag = tables.iter().position(|t| *t == table).unwrap() as u32;
+    (row << bits_needed(tables.len())) | tag
+}
+
+fn bits_needed(count: usize) -> u32 {
+    let mut value = count - 1;
+    let mut bits = 1;
+
+    while value > 1 {
+        value >>= 1;
+        bits += 1;
+    }
+
+    bits
+}
+
+/// Writes the `#~` stream containing the given tables, which must be sorted by table number
+pub(crate) fn write_tables(tables: &[&Table], heaps: (usize, usize, usize)) -> Vec<u8> {
+    let (strings, guids, blobs) = heaps;
+
+    let row_count = |number: usize| -> u32 {
+        tables
+            .iter()
+            .find(|table| table.number == number)
+            .map_or(0, |table| table.len())
+    };
+
+    let heap_size = |len: usize| if len < 0x10000 { 2 } else { 4 };
+
+    let column_size = |column: &Column| -> usize {
+        match column {
+            Column::U16 => 2,
+            Column::U32 => 4,
+            Column::String => heap_size(strings),
+            Column::Guid => heap_size(guids / 16 + 1),
+            Column::Blob => heap_size(blobs),
+            Column::Index(number) => heap_size(row_count(*number) as usize),
+            Column::Coded(coded) => {
+                let limit = 1u32 << (16 - bits_needed(coded.len()));
+
+                if coded.iter().all(|number| row_count(*number) < limit) {
+                    2
+                } else {
+                    4
+                }
+            }
+        }
+    };
+
+    let mut heap_sizes = 0u8;
+
+    if strings >= 0x10000 {
+        heap_sizes |= 1;
+    }
+    if guids / 16 + 1 >= 0x10000 {
+        heap_sizes |= 2;
+    }
+    if blobs >= 0x10000 {
+        heap_sizes |= 4;
+    }
+
+    let present: Vec<&&Table> = tables.iter().filter(|table| table.len() > 0).collect();
+    let valid = present
+        .iter()
+        .fold(0u64, |valid, table| valid | 1 << table.number);
+
+    // The tables that are required to be sorted, whether or not they are present.
+    let sorted = [
+        INTERFACE_IMPL,
+        CONSTANT,
+        CUSTOM_ATTRIBUTE,
+        0x0E,
+        0x0F,
+        0x10,
+        0x18,
+        0x19,
+        0x1C,
+        0x1D,
+        0x29,
+        GENERIC_PARAM,
+        0x2C,
+    ]
+    .iter()
+    .fold(0u64, |sorted, number| sorted | 1 << number);
+
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(&0u32.to_le_bytes());
+    buffer.push(2);
+    buffer.push(0);
+    buffer.push(heap_sizes);
+    buffer.push(1);
+    buffer.extend_from_slice(&valid.to_le_bytes());
+    buffer.extend_from_slice(&sorted.to_le_bytes());
+
+    for table in &present {
+        buffer.extend_from_slice(&table.len().to_le_bytes());
+    }
+
+    for table in &present {
+        for row in &table.rows {
+            for (value, column) in row.iter().zip(table.columns) {
+                match column_size(column) {
+                    2 => buffer.extend_from_slice(&(*value as u16).to_le_bytes()),
+                    _ => buffer.extend_from_slice(&value.to_le_bytes()),
+                }
+            }
+        }
+    }
+
+    buffer
+}
@@ -8,7 +8,8 @@ struct Implements(Vec<gen::TypeDefinition>);
 impl syn::parse::Parse for Implements {
     fn parse(inner_type: syn::parse::ParseStream) -> syn::parse::Result<Self> {
         let mut types = Vec::new();
-        let reader = winmd::TypeReader::get();
+        let reader = winmd::TypeReader::try_get()
+            .map_err(|error| syn::Error::new(inner_type.span(), error))?;
 
         loop {
             use_tree_to_types(reader, &inner_type.parse::<ImplementTree>()?, &mut types)?;
@@ -51,7 +52,7 @@ fn use_tree_to_types(
                 }
             }
             ImplementTree::Name(name) => {
-                let namespace = crate::namespace_literal_to_rough_namespace(&current.clone());
+                let namespace = gen::namespace_literal_to_rough_namespace(&current.clone());
                 let namespace = reader.find_lowercase_namespace(&namespace).unwrap(); // TODO: handle
 
                 let mut meta_name = name.ident.to_string();
@@ -1,9 +1,7 @@
-mod build_limits;
 mod implement;
 mod implement_tree;
 
-use build_limits::*;
-use gen::winmd;
+use gen::{winmd, BuildLimits};
 use implement_tree::*;
 use proc_macro::TokenStream;
 use quote::quote;
@@ -32,40 +30,176 @@ use syn::parse_macro_input;
 ///     microsoft::ai::machine_learning::*
 /// );
 /// ```
+///
+/// # Types without a namespace
+/// Types, functions and constants may also be given by name alone, in which case the namespace
+/// that defines the name is found in the metadata. Names defined by more than one namespace
+/// must be given with a path instead.
+///
+/// ```rust,ignore
+/// build!(CreateEventW, MessageBoxW);
+/// ```
+///
+/// # Patterns, exclusions and renames
+/// Groups may be nested, and the names in a path may be patterns in which `*` matches any
+/// sequence of characters. Types preceded by `!` aren't generated unless other types depend on
+/// them, and types followed by `as` are also generated with the given alias. Exclusions must be
+/// given along with the types they limit, and the types of `Windows.Foundation`, which are used
+/// from the `windows` crate, can't be renamed.
+///
+/// ```rust,ignore
+/// build!(
+///     windows::win32::{
+///         system::threading::{Create*W, SetEvent},
+///         ui::windows_and_messaging::MessageBoxW as message_box,
+///     },
+///     windows::ui::{*, !Colors},
+/// );
+/// ```
+///
+/// # Versions
+/// APIs that are newer than the oldest release of Windows an application supports can be
+/// excluded by giving the minimum version of the contracts the APIs belong to, or the minimum
+/// OS version for APIs that predate Windows 10 contracts. Classes then only include the
+/// interfaces that were available in those versions.
+///
+/// ```rust,ignore
+/// build!(
+///     minimum_contract(Windows.Foundation.UniversalApiContract, 7),
+///     minimum_os_version(6.3),
+///     windows::ui::Colors
+/// );
+/// ```
+///
+/// APIs marked as deprecated in the metadata are marked as `#[deprecated]` in Rust.
+///
+/// # Manifests
+/// The types and options may also be listed in a manifest such as `windows.toml`, which is
+/// read with `manifest("...")`, relative to the crate's directory, and may be shared with the
+/// `windows-bindgen` tool. Manifests also list the types to exclude and the types to generate
+/// with an alias. See `windows_gen::Manifest` for the format.
+///
+/// ```rust,ignore
+/// build!(manifest("windows.toml"));
+/// ```
+///
+/// # Metadata and DLLs
+/// Types are read from the metadata (winmd) files in the workspace's `.windows/winmd`
+/// directory, or from the default Windows metadata if there are none. The workspace is the
+/// Cargo workspace of the crate unless the `WINDOWS_WORKSPACE_DIR` environment variable names
+/// another directory. Further metadata files, or directories of them, are read from:
+///
+/// * `winmd_path("...")` arguments, relative to the crate's directory
+/// * the `WINDOWS_WINMD_PATH` environment variable, a list of paths separated like `PATH`
+///
+/// The build script is rerun when any of the metadata files or manifests that were read change.
+/// The bindings are generated when the build script is compiled, though, so it then warns that
+/// the package must be cleaned for the bindings to be regenerated.
+///
+/// DLLs for the target architecture are copied next to the crate's binaries from the `x64`,
+/// `x86`, `arm` or `arm64` subdirectory of the workspace's `.windows` directory, and of
+/// directories laid out the same way that are given as `dll_path("...")` arguments, listed in
+/// the `WINDOWS_DLL_PATH` environment variable or in the `DEP_<links>_DLL` variables of
+/// dependencies.
+///
+/// ```rust,ignore
+/// build!(
+///     winmd_path("metadata/Contoso.Widgets.winmd"),
+///     dll_path("bin"),
+///     contoso::widgets::Widget
+/// );
+/// ```
+///
+/// A crate packages DLLs for its dependents by declaring a `links` key in its Cargo.toml and
+/// printing `cargo:dll=<path>` from its build script, which Cargo passes on to the build
+/// scripts of its dependents as a `DEP_<links>_DLL` variable. Packaged metadata must be
+/// listed in `WINDOWS_WINMD_PATH` instead, for example in the `[env]` table of
+/// `.cargo/config.toml`, since the metadata is read when the build script is compiled and
+/// Cargo only sets `DEP_<links>_*` variables when it runs. The build script warns about
+/// metadata printed as `cargo:winmd=<path>` by a dependency that isn't listed there.
 #[proc_macro]
 pub fn build(stream: TokenStream) -> TokenStream {
     let build = parse_macro_input!(stream as BuildLimits);
 
+    // DLLs are copied from the workspace's `.windows` directory followed by the extra
+    // directories given to the macro.
+    let dll_paths: Vec<String> = winmd::workspace_windows_dir()
+        .iter()
+        .chain(&build.dll_paths)
+        .map(|path| path.to_str().expect("Invalid DLL path").to_string())
+        .collect();
+
+    // The metadata files and manifests that were read, along with their length and time of
+    // last modification, so that the build script can tell when they change.
+    let read_files: Vec<(String, u64, u128)> = build
+        .reader
+        .files
+        .iter()
+        .filter(|file| file.is_on_disk())
+        .map(|file| file.path())
+        .chain(build.manifest_paths.iter().map(|path| path.as_path()))
+        .map(|path| {
+            let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            let (len, modified) = fingerprint(&path).unwrap_or_default();
+            let path = path.to_str().expect("Invalid metadata path").to_string();
+            (path, len, modified)
+        })
+        .collect();
+
+    let read_lens: Vec<u64> = read_files.iter().map(|(_, len, _)| *len).collect();
+    let read_times: Vec<u128> = read_files.iter().map(|(_, _, time)| *time).collect();
+    let read_files: Vec<&String> = read_files.iter().map(|(path, _, _)| path).collect();
+
+    let env_winmd_paths: Vec<String> = winmd::env_winmd_paths()
+        .iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+
     let tokens = match build.to_tokens_string() {
         Ok(t) => t,
-        Err(t) => return t.into(),
+        Err(error) => return error.to_compile_error().into(),
     };
 
-    let workspace_windows_dir = winmd::workspace_windows_dir();
+    let dll_path_var = winmd::DLL_PATH_VAR;
+    let winmd_path_var = winmd::WINMD_PATH_VAR;
 
-    let mut source = workspace_windows_dir.clone();
-    source.push(ARCHITECTURE);
-    let source = source.to_str().expect("Invalid workspace architecture dir");
+    let tokens = quote! {
+        {
+            // The following must be injected into the token stream because the `OUT_DIR` and
+            // `CARGO_CFG_TARGET_ARCH` environment variables are only set when the build script
+            // runs and not when it is being compiled. The build script is also compiled for the
+            // host rather than the target so `cfg(target_arch)` can't be used.
 
-    let mut destination = workspace_windows_dir.clone();
-    destination.pop();
-    destination.push("target");
-    let destination = destination.to_str().expect("Invalid workspace target dir");
+            // Reading the environment variable that lists the metadata files makes Cargo rebuild
+            // the build script when it changes.
+            const _: ::std::option::Option<&str> = option_env!(#winmd_path_var);
 
-    let workspace_windows_dir = workspace_windows_dir
-        .to_str()
-        .expect("Invalid workspace windows dir");
+            // The build script is rerun when the metadata files or manifests change, but the
+            // bindings were generated when it was compiled so they can only be regenerated by
+            // rebuilding it.
+            fn fingerprint(path: &str) -> ::std::option::Option<(u64, u128)> {
+                let metadata = ::std::fs::metadata(path).ok()?;
+                let modified = metadata.modified().ok()?.duration_since(::std::time::UNIX_EPOCH).ok()?;
+                ::std::option::Option::Some((metadata.len(), modified.as_nanos()))
+            }
 
-    let tokens = quote! {
-        {
-            // The following must be injected into the token stream because the `OUT_DIR` and `PROFILE`
-            // environment variables are only set when the build script run and not when it is being compiled.
+            #(
+                println!("cargo:rerun-if-changed={}", #read_files);
+                if fingerprint(#read_files) != ::std::option::Option::Some((#read_lens, #read_times)) {
+                    println!(
+                        "cargo:warning=`{}` changed after the bindings were generated. Run `cargo clean -p {}` to regenerate them.",
+                        #read_files,
+                        ::std::env::var("CARGO_PKG_NAME").unwrap_or_default(),
+                    );
+                }
+            )*
 
             use ::std::io::Write;
-            let mut path = ::std::path::PathBuf::from(
+            let out_dir = ::std::path::PathBuf::from(
                 ::std::env::var("OUT_DIR").expect("No `OUT_DIR` env variable set"),
             );
 
+            let mut path = out_dir.clone();
             path.push("windows.rs");
             let mut file = ::std::fs::File::create(&path).expect("Failed to create windows.rs");
             file.write_all(#tokens.as_bytes()).expect("Could not write generated code to output file");
@@ -74,7 +208,7 @@ pub fn build(stream: TokenStream) -> TokenStream {
             cmd.arg(&path);
             let _ = cmd.output();
 
-            fn copy(source: &::std::path::PathBuf, destination: &mut ::std::path::PathBuf) {
+            fn copy(source: &::std::path::Path, destination: &mut ::std::path::PathBuf) {
                 if let ::std::result::Result::Ok(files) = ::std::fs::read_dir(source) {
                     for file in files.filter_map(|file| file.ok())  {
                         if let ::std::result::Result::Ok(file_type) = file.file_type() {
@@ -91,31 +225,70 @@ pub fn build(stream: TokenStream) -> TokenStream {
                 }
             }
 
-            fn copy_to_profile(source: &::std::path::PathBuf, destination: &::std::path::PathBuf, profile: &str) {
-                if let ::std::result::Result::Ok(files) = ::std::fs::read_dir(destination) {
-                    for file in files.filter_map(|file| file.ok())  {
-                        if let ::std::result::Result::Ok(file_type) = file.file_type() {
-                            if file_type.is_dir() {
-                                let mut path = file.path();
-                                if let ::std::option::Option::Some(filename) = path.file_name() {
-                                    if filename == profile {
-                                        copy(source, &mut path);
-                                    } else {
-                                        copy_to_profile(source, &path, profile);
-                                    }
-                                }
-                            }
+            // Dependencies expose metadata and DLLs with `DEP_<links>_<key>` variables.
+            fn dependency_vars(key: &str) -> ::std::vec::Vec<(::std::string::String, ::std::ffi::OsString)> {
+                let suffix = ::std::format!("_{}", key);
+                let mut vars: ::std::vec::Vec<_> = ::std::env::vars_os()
+                    .filter_map(|(name, value)| {
+                        let name = name.into_string().ok()?;
+                        if name.starts_with("DEP_") && name.ends_with(&suffix) && name.len() > suffix.len() + 4 {
+                            ::std::option::Option::Some((name, value))
+                        } else {
+                            ::std::option::Option::None
                         }
+                    })
+                    .collect();
+                vars.sort();
+                vars
+            }
+
+            let env_winmd_paths: &[&str] = &[#(#env_winmd_paths),*];
+            for (name, value) in dependency_vars("WINMD") {
+                for path in ::std::env::split_paths(&value) {
+                    if !env_winmd_paths.iter().any(|listed| ::std::path::Path::new(listed) == path) {
+                        println!(
+                            "cargo:warning=Metadata in `{}` was not available when the bindings were generated. Add `{}` to `{}` to use it.",
+                            name,
+                            path.display(),
+                            #winmd_path_var,
+                        );
                     }
                 }
             }
 
-            if ::std::path::PathBuf::from(#workspace_windows_dir).exists() {
-                println!("cargo:rerun-if-changed={}", #workspace_windows_dir);
-                let source = ::std::path::PathBuf::from(#source);
-                let destination = ::std::path::PathBuf::from(#destination);
-                let profile = ::std::env::var("PROFILE").expect("No `PROFILE` env variable set");
-                copy_to_profile(&source, &destination, &profile);
+            let architecture = match ::std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() {
+                ::std::result::Result::Ok("x86_64") => ::std::option::Option::Some("x64"),
+                ::std::result::Result::Ok("x86") => ::std::option::Option::Some("x86"),
+                ::std::result::Result::Ok("arm") => ::std::option::Option::Some("arm"),
+                ::std::result::Result::Ok("aarch64") => ::std::option::Option::Some("arm64"),
+                _ => ::std::option::Option::None,
+            };
+
+            // `OUT_DIR` is `<target>/<profile>/build/<package>/out`, or
+            // `<target>/<triple>/<profile>/build/<package>/out` when cross-compiling, and the
+            // DLLs belong next to the binaries in the profile directory.
+            let profile_dir = out_dir.ancestors().nth(3);
+
+            let mut dll_dirs: ::std::vec::Vec<::std::path::PathBuf> = ::std::vec![
+                #(::std::path::PathBuf::from(#dll_paths),)*
+            ];
+
+            println!("cargo:rerun-if-env-changed={}", #dll_path_var);
+            if let ::std::option::Option::Some(paths) = ::std::env::var_os(#dll_path_var) {
+                dll_dirs.extend(::std::env::split_paths(&paths));
+            }
+
+            for (_, value) in dependency_vars("DLL") {
+                dll_dirs.extend(::std::env::split_paths(&value));
+            }
+
+            if let (::std::option::Option::Some(architecture), ::std::option::Option::Some(profile_dir)) = (architecture, profile_dir) {
+                for dir in dll_dirs {
+                    if dir.exists() {
+                        println!("cargo:rerun-if-changed={}", dir.display());
+                        copy(&dir.join(architecture), &mut profile_dir.to_path_buf());
+                    }
+                }
             }
         }
     };
@@ -123,6 +296,17 @@ pub fn build(stream: TokenStream) -> TokenStream {
     tokens.into()
 }
 
+/// The length and time of last modification of a file, in nanoseconds since the Unix epoch
+fn fingerprint(path: &std::path::Path) -> Option<(u64, u128)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    Some((metadata.len(), modified.as_nanos()))
+}
+
 /// Rust structs can use the `implement` macro to implement entire WinRT classes or
 /// any combination of existing COM and WinRT interfaces.
 ///
@@ -133,24 +317,3 @@ pub fn build(stream: TokenStream) -> TokenStream {
 pub fn implement(attribute: TokenStream, input: TokenStream) -> TokenStream {
     implement::gen(attribute, input)
 }
-
-// Snake <-> camel casing is lossy so we go for character but not case conversion
-// and deal with casing once we have an index of namespaces to compare against.
-pub(crate) fn namespace_literal_to_rough_namespace(namespace: &str) -> String {
-    let mut result = String::with_capacity(namespace.len());
-    for c in namespace.chars() {
-        if c != '"' && c != '_' {
-            result.extend(c.to_lowercase());
-        }
-    }
-    result
-}
-
-#[cfg(target_arch = "x86_64")]
-const ARCHITECTURE: &str = "x64";
-#[cfg(target_arch = "x86")]
-const ARCHITECTURE: &str = "x86";
-#[cfg(target_arch = "arm")]
-const ARCHITECTURE: &str = "arm";
-#[cfg(target_arch = "aarch64")]
-const ARCHITECTURE: &str = "arm64";